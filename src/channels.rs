@@ -1,16 +1,131 @@
 use crate::prelude::*;
 use crate::eg4::inverter::ChannelData;
+use crate::coordinator::commands::rate_limiter::ReadRateLimiter;
+use crate::coordinator::dispatcher::Dispatcher;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Identifies one of `Channels`' broadcast channels, for lag accounting in [`ChannelStats`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ChannelKind {
+    FromInverter,
+    ToInverter,
+    FromMqtt,
+    ToMqtt,
+    ToCoordinator,
+    FromCoordinator,
+    ToInflux,
+    ToDatabase,
+    ReadRegisterCache,
+    ToRegisterCache,
+}
+
+/// Per-channel capacity for the broadcast channels backing [`Channels`].
+///
+/// Defaults match the previous hard-coded `2048`; override individual fields for
+/// high-frequency inverters or slow downstream sinks that need more headroom before a
+/// lagging subscriber starts dropping messages.
+#[derive(Debug, Clone)]
+pub struct ChannelConfig {
+    pub from_inverter: usize,
+    pub to_inverter: usize,
+    pub from_mqtt: usize,
+    pub to_mqtt: usize,
+    pub to_coordinator: usize,
+    pub from_coordinator: usize,
+    pub to_influx: usize,
+    pub to_database: usize,
+    pub read_register_cache: usize,
+    pub to_register_cache: usize,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        let capacity = 2048;
+        Self {
+            from_inverter: capacity,
+            to_inverter: capacity,
+            from_mqtt: capacity,
+            to_mqtt: capacity,
+            to_coordinator: capacity,
+            from_coordinator: capacity,
+            to_influx: capacity,
+            to_database: capacity,
+            read_register_cache: capacity,
+            to_register_cache: capacity,
+        }
+    }
+}
+
+/// Tracks messages lost to slow subscribers across all of `Channels`' broadcast
+/// channels, so a lagging MQTT/InfluxDB/database consumer shows up as a counter instead
+/// of silently missing data.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelStats {
+    dropped: Arc<Mutex<HashMap<ChannelKind, u64>>>,
+}
+
+impl ChannelStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record_lagged(&self, channel: ChannelKind, skipped: u64) {
+        *self.dropped.lock().unwrap().entry(channel).or_insert(0) += skipped;
+    }
+
+    /// Total messages dropped on `channel` because a subscriber fell behind.
+    pub fn dropped(&self, channel: ChannelKind) -> u64 {
+        *self.dropped.lock().unwrap().get(&channel).unwrap_or(&0)
+    }
+}
+
+/// Receives from `receiver`, transparently handling `RecvError::Lagged` by recording it
+/// against `channel` in `stats`, logging a warning, and retrying instead of returning an
+/// error that the caller would need to special-case.
+pub async fn recv_tracked<T: Clone>(
+    receiver: &mut broadcast::Receiver<T>,
+    stats: &ChannelStats,
+    channel: ChannelKind,
+) -> std::result::Result<T, broadcast::error::RecvError> {
+    loop {
+        match receiver.recv().await {
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                stats.record_lagged(channel, skipped);
+                warn!("{:?} channel lagged, dropped {} message(s)", channel, skipped);
+            }
+            other => return other,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Channels {
     pub from_inverter: broadcast::Sender<ChannelData>,
     pub to_inverter: broadcast::Sender<ChannelData>,
+    /// Correlates requests sent via `to_coordinator`/`to_inverter` to their replies on
+    /// `from_inverter` with per-request oneshot channels, so commands no longer each
+    /// subscribe to and filter the whole `from_inverter` broadcast.
+    pub dispatcher: Dispatcher,
     pub from_mqtt: broadcast::Sender<crate::mqtt::ChannelData>,
     pub to_mqtt: broadcast::Sender<crate::mqtt::ChannelData>,
+    /// Commands bound for the coordinator's main loop (packets to send,
+    /// shutdown) — sent by command runners and the scheduler.
+    pub to_coordinator: broadcast::Sender<crate::coordinator::ChannelData>,
+    /// Coordinator-originated broadcasts (currently just shutdown) consumed
+    /// by subsystems like the scheduler that need to exit their own loop.
+    pub from_coordinator: broadcast::Sender<crate::coordinator::ChannelData>,
     pub to_influx: broadcast::Sender<crate::influx::ChannelData>,
     pub to_database: broadcast::Sender<database::ChannelData>,
     pub read_register_cache: broadcast::Sender<register_cache::ChannelData>,
     pub to_register_cache: broadcast::Sender<register_cache::ChannelData>,
+    /// Dropped-message counters for every channel above, kept up to date by
+    /// [`recv_tracked`]. Shared (not rebuilt) across clones of `Channels`.
+    pub stats: ChannelStats,
+    /// Per-inverter read-word budget consulted by commands (e.g. `ReadInputs`) before
+    /// sending a request over `to_inverter`, so one producer can't flood the link.
+    pub read_rate_limiter: ReadRateLimiter,
 }
 
 impl Default for Channels {
@@ -21,19 +136,50 @@ impl Default for Channels {
 
 impl Channels {
     pub fn new() -> Self {
+        Self::with_config(ChannelConfig::default())
+    }
+
+    pub fn with_config(config: ChannelConfig) -> Self {
         Self {
-            from_inverter: Self::channel(),
-            to_inverter: Self::channel(),
-            from_mqtt: Self::channel(),
-            to_mqtt: Self::channel(),
-            to_influx: Self::channel(),
-            to_database: Self::channel(),
-            read_register_cache: Self::channel(),
-            to_register_cache: Self::channel(),
+            from_inverter: Self::channel(config.from_inverter),
+            to_inverter: Self::channel(config.to_inverter),
+            dispatcher: Dispatcher::new(),
+            from_mqtt: Self::channel(config.from_mqtt),
+            to_mqtt: Self::channel(config.to_mqtt),
+            to_coordinator: Self::channel(config.to_coordinator),
+            from_coordinator: Self::channel(config.from_coordinator),
+            to_influx: Self::channel(config.to_influx),
+            to_database: Self::channel(config.to_database),
+            read_register_cache: Self::channel(config.read_register_cache),
+            to_register_cache: Self::channel(config.to_register_cache),
+            stats: ChannelStats::new(),
+            read_rate_limiter: ReadRateLimiter::new(),
         }
     }
 
-    fn channel<T: Clone>() -> broadcast::Sender<T> {
-        broadcast::channel(2048).0
+    fn channel<T: Clone>(capacity: usize) -> broadcast::Sender<T> {
+        broadcast::channel(capacity).0
+    }
+
+    /// Registers `packet` with `dispatcher`, sends it to the coordinator to forward to
+    /// the inverter, and awaits the matching reply — the single request/await primitive
+    /// every command runner builds on, instead of each one repeating its own
+    /// register-then-send-then-wait boilerplate. Registers before sending so a reply
+    /// that arrives in the gap between the two still completes this call.
+    pub async fn send_and_wait(
+        &self,
+        packet: crate::eg4::packet::Packet,
+        timeout: std::time::Duration,
+    ) -> Result<crate::eg4::packet::Packet> {
+        let pending = self.dispatcher.register(&packet)?;
+
+        if let Err(e) = self
+            .to_coordinator
+            .send(crate::coordinator::ChannelData::SendPacket(packet.clone()))
+        {
+            bail!("send_and_wait: failed to send packet to coordinator: {}", e);
+        }
+
+        pending.wait(timeout).await
     }
 }
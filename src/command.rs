@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use thiserror::Error;
 
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -24,6 +25,13 @@ pub enum Command {
     AcChargeRate(config::Inverter, u16),
     AcChargeSocLimit(config::Inverter, u16),
     DischargeCutoffSocLimit(config::Inverter, u16),
+    /// Atomically writes a list of `(register, value)` holding-register
+    /// pairs, e.g. from a `set/batch` MQTT payload.
+    SetBatch(config::Inverter, Vec<(u16, u16)>),
+    /// Atomically writes a full list of start/end windows for one
+    /// time-of-use schedule kind (`ac_charge`/`ac_first`/`charge_priority`/
+    /// `forced_discharge`), e.g. from a `set/schedule/{kind}` MQTT payload.
+    SetSchedule(config::Inverter, String, Vec<[u8; 4]>),
 }
 
 impl Command {
@@ -53,8 +61,173 @@ impl Command {
             AcChargeRate(inverter, _) => format!("{}/set/ac_charge_rate_pct", inverter.datalog().map(|s| s.to_string()).unwrap_or_default()),
             AcChargeSocLimit(inverter, _) => format!("{}/set/ac_charge_soc_limit_pct", inverter.datalog().map(|s| s.to_string()).unwrap_or_default()),
             DischargeCutoffSocLimit(inverter, _) => format!("{}/set/discharge_cutoff_soc_limit_pct", inverter.datalog().map(|s| s.to_string()).unwrap_or_default()),
+            SetBatch(inverter, _) => format!("{}/set/batch", inverter.datalog().map(|s| s.to_string()).unwrap_or_default()),
+            SetSchedule(inverter, kind, _) => format!("{}/set/schedule/{}", inverter.datalog().map(|s| s.to_string()).unwrap_or_default(), kind),
         };
 
         format!("result/{}", rest)
     }
+
+    /// Variant name for the structured result published to
+    /// `to_result_topic()` - see `CommandResult::to_outcome_json`.
+    pub fn name(&self) -> &'static str {
+        use Command::*;
+
+        match self {
+            ReadInputs(..) => "read_inputs",
+            ReadInput(..) => "read_input",
+            ReadHold(..) => "read_hold",
+            ReadParam(..) => "read_param",
+            ReadAcChargeTime(..) => "read_ac_charge_time",
+            ReadAcFirstTime(..) => "read_ac_first_time",
+            ReadChargePriorityTime(..) => "read_charge_priority_time",
+            ReadForcedDischargeTime(..) => "read_forced_discharge_time",
+            SetHold(..) => "set_hold",
+            WriteParam(..) => "write_param",
+            SetAcChargeTime(..) => "set_ac_charge_time",
+            SetAcFirstTime(..) => "set_ac_first_time",
+            SetChargePriorityTime(..) => "set_charge_priority_time",
+            SetForcedDischargeTime(..) => "set_forced_discharge_time",
+            ChargeRate(..) => "charge_rate",
+            DischargeRate(..) => "discharge_rate",
+            AcCharge(..) => "ac_charge",
+            ChargePriority(..) => "charge_priority",
+            ForcedDischarge(..) => "forced_discharge",
+            AcChargeRate(..) => "ac_charge_rate",
+            AcChargeSocLimit(..) => "ac_charge_soc_limit",
+            DischargeCutoffSocLimit(..) => "discharge_cutoff_soc_limit",
+            SetBatch(..) => "set_batch",
+            SetSchedule(..) => "set_schedule",
+        }
+    }
+
+    /// The single register this command targets, echoed on its structured
+    /// result. `None` for the name-addressed percent/bool setters and for
+    /// `SetBatch`/`SetSchedule`, which each touch more than one register.
+    pub fn register(&self) -> Option<u16> {
+        use Command::*;
+
+        match self {
+            ReadInput(_, r, _) | ReadHold(_, r, _) | ReadParam(_, r) | SetHold(_, r, _) | WriteParam(_, r, _) => {
+                Some(*r)
+            }
+            _ => None,
+        }
+    }
+
+    /// The value this command reads or writes, echoed on its structured
+    /// result so a caller doesn't have to remember what it asked for.
+    pub fn value(&self) -> Option<serde_json::Value> {
+        use Command::*;
+
+        match self {
+            ReadInputs(_, count) | ReadInput(_, _, count) | ReadHold(_, _, count) => Some(serde_json::json!(count)),
+            SetHold(_, _, v) | WriteParam(_, _, v) => Some(serde_json::json!(v)),
+            ChargeRate(_, v)
+            | DischargeRate(_, v)
+            | AcChargeRate(_, v)
+            | AcChargeSocLimit(_, v)
+            | DischargeCutoffSocLimit(_, v) => Some(serde_json::json!(v)),
+            AcCharge(_, v) | ChargePriority(_, v) | ForcedDischarge(_, v) => Some(serde_json::json!(v)),
+            _ => None,
+        }
+    }
+}
+
+/// Structured, machine-readable outcome of running a [`Command`], published to
+/// `response/<request_id>` when the inbound MQTT message carried one (see
+/// `mqtt::Message::request_id`) so a scripted caller can branch on `code`
+/// instead of scraping log lines or guessing from the legacy `result/...`
+/// topic's bare `"FAIL"` payload.
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum CommandResult {
+    #[error("ok")]
+    Ok,
+    #[error("timed out waiting for a reply")]
+    Timeout,
+    #[error("inverter reported modbus error code {0}")]
+    ModbusError(u8),
+    #[error("reply serial number did not match the target inverter")]
+    SerialMismatch,
+    #[error("invalid register: {0}")]
+    InvalidRegister(String),
+    #[error("{0}")]
+    Error(String),
+}
+
+impl CommandResult {
+    fn code(&self) -> &'static str {
+        match self {
+            CommandResult::Ok => "ok",
+            CommandResult::Timeout => "timeout",
+            CommandResult::ModbusError(_) => "modbus_error",
+            CommandResult::SerialMismatch => "serial_mismatch",
+            CommandResult::InvalidRegister(_) => "invalid_register",
+            CommandResult::Error(_) => "error",
+        }
+    }
+
+    /// Classifies a [`process_command`](crate::coordinator::Coordinator::process_command)
+    /// failure into a [`CommandResult`] variant, so far as the error's message and
+    /// downcast chain let us tell one failure mode from another; anything we can't
+    /// recognize falls back to `Error` with the original message preserved.
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        if let Some(reg_err) = err.downcast_ref::<crate::coordinator::commands::validation::RegisterError>() {
+            return CommandResult::InvalidRegister(reg_err.to_string());
+        }
+
+        let message = err.to_string();
+        if message.contains("timed out") {
+            CommandResult::Timeout
+        } else if message.to_ascii_lowercase().contains("serial mismatch") {
+            CommandResult::SerialMismatch
+        } else {
+            CommandResult::Error(message)
+        }
+    }
+
+    /// Renders as the `{"code":...,"message":...}` payload published to
+    /// `response/<request_id>`.
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(serde::Serialize)]
+        struct Payload<'a> {
+            code: &'a str,
+            message: String,
+        }
+
+        Ok(serde_json::to_string(&Payload {
+            code: self.code(),
+            message: self.to_string(),
+        })?)
+    }
+
+    /// Renders the full structured acknowledgement published to every
+    /// command's `to_result_topic()` - `status` (`"ok"`/`"error"`), the
+    /// command name and its target register/value echoed back, an `error`
+    /// message when it failed, and a UTC timestamp. Published for every
+    /// `Command`, not just ones an MQTT caller tagged with a request id -
+    /// see `Coordinator::process_message`.
+    pub fn to_outcome_json(&self, command: &Command) -> Result<String> {
+        #[derive(serde::Serialize)]
+        struct Outcome<'a> {
+            status: &'a str,
+            command: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            register: Option<u16>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value: Option<serde_json::Value>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<String>,
+            time: i64,
+        }
+
+        Ok(serde_json::to_string(&Outcome {
+            status: if matches!(self, CommandResult::Ok) { "ok" } else { "error" },
+            command: command.name(),
+            register: command.register(),
+            value: command.value(),
+            error: (!matches!(self, CommandResult::Ok)).then(|| self.to_string()),
+            time: Utils::utc().timestamp(),
+        })?)
+    }
 }
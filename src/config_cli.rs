@@ -0,0 +1,167 @@
+use crate::prelude::*;
+
+use std::io::{self, BufRead, Write};
+
+/// Parses and validates `file` via the normal `Config::new` load path - the
+/// same checks the bridge itself runs at startup - returning the precise
+/// error on failure instead of a generic "didn't start" message.
+pub fn validate(file: &str) -> Result<()> {
+    Config::new(file.to_string())?;
+    Ok(())
+}
+
+/// A fully-commented reference config covering every field and its
+/// built-in default, for `config print-default`. Kept as a hand-written
+/// template (rather than derived from `Config`'s `default_*` functions)
+/// since a `serde`-round-tripped config can't carry field comments.
+pub fn print_default() {
+    println!(
+        r#"# eg4-bridge reference configuration. Every field below shows its
+# built-in default - uncomment and edit only the ones you need to change.
+
+inverters:
+  - enabled: true
+    host: "192.168.1.100"
+    port: 8000
+    serial: ""       # inverter serial number, e.g. "BA12345678"
+    datalog: ""      # datalogger serial number, e.g. "1234567890"
+    # read_timeout: 900
+    # use_tcp_nodelay: true
+    # register_block_size: 40
+    # delay_ms: 1000
+    # read_only: false
+    # write_verify: false
+    # reply_timeout_secs: 30
+    # timezone: "Europe/London"
+    # error_storm_threshold: 5
+    # error_storm_window_secs: 60
+    # reset_cooldown_secs: 30
+
+mqtt:
+  enabled: true
+  host: "localhost"
+  port: 1883
+  # username: "user"
+  # password: "env:EG4_MQTT_PASSWORD"   # or "file:/run/secrets/mqtt_pw"
+  namespace: "lxp"
+  homeassistant:
+    enabled: false
+    prefix: "homeassistant"
+  # protocol_version: 4
+  # qos_commands: 1
+  # qos_input: 0
+  # qos_state: 1
+  # qos_discovery: 1
+
+influx:
+  enabled: false
+  url: "http://localhost:8086"
+  # username: "user"
+  # password: "env:EG4_INFLUX_PASSWORD"
+  database: "eg4"
+
+databases: []
+
+scheduler:
+  enabled: true
+  # timesync_cron: "0 */5 * * * *"
+  register_groups: []
+
+loglevel: "debug"
+read_only: false
+homeassistant_enabled: false
+strict_data_check: false
+# register_file: "registers.json"
+show_unknown: false
+watch_config: false
+# telemetry_interval_secs: 60
+"#
+    );
+}
+
+fn prompt(stdin: &mut impl BufRead, question: &str, default: &str) -> Result<String> {
+    print!("{} [{}]: ", question, default);
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    stdin.read_line(&mut line)?;
+    let answer = line.trim();
+
+    Ok(if answer.is_empty() { default.to_string() } else { answer.to_string() })
+}
+
+/// Interactively prompts for MQTT/Influx settings and one or more
+/// inverters, then writes a valid YAML config to `output`. Runs
+/// `Config::new` against the written file before returning, so a mistake
+/// made along the way is caught immediately instead of surfacing on the
+/// next real startup.
+pub fn run_wizard(output: &str) -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+
+    println!("eg4-bridge config wizard - press Enter to accept the default shown in [brackets]");
+
+    let mqtt_host = prompt(&mut stdin, "MQTT broker host", "localhost")?;
+    let mqtt_port = prompt(&mut stdin, "MQTT broker port", "1883")?;
+    let mqtt_username = prompt(&mut stdin, "MQTT username (blank for none)", "")?;
+    let mqtt_password = prompt(&mut stdin, "MQTT password (blank for none)", "")?;
+
+    let influx_enabled = prompt(&mut stdin, "Enable InfluxDB? (y/n)", "n")?;
+    let influx_enabled = influx_enabled.eq_ignore_ascii_case("y");
+    let influx_url = if influx_enabled {
+        prompt(&mut stdin, "InfluxDB URL", "http://localhost:8086")?
+    } else {
+        "http://localhost:8086".to_string()
+    };
+    let influx_database = if influx_enabled {
+        prompt(&mut stdin, "InfluxDB database", "eg4")?
+    } else {
+        "eg4".to_string()
+    };
+
+    let mut inverters = Vec::new();
+    loop {
+        let host = prompt(&mut stdin, "Inverter host", "192.168.1.100")?;
+        let port = prompt(&mut stdin, "Inverter port", "8000")?;
+        let serial = prompt(&mut stdin, "Inverter serial number", "")?;
+        let datalog = prompt(&mut stdin, "Inverter datalog serial number", "")?;
+        inverters.push((host, port, serial, datalog));
+
+        let more = prompt(&mut stdin, "Add another inverter? (y/n)", "n")?;
+        if !more.eq_ignore_ascii_case("y") {
+            break;
+        }
+    }
+
+    let mut yaml = String::new();
+    yaml.push_str("inverters:\n");
+    for (host, port, serial, datalog) in &inverters {
+        yaml.push_str(&format!("  - enabled: true\n    host: \"{}\"\n    port: {}\n    serial: \"{}\"\n    datalog: \"{}\"\n", host, port, serial, datalog));
+    }
+
+    yaml.push_str(&format!(
+        "\nmqtt:\n  enabled: true\n  host: \"{}\"\n  port: {}\n",
+        mqtt_host, mqtt_port
+    ));
+    if !mqtt_username.is_empty() {
+        yaml.push_str(&format!("  username: \"{}\"\n", mqtt_username));
+    }
+    if !mqtt_password.is_empty() {
+        yaml.push_str(&format!("  password: \"{}\"\n", mqtt_password));
+    }
+
+    yaml.push_str(&format!(
+        "\ninflux:\n  enabled: {}\n  url: \"{}\"\n  database: \"{}\"\n",
+        influx_enabled, influx_url, influx_database
+    ));
+
+    yaml.push_str("\nread_only: false\n");
+
+    std::fs::write(output, &yaml)
+        .map_err(|e| anyhow!("failed to write {}: {}", output, e))?;
+
+    Config::new(output.to_string())?;
+
+    println!("Wrote a valid config to {}", output);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::prelude::*;
+
+use serde_yaml::Value;
+
+/// Walks a `Config`'s YAML representation along a dotted path such as
+/// `inverters.0.delay_ms` or `mqtt.homeassistant.enabled`, where a numeric
+/// segment indexes into a sequence and any other segment is a mapping key.
+/// Used by the MQTT `_config/<path>/set` and `.../get` topics to address any
+/// leaf of the config tree without hand-writing a getter/setter per field.
+fn walk<'a>(value: &'a Value, path: &[&str]) -> Result<&'a Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Ok(value);
+    };
+
+    let next = if let Ok(index) = segment.parse::<usize>() {
+        value
+            .as_sequence()
+            .and_then(|seq| seq.get(index))
+            .ok_or_else(|| anyhow!("no element at index {} (path segment {:?})", index, segment))?
+    } else {
+        value
+            .as_mapping()
+            .and_then(|map| map.get(&Value::String(segment.to_string())))
+            .ok_or_else(|| anyhow!("no field named {:?}", segment))?
+    };
+
+    walk(next, rest)
+}
+
+fn walk_mut<'a>(value: &'a mut Value, path: &[&str]) -> Result<&'a mut Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Ok(value);
+    };
+
+    let next = if let Ok(index) = segment.parse::<usize>() {
+        value
+            .as_sequence_mut()
+            .and_then(|seq| seq.get_mut(index))
+            .ok_or_else(|| anyhow!("no element at index {} (path segment {:?})", index, segment))?
+    } else {
+        value
+            .as_mapping_mut()
+            .and_then(|map| map.get_mut(&Value::String(segment.to_string())))
+            .ok_or_else(|| anyhow!("no field named {:?}", segment))?
+    };
+
+    walk_mut(next, rest)
+}
+
+/// Parses an MQTT payload into whichever `serde_yaml::Value` scalar it
+/// looks like - bool, then integer, then float, falling back to a plain
+/// string - so `"true"`/`"1000"`/`"example.com"` all round-trip sensibly
+/// without the caller needing to know the target field's type up front.
+fn parse_leaf(raw: &str) -> Value {
+    if let Ok(b) = raw.parse::<bool>() {
+        return Value::Bool(b);
+    }
+    if let Ok(i) = raw.parse::<i64>() {
+        return Value::Number(i.into());
+    }
+    if let Ok(f) = raw.parse::<f64>() {
+        return Value::Number(f.into());
+    }
+    Value::String(raw.to_string())
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('.').filter(|s| !s.is_empty()).collect()
+}
+
+/// Reads the value at `path` in `config` and renders it back as a trimmed
+/// YAML scalar/block, suitable for publishing on a `_config/<path>/get`
+/// topic.
+pub fn get(config: &crate::config::Config, path: &str) -> Result<String> {
+    let value = serde_yaml::to_value(config)?;
+    let found = walk(&value, &segments(path))?;
+    Ok(serde_yaml::to_string(found)?.trim().to_string())
+}
+
+/// Sets `raw_value` at `path` directly on a `serde_yaml::Value` tree, with
+/// no validation or re-deserialize - the building block `set` uses, and
+/// also reused by `Config::apply_env_overrides` to overlay `EG4_*`
+/// environment variables onto a freshly-loaded config tree before it's
+/// deserialized for the first time.
+pub fn apply(value: &mut Value, path: &str, raw_value: &str) -> Result<()> {
+    let segments = segments(path);
+    if segments.is_empty() {
+        bail!("config path cannot be empty");
+    }
+
+    let leaf = walk_mut(value, &segments)?;
+    *leaf = parse_leaf(raw_value);
+    Ok(())
+}
+
+/// Applies `raw_value` at `path` in a clone of `config`'s tree, validates
+/// the result, and returns the new `Config` on success. `config` itself is
+/// left untouched so the caller can swap it in atomically only once
+/// validation passes.
+pub fn set(config: &crate::config::Config, path: &str, raw_value: &str) -> Result<crate::config::Config> {
+    let mut value = serde_yaml::to_value(config)?;
+    apply(&mut value, path, raw_value)?;
+
+    let updated: crate::config::Config = serde_yaml::from_value(value)
+        .map_err(|e| anyhow!("{} = {:?} produced an invalid config: {}", path, raw_value, e))?;
+    updated.validate()?;
+
+    Ok(updated)
+}
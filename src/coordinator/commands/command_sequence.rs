@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+use eg4::packet::{DeviceFunction, Packet, TranslatedData};
+
+use crate::coordinator::commands::validation::BLOCK_SIZE;
+use crate::coordinator::Channels;
+use crate::config;
+
+/// Which register table a [`ReadIntent`] targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadKind {
+    Hold,
+    Input,
+}
+
+/// One step of a [`CommandSequence`]: a `[register, register+count)` read against
+/// `kind`'s register table.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadIntent {
+    pub kind: ReadKind,
+    pub register: u16,
+    pub count: u16,
+}
+
+/// One pre-built wire request realizing one chunk of a recorded step, plus the
+/// register/function its reply must match.
+struct CompiledChunk {
+    packet: Packet,
+    register: u16,
+    device_function: DeviceFunction,
+}
+
+/// One recorded [`ReadIntent`], compiled into the ordered chunk requests needed to
+/// satisfy it without crossing a 40-register block boundary.
+struct CompiledStep {
+    register: u16,
+    device_function: DeviceFunction,
+    chunks: Vec<CompiledChunk>,
+}
+
+/// A pre-compiled, reusable plan for reading the same set of hold/input registers from
+/// one inverter over and over, borrowing the DMA "record once, replay cheaply" idea:
+/// [`CommandSequence::record`] resolves register ranges, chunk boundaries, and expected
+/// reply shapes a single time; [`CommandSequence::replay`] just fires the pre-built
+/// packets in order and demultiplexes replies against the precomputed match predicates,
+/// with no per-cycle planning work. Meant for high-frequency pollers (e.g. a dashboard
+/// reading the same 150 holding registers every few seconds) that would otherwise
+/// rebuild and re-validate the same request plan on every tick.
+pub struct CommandSequence {
+    channels: Channels,
+    datalog: Serial,
+    inverter_serial: Serial,
+    timeout: std::time::Duration,
+    steps: Vec<CompiledStep>,
+}
+
+impl CommandSequence {
+    /// Compiles `intents` against `inverter` into a reusable sequence, splitting each
+    /// intent at 40-register block boundaries up front so `replay` never has to.
+    pub fn record(channels: Channels, inverter: &config::Inverter, intents: &[ReadIntent]) -> Result<Self> {
+        let datalog = inverter.datalog().ok_or_else(|| anyhow!("command_sequence: datalog must be set to record a sequence"))?;
+        let inverter_serial = inverter.serial().ok_or_else(|| anyhow!("command_sequence: serial must be set to record a sequence"))?;
+        let timeout = std::time::Duration::from_secs(inverter.reply_timeout_secs());
+
+        let mut steps = Vec::with_capacity(intents.len());
+        for intent in intents {
+            let device_function = match intent.kind {
+                ReadKind::Hold => DeviceFunction::ReadHold,
+                ReadKind::Input => DeviceFunction::ReadInput,
+            };
+
+            let mut chunks = Vec::new();
+            let mut register = intent.register;
+            let mut remaining = intent.count;
+
+            while remaining > 0 {
+                let remaining_in_block = BLOCK_SIZE - (register % BLOCK_SIZE);
+                let seg = remaining.min(remaining_in_block);
+
+                chunks.push(CompiledChunk {
+                    packet: Packet::TranslatedData(TranslatedData {
+                        datalog,
+                        device_function,
+                        inverter: inverter_serial,
+                        register,
+                        values: vec![seg as u8, 0],
+                        checksum_valid: true,
+                    }),
+                    register,
+                    device_function,
+                });
+
+                register = register.checked_add(seg).ok_or_else(|| anyhow!(
+                    "command_sequence: register overflow while compiling intent starting at register {}",
+                    intent.register
+                ))?;
+                remaining -= seg;
+            }
+
+            steps.push(CompiledStep {
+                register: intent.register,
+                device_function,
+                chunks,
+            });
+        }
+
+        Ok(Self {
+            channels,
+            datalog,
+            inverter_serial,
+            timeout,
+            steps,
+        })
+    }
+
+    /// Replays the recorded sequence against `inverter`, returning one merged reply
+    /// packet per recorded step, in order. Fails fast, without sending anything, if
+    /// `inverter`'s datalog/serial no longer match what was recorded — a stale compiled
+    /// plan talking to the wrong device is worse than silently re-recording.
+    pub async fn replay(&self, inverter: &config::Inverter) -> Result<Vec<Packet>> {
+        if inverter.datalog() != Some(self.datalog) || inverter.serial() != Some(self.inverter_serial) {
+            bail!(
+                "command_sequence: inverter identity changed since recording (datalog {:?} -> {:?}, serial {:?} -> {:?})",
+                self.datalog, inverter.datalog(), self.inverter_serial, inverter.serial()
+            );
+        }
+
+        let mut results = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut values = Vec::new();
+            for chunk in &step.chunks {
+                let reply = self.channels.send_and_wait(chunk.packet.clone(), self.timeout).await?;
+                match reply {
+                    Packet::TranslatedData(td) if td.register == chunk.register && td.device_function == chunk.device_function => {
+                        values.extend(td.values)
+                    }
+                    Packet::TranslatedData(td) => bail!(
+                        "command_sequence: reply mismatch for register {}: expected function {:?}, got register {} function {:?}",
+                        chunk.register, chunk.device_function, td.register, td.device_function
+                    ),
+                    _ => bail!("command_sequence: unexpected reply packet for register {}", chunk.register),
+                }
+            }
+
+            results.push(Packet::TranslatedData(TranslatedData {
+                datalog: self.datalog,
+                device_function: step.device_function,
+                inverter: self.inverter_serial,
+                register: step.register,
+                values,
+                checksum_valid: true,
+            }));
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,149 @@
+use log::warn;
+
+use crate::prelude::*;
+
+use crate::coordinator::commands::validation::BLOCK_SIZE;
+use crate::coordinator::commands::{read_hold::ReadHold, read_param::ReadParam, set_hold::SetHold, write_param::WriteParam};
+use crate::coordinator::Channels;
+use crate::config;
+
+/// One protocol-max-sized piece of a [`InverterClient::read_range`] scan, in the order it
+/// was read. `register` is this chunk's own starting register, not the scan's. `values` are
+/// the raw undecoded bytes straight off `TranslatedData`, same representation `ReadHold`
+/// and `ReadInputs` already use — decode with [`TranslatedData::pairs`]-style chunking if
+/// you need `u16`s.
+#[derive(Debug, Clone)]
+pub struct RegisterChunk {
+    pub register: u16,
+    pub values: Vec<u8>,
+}
+
+/// A handle callers outside the coordinator (a dashboard, a CLI tool, a test) can use to
+/// issue commands against one inverter without going through the scheduler.
+///
+/// Each method here builds the same packet the equivalent command runner does, registers
+/// it with [`Channels::dispatcher`], and awaits only its own oneshot reply. Because
+/// registration happens per call rather than against a single shared subscription, any
+/// number of `InverterClient`s (or calls on the same one) can have reads and writes for
+/// *different* registers in flight on the same inverter at once without one call's reply
+/// being misdelivered to, or silently dropped by, another's filter — dropping the returned
+/// future before it resolves cancels that one call's wait without affecting any other in
+/// flight.
+///
+/// Two calls for the *same* register (datalog + device function + register) can't be in
+/// flight at once, though: the reply frame carries no per-call correlation id, so
+/// `Dispatcher::register` rejects the second registration outright rather than silently
+/// replacing the first's pending entry. A second concurrent call on the same register
+/// fails fast with an "already in flight" error instead of either being misdelivered the
+/// other's reply.
+#[derive(Clone)]
+pub struct InverterClient {
+    channels: Channels,
+    inverter: config::Inverter,
+}
+
+impl InverterClient {
+    pub fn new(channels: Channels, inverter: config::Inverter) -> Self {
+        Self { channels, inverter }
+    }
+
+    pub async fn read_hold<U>(&self, register: U, count: u16) -> Result<Packet>
+    where
+        U: Into<u16>,
+    {
+        ReadHold::new(self.channels.clone(), self.inverter.clone(), register, count).run().await
+    }
+
+    pub async fn read_param<U>(&self, register: U) -> Result<Packet>
+    where
+        U: Into<u16>,
+    {
+        ReadParam::new(self.channels.clone(), self.inverter.clone(), register).run().await
+    }
+
+    pub async fn write_param<U>(&self, register: U, value: u16) -> Result<Packet>
+    where
+        U: Into<u16>,
+    {
+        WriteParam::new(self.channels.clone(), self.inverter.clone(), register, value).run().await
+    }
+
+    pub async fn set_hold<U>(&self, register: U, value: u16) -> Result<Packet>
+    where
+        U: Into<u16>,
+    {
+        SetHold::new(self.channels.clone(), self.inverter.clone(), register, value).run().await
+    }
+
+    /// Streams holding registers `start..end` as an ordered sequence of [`RegisterChunk`]s,
+    /// instead of making the caller loop over `read_hold` and wait for the whole range.
+    ///
+    /// Internally this is the same block-sized `ReadHold` chunking `ReadInputs::run` does
+    /// for input registers, issued one chunk at a time (respecting `inverter.delay_ms()`
+    /// between them) and retried up to `MAX_ATTEMPTS` times on timeout before the stream
+    /// gives up and closes. The returned channel has capacity 1, so a slow consumer applies
+    /// backpressure to the scan rather than this method buffering the whole range in memory;
+    /// dropping the receiver stops the scan after its current in-flight chunk.
+    pub fn read_range(&self, start: u16, end: u16) -> tokio::sync::mpsc::Receiver<Result<RegisterChunk>> {
+        const MAX_ATTEMPTS: u32 = 3;
+        const RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let client = self.clone();
+
+        tokio::spawn(async move {
+            let mut register = start;
+            while register < end {
+                let remaining_in_block = BLOCK_SIZE - (register % BLOCK_SIZE);
+                let count = (end - register).min(remaining_in_block);
+
+                let mut last_err = None;
+                let mut chunk = None;
+                for attempt in 1..=MAX_ATTEMPTS {
+                    match client.read_hold(register, count).await {
+                        Ok(Packet::TranslatedData(td)) => {
+                            chunk = Some(RegisterChunk { register, values: td.values });
+                            break;
+                        }
+                        Ok(_) => {
+                            last_err = Some(anyhow!("read_range: unexpected reply packet for register {}", register));
+                            break;
+                        }
+                        Err(e) => {
+                            warn!(
+                                "read_range: attempt {}/{} for register {} (count {}) failed: {}",
+                                attempt, MAX_ATTEMPTS, register, count, e
+                            );
+                            last_err = Some(e);
+                            if attempt < MAX_ATTEMPTS {
+                                tokio::time::sleep(RETRY_DELAY).await;
+                            }
+                        }
+                    }
+                }
+
+                let sent = match chunk {
+                    Some(chunk) => tx.send(Ok(chunk)).await,
+                    None => {
+                        let err = last_err.expect("loop always runs at least once, setting last_err on every iteration");
+                        let _ = tx.send(Err(err)).await;
+                        break;
+                    }
+                };
+                if sent.is_err() {
+                    // Receiver dropped — caller is no longer interested, stop scanning.
+                    break;
+                }
+
+                register += count;
+                if let Some(delay_ms) = client.inverter.delay_ms() {
+                    if delay_ms > 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                    }
+                }
+            }
+        });
+
+        rx
+    }
+}
@@ -1,6 +1,10 @@
+pub mod command_sequence;
+pub mod inverter_client;
 pub mod parse_hold;
 pub mod parse_input;
+pub mod rate_limiter;
 pub mod read_hold;
+pub mod read_hold_batch;
 pub mod read_inputs;
 pub mod read_param;
 pub mod set_ac_charge_time;
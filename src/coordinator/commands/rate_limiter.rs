@@ -0,0 +1,69 @@
+use crate::prelude::*;
+use crate::coordinator::commands::validation::RegisterError;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Budget {
+    remaining: u32,
+    window_started: Instant,
+}
+
+/// Per-inverter token budget for register-read words, modeled on Cap'n Proto's
+/// `ReadLimiter`: a counter decremented per unit of work that errors once exhausted,
+/// except the counter refills to `limit` at the start of each new window instead of
+/// staying exhausted for the life of the connection.
+///
+/// Shared (via `Clone`, like [`crate::coordinator::dispatcher::Dispatcher`]) across every
+/// command reading from a given inverter, so a large auto-split `ReadInputs` or a flood
+/// of MQTT-triggered reads can't saturate a serial-constrained link.
+#[derive(Clone, Default)]
+pub struct ReadRateLimiter {
+    budgets: Arc<Mutex<HashMap<Serial, Budget>>>,
+}
+
+impl ReadRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spends `words` from `datalog`'s budget (refilling to `limit` every `window`),
+    /// blocking for one refill if the budget is currently exhausted. Returns
+    /// `Err(RegisterError::RateLimited)` if `words` alone exceeds `limit` — waiting
+    /// would never be enough, so there's no point sleeping first.
+    pub async fn spend(&self, datalog: Serial, words: u32, limit: u32, window: Duration) -> std::result::Result<(), RegisterError> {
+        if words > limit {
+            return Err(RegisterError::RateLimited {
+                datalog,
+                requested: words,
+                available: limit,
+                retry_after_ms: 0,
+            });
+        }
+
+        loop {
+            let wait = {
+                let mut budgets = self.budgets.lock().unwrap();
+                let budget = budgets.entry(datalog).or_insert_with(|| Budget {
+                    remaining: limit,
+                    window_started: Instant::now(),
+                });
+
+                if budget.window_started.elapsed() >= window {
+                    budget.remaining = limit;
+                    budget.window_started = Instant::now();
+                }
+
+                if words <= budget.remaining {
+                    budget.remaining -= words;
+                    return Ok(());
+                }
+
+                window.saturating_sub(budget.window_started.elapsed())
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -1,19 +1,33 @@
 use crate::prelude::*;
-use log::info;
+use log::{info, warn};
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{DeviceFunction, TranslatedData, Packet},
-};
+use eg4::packet::{DeviceFunction, TranslatedData, Packet};
 
 use crate::coordinator::Channels;
 use crate::config;
 
+/// Default ceiling on registers per wire read, matching the EG4 frame's practical limit
+/// (real inverters reject or truncate single reads above this). Kept as an instance field
+/// rather than a module constant so tests can shrink it to force multi-chunk splitting
+/// without a register count large enough to be unwieldy.
+const DEFAULT_MAX_REGISTERS_PER_READ: u16 = 40;
+
+/// Default number of attempts per chunk before giving up, covering one original send
+/// plus two retries.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first retry; doubles (capped at [`MAX_RETRY_BACKOFF`]) after each
+/// subsequent failed attempt.
+const INITIAL_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+const MAX_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+
 pub struct ReadHold {
     channels: Channels,
     inverter: config::Inverter,
     register: u16,
     count: u16,
+    max_registers_per_read: u16,
+    max_attempts: u32,
 }
 
 impl ReadHold {
@@ -26,34 +40,111 @@ impl ReadHold {
             inverter,
             register: register.into(),
             count,
+            max_registers_per_read: DEFAULT_MAX_REGISTERS_PER_READ,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
         }
     }
 
+    /// Overrides the per-chunk register ceiling; exists so tests can force small chunks
+    /// without constructing an unwieldy `count`.
+    pub fn with_max_registers_per_read(mut self, max: u16) -> Self {
+        self.max_registers_per_read = max;
+        self
+    }
+
+    /// Overrides the number of attempts (original send plus retries) per chunk before
+    /// giving up; exists so tests can force a deterministic attempt count.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Reads `self.count` registers starting at `self.register`, transparently splitting
+    /// the request into sub-reads of at most `max_registers_per_read` registers (real
+    /// inverters reject or truncate reads above the frame's register-count ceiling) and
+    /// concatenating the replies in register order into one synthesized packet covering
+    /// the full requested range.
     pub async fn run(&self) -> Result<Packet> {
-        info!("Starting read hold operation for inverter {} at register {} with count {}", 
-            self.inverter.serial().expect("serial must be set for read_hold command"),
-            self.register,
-            self.count
+        let datalog = self.inverter.datalog().expect("datalog must be set for read_hold command");
+        let inverter_serial = self.inverter.serial().expect("serial must be set for read_hold command");
+
+        info!(
+            "Starting read hold operation for inverter {} at register {} with count {}",
+            inverter_serial, self.register, self.count
         );
 
-        let packet = Packet::TranslatedData(TranslatedData {
-            datalog: self.inverter.datalog().expect("datalog must be set for read_hold command"),
+        let mut register = self.register;
+        let mut remaining = self.count;
+        let mut values = Vec::with_capacity(self.count as usize);
+
+        while remaining > 0 {
+            let seg = remaining.min(self.max_registers_per_read);
+
+            let reply = self.read_chunk(datalog, inverter_serial, register, seg).await?;
+
+            match reply {
+                Packet::TranslatedData(td) if td.register == register => values.extend(td.values),
+                Packet::TranslatedData(td) => bail!(
+                    "read_hold: sub-range [{}, {}): reply register mismatch: expected {}, got {}",
+                    register, register + seg, register, td.register
+                ),
+                _ => bail!("read_hold: sub-range [{}, {}): unexpected reply packet", register, register + seg),
+            }
+
+            register = register
+                .checked_add(seg)
+                .ok_or_else(|| anyhow!("read_hold: register overflow while advancing past {}", register))?;
+            remaining -= seg;
+        }
+
+        info!("Completed read hold operation for inverter {} at register {}", inverter_serial, self.register);
+
+        Ok(Packet::TranslatedData(TranslatedData {
+            datalog,
             device_function: DeviceFunction::ReadHold,
-            inverter: self.inverter.serial().expect("serial must be set for read_hold command"),
+            inverter: inverter_serial,
             register: self.register,
-            values: vec![self.count as u8, 0],
+            values,
+            checksum_valid: true,
+        }))
+    }
+
+    /// Issues a single protocol-legal read (no more than `max_registers_per_read`
+    /// registers), retrying up to `max_attempts` times with doubling backoff if a reply
+    /// never arrives or never matches (the dispatcher only completes a reply whose
+    /// register/datalog/function/inverter match the request, so a stray reply from
+    /// another inverter just leaves this attempt to time out). Re-sends the identical
+    /// request packet on each attempt.
+    async fn read_chunk(&self, datalog: Serial, inverter_serial: Serial, register: u16, count: u16) -> Result<Packet> {
+        let packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter: inverter_serial,
+            register,
+            values: vec![count as u8, 0],
+            checksum_valid: true,
         });
 
-        let mut receiver = self.channels.from_inverter.subscribe();
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        let mut backoff = INITIAL_RETRY_BACKOFF;
+
+        for attempt in 1..=self.max_attempts {
+            info!("Sending read hold packet to coordinator for register {} count {} (attempt {}/{})", register, count, attempt, self.max_attempts);
 
-        info!("Sending read hold packet to coordinator");
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
+            match self.channels.send_and_wait(packet.clone(), timeout).await {
+                Ok(reply) => return Ok(reply),
+                Err(e) if attempt < self.max_attempts => {
+                    warn!(
+                        "read_hold: attempt {}/{} for register {} (count {}) failed: {}",
+                        attempt, self.max_attempts, register, count, e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+                }
+                Err(_) => bail!("wait_for_reply {:?} - timeout after {} attempts", packet, self.max_attempts),
+            }
         }
 
-        info!("Waiting for reply from inverter");
-        let packet = receiver.wait_for_reply(&packet).await?;
-        info!("Received reply from inverter");
-        Ok(packet)
+        unreachable!("max_attempts is always >= 1, so the loop above always returns or bails")
     }
 }
@@ -0,0 +1,133 @@
+use crate::prelude::*;
+
+use eg4::packet::Packet;
+
+use crate::coordinator::commands::read_hold::ReadHold;
+use crate::coordinator::Channels;
+use crate::config;
+
+/// One caller's read intent within a [`ReadHoldBatch`]: the `[register, register+count)`
+/// range it actually wants, to be sliced back out of whatever combined reply covers it.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadIntent {
+    pub register: u16,
+    pub count: u16,
+}
+
+/// A run of intents merged into one combined `[register, register+count)` read, plus the
+/// indices (into the original `intents` slice) it satisfies.
+struct Group {
+    register: u16,
+    count: u16,
+    members: Vec<usize>,
+}
+
+/// Coalesces multiple `ReadHold` intents against the same inverter into as few wire
+/// transactions as possible, modeled on Modbus coupler drivers that read a whole
+/// contiguous register block in one request rather than one register at a time.
+///
+/// Intents whose ranges are adjacent or overlap within `gap` registers are merged into
+/// one combined `ReadHold` spanning `[min_register, max_register+max_count)`; disjoint
+/// groups still cost one transaction each. Each caller's slice of its group's reply is
+/// handed back in the same order as `intents`.
+pub struct ReadHoldBatch {
+    channels: Channels,
+    inverter: config::Inverter,
+    gap: u16,
+}
+
+impl ReadHoldBatch {
+    pub fn new(channels: Channels, inverter: config::Inverter) -> Self {
+        Self {
+            channels,
+            inverter,
+            gap: 0,
+        }
+    }
+
+    /// Sets the maximum register gap between two intents' ranges for them to still be
+    /// merged into one request. 0 (the default) only merges intents that are adjacent or
+    /// overlapping.
+    pub fn with_gap(mut self, gap: u16) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Runs every intent in `intents`, returning one raw value slice per intent in the
+    /// same order as `intents`. Each slice is `2 * intent.count` bytes, matching
+    /// `TranslatedData::values`' two-bytes-per-register encoding.
+    pub async fn run(&self, intents: &[ReadIntent]) -> Result<Vec<Vec<u8>>> {
+        if intents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let groups = self.coalesce(intents);
+        let mut results: Vec<Vec<u8>> = vec![Vec::new(); intents.len()];
+
+        for group in groups {
+            let reply = ReadHold::new(self.channels.clone(), self.inverter.clone(), group.register, group.count)
+                .run()
+                .await
+                .map_err(|e| anyhow!(
+                    "read_hold_batch: combined read [{}, {}) failed: {}",
+                    group.register, group.register + group.count, e
+                ))?;
+
+            let values = match reply {
+                Packet::TranslatedData(td) if td.register == group.register => td.values,
+                Packet::TranslatedData(td) => bail!(
+                    "read_hold_batch: combined read [{}, {}): reply register mismatch: expected {}, got {}",
+                    group.register, group.register + group.count, group.register, td.register
+                ),
+                _ => bail!(
+                    "read_hold_batch: combined read [{}, {}): unexpected reply packet",
+                    group.register, group.register + group.count
+                ),
+            };
+
+            for idx in group.members {
+                let intent = intents[idx];
+                let start = (intent.register - group.register) as usize * 2;
+                let end = start + intent.count as usize * 2;
+                let slice = values.get(start..end).ok_or_else(|| anyhow!(
+                    "read_hold_batch: intent [{}, {}) falls outside combined reply [{}, {}) ({} bytes)",
+                    intent.register, intent.register + intent.count,
+                    group.register, group.register + group.count, values.len()
+                ))?;
+                results[idx] = slice.to_vec();
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Sorts intents by register and merges adjacent/overlapping-within-`gap` ranges into
+    /// groups, tracking which original intent indices each group satisfies.
+    fn coalesce(&self, intents: &[ReadIntent]) -> Vec<Group> {
+        let mut order: Vec<usize> = (0..intents.len()).collect();
+        order.sort_by_key(|&i| intents[i].register);
+
+        let mut groups: Vec<Group> = Vec::new();
+        for idx in order {
+            let intent = intents[idx];
+            let intent_end = intent.register + intent.count;
+
+            if let Some(last) = groups.last_mut() {
+                let last_end = last.register + last.count;
+                if intent.register <= last_end.saturating_add(self.gap) {
+                    last.count = last.count.max(intent_end - last.register);
+                    last.members.push(idx);
+                    continue;
+                }
+            }
+
+            groups.push(Group {
+                register: intent.register,
+                count: intent.count,
+                members: vec![idx],
+            });
+        }
+
+        groups
+    }
+}
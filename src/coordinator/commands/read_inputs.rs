@@ -1,11 +1,9 @@
 use crate::prelude::*;
-use log::info;
+use log::{info, warn};
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{DeviceFunction, TranslatedData},
-};
+use eg4::packet::{DeviceFunction, TranslatedData};
 
+use crate::coordinator::commands::validation::{validate_register_block_boundary, RegisterError, BLOCK_SIZE};
 use crate::coordinator::Channels;
 use crate::config;
 
@@ -29,31 +27,130 @@ impl ReadInputs {
         }
     }
 
+    /// Reads `self.count` registers starting at `self.register`, transparently splitting
+    /// the request at 40-register block boundaries (the protocol cannot read across a
+    /// block in a single call) and reassembling the replies into one merged packet whose
+    /// `register` is the original starting register and whose `values` are in order. Each
+    /// chunk is retried a bounded number of times before giving up, so one dropped reply
+    /// doesn't fail the whole range.
     pub async fn run(&self) -> Result<Packet> {
-        info!("Starting read input operation for inverter {} at register {} with count {}", 
-            self.inverter.serial().expect("serial must be set for read_inputs command"),
-            self.register,
-            self.count
+        let datalog = self.inverter.datalog().expect("datalog must be set for read_inputs command");
+        let inverter_serial = self.inverter.serial().expect("serial must be set for read_inputs command");
+
+        // A request that doesn't fit in one block needs splitting below; one that can't
+        // be represented at all (register + count overflows u16) can't be salvaged by
+        // splitting, so reject it outright instead of looping forever trying to advance.
+        match validate_register_block_boundary(self.register, self.count) {
+            Ok(()) | Err(RegisterError::CrossesBlockBoundary { .. }) | Err(RegisterError::CountExceedsBlock { .. }) => {}
+            Err(err @ RegisterError::RegisterOverflow { .. }) => bail!("read_inputs: {}", err),
+        }
+
+        info!(
+            "Starting read input operation for inverter {} at register {} with count {}",
+            inverter_serial, self.register, self.count
         );
 
-        let packet = Packet::TranslatedData(TranslatedData {
-            datalog: self.inverter.datalog().expect("datalog must be set for read_inputs command"),
+        let mut register = self.register;
+        let mut count = self.count;
+        let mut values = Vec::new();
+
+        while count > 0 {
+            let remaining_in_block = BLOCK_SIZE - (register % BLOCK_SIZE);
+            let seg = count.min(remaining_in_block);
+
+            let reply = self.read_segment_with_retry(datalog, inverter_serial, register, seg).await?;
+            match reply {
+                Packet::TranslatedData(td) if td.register == register => values.extend(td.values),
+                Packet::TranslatedData(td) => bail!(
+                    "read_inputs: reply register mismatch: expected {}, got {}",
+                    register,
+                    td.register
+                ),
+                _ => bail!("read_inputs: unexpected reply packet for register {}", register),
+            }
+
+            register = register
+                .checked_add(seg)
+                .ok_or_else(|| anyhow!("read_inputs: register overflow while advancing past {}", register))?;
+            count -= seg;
+        }
+
+        info!("Completed read input operation for inverter {} at register {}", inverter_serial, self.register);
+
+        Ok(Packet::TranslatedData(TranslatedData {
+            datalog,
             device_function: DeviceFunction::ReadInput,
-            inverter: self.inverter.serial().expect("serial must be set for read_inputs command"),
+            inverter: inverter_serial,
             register: self.register,
-            values: vec![self.count as u8, 0],
-        });
+            values,
+            checksum_valid: true,
+        }))
+    }
 
-        let mut receiver = self.channels.from_inverter.subscribe();
+    /// Retries a single chunk's read up to `MAX_ATTEMPTS` times with a short delay between
+    /// attempts, so one dropped reply on a busy bus doesn't fail the whole multi-chunk range.
+    async fn read_segment_with_retry(
+        &self,
+        datalog: Serial,
+        inverter_serial: Serial,
+        register: u16,
+        count: u16,
+    ) -> Result<Packet> {
+        const MAX_ATTEMPTS: u32 = 3;
+        const RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
 
-        info!("Sending read input packet to coordinator");
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
+        let mut last_err = None;
+        for attempt in 1..=MAX_ATTEMPTS {
+            match self.read_segment(datalog, inverter_serial, register, count).await {
+                Ok(reply) => return Ok(reply),
+                Err(e) => {
+                    warn!(
+                        "read_inputs: attempt {}/{} for register {} (count {}) failed: {}",
+                        attempt, MAX_ATTEMPTS, register, count, e
+                    );
+                    last_err = Some(e);
+                    if attempt < MAX_ATTEMPTS {
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
         }
 
-        info!("Waiting for reply from inverter");
-        let packet = receiver.wait_for_reply(&packet).await?;
-        info!("Received reply from inverter");
-        Ok(packet)
+        Err(last_err.expect("loop always runs at least once, setting last_err on every iteration"))
+    }
+
+    /// Issues a single protocol-legal read (no more than one block) and waits for its
+    /// reply via the coordinator's request/reply dispatcher, rather than subscribing to
+    /// the whole `from_inverter` broadcast and filtering out every unrelated packet.
+    async fn read_segment(
+        &self,
+        datalog: Serial,
+        inverter_serial: Serial,
+        register: u16,
+        count: u16,
+    ) -> Result<Packet> {
+        self.channels
+            .read_rate_limiter
+            .spend(
+                datalog,
+                count as u32,
+                self.inverter.read_rate_limit_words(),
+                self.inverter.read_rate_limit_window(),
+            )
+            .await
+            .map_err(|e| anyhow!("read_inputs: {}", e))?;
+
+        let packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadInput,
+            inverter: inverter_serial,
+            register,
+            values: vec![count as u8, 0],
+            checksum_valid: true,
+        });
+
+        info!("Sending read input packet to coordinator for register {} count {}", register, count);
+        let timeout = std::time::Duration::from_secs(self.inverter.command_timeout_secs());
+        self.channels.send_and_wait(packet, timeout).await
     }
 }
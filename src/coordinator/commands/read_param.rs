@@ -1,9 +1,6 @@
 use crate::prelude::*;
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{ReadParam as ReadParamPacket, Packet},
-};
+use eg4::packet::{ReadParam as ReadParamPacket, Packet};
 
 use crate::coordinator::Channels;
 use crate::config;
@@ -27,19 +24,32 @@ impl ReadParam {
     }
 
     pub async fn run(&self) -> Result<Packet> {
+        let datalog = self.inverter.datalog().expect("datalog must be set for read_param command");
+
         let packet = Packet::ReadParam(ReadParamPacket {
-            datalog: self.inverter.datalog().expect("datalog must be set for read_param command"),
+            datalog,
             register: self.register,
             values: vec![], // unused for read param
         });
 
-        let mut receiver = self.channels.from_inverter.subscribe();
-
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
+        let timeout = std::time::Duration::from_secs(self.inverter.command_timeout_secs());
+        let retries = self.inverter.command_retries();
+
+        for attempt in 0..=retries {
+            match self.channels.send_and_wait(packet.clone(), timeout).await {
+                Ok(result) => return Ok(result),
+                Err(_) => {
+                    warn!(
+                        "read_param timed out waiting for reply from datalog {} register {} (attempt {}/{})",
+                        datalog, self.register, attempt + 1, retries + 1
+                    );
+                }
+            }
         }
 
-        let packet = receiver.wait_for_reply(&packet).await?;
-        Ok(packet)
+        bail!(
+            "read_param: no reply from datalog {} register {} after {} attempt(s)",
+            datalog, self.register, retries + 1
+        );
     }
 }
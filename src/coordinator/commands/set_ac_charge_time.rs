@@ -1,23 +1,153 @@
 use crate::prelude::*;
 
-#[allow(dead_code)]
+use eg4::packet::{DeviceFunction, TranslatedData};
+
+use crate::register::RegisterParser;
+
+/// One AC-charge time-of-day window. The inverter stores each window as a
+/// `[start_hour, start_minute, end_hour, end_minute]` byte quartet across a
+/// pair of consecutive hold registers, the same layout
+/// `SetForcedDischargeTime` uses for its single window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChargeWindow {
+    pub start_hour: u8,
+    pub start_minute: u8,
+    pub end_hour: u8,
+    pub end_minute: u8,
+}
+
+impl ChargeWindow {
+    fn to_bytes(self) -> [u8; 4] {
+        [self.start_hour, self.start_minute, self.end_hour, self.end_minute]
+    }
+
+    pub fn from_bytes(values: [u8; 4]) -> Self {
+        Self {
+            start_hour: values[0],
+            start_minute: values[1],
+            end_hour: values[2],
+            end_minute: values[3],
+        }
+    }
+
+    fn start_minutes(self) -> u32 {
+        self.start_hour as u32 * 60 + self.start_minute as u32
+    }
+
+    fn end_minutes(self) -> u32 {
+        self.end_hour as u32 * 60 + self.end_minute as u32
+    }
+}
+
 pub struct SetAcChargeTime {
     channels: Channels,
     inverter: config::Inverter,
-    values: [u8; 4],
+    config: ConfigWrapper,
+    /// Each window paired with its 1-based slot number (the `num` from
+    /// `Command::SetAcChargeTime`/`set/schedule/ac_charge`), which is what
+    /// resolves to a concrete register via `ac_charge_time_{num}` below -
+    /// not the window's position in this `Vec`, so a single-window call can
+    /// target any slot without the caller padding out the others.
+    windows: Vec<(u16, ChargeWindow)>,
 }
 
 impl SetAcChargeTime {
-    pub fn new(channels: Channels, inverter: config::Inverter, values: [u8; 4]) -> Self {
+    pub fn new(channels: Channels, inverter: config::Inverter, config: ConfigWrapper, windows: Vec<(u16, ChargeWindow)>) -> Self {
         Self {
             channels,
             inverter,
-            values,
+            config,
+            windows,
         }
     }
 
     pub async fn run(&self) -> Result<()> {
-        // Implementation will be added later
+        if self.inverter.read_only() {
+            bail!(
+                "Cannot set AC charge time for inverter {} - inverter is in read-only mode",
+                self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default()
+            );
+        }
+
+        let windows: Vec<ChargeWindow> = self.windows.iter().map(|(_, w)| *w).collect();
+        Self::validate(&windows)?;
+
+        let register_file = self.config.register_file().ok_or_else(|| {
+            anyhow!("cannot resolve AC charge time registers: no register_file configured")
+        })?;
+        let parser = RegisterParser::new(&register_file)?;
+
+        let datalog = self.inverter.datalog().expect("datalog must be set for set_ac_charge_time command");
+        let serial = self.inverter.serial().expect("serial must be set for set_ac_charge_time command");
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+
+        for (num, window) in &self.windows {
+            let shortname = format!("ac_charge_time_{}", num);
+            let register = parser
+                .find_by_name(&shortname)
+                .ok_or_else(|| anyhow!("no register named '{}' in register file {}", shortname, register_file))?
+                .register_number;
+
+            let packet = Packet::TranslatedData(TranslatedData {
+                datalog,
+                device_function: DeviceFunction::WriteMulti,
+                inverter: serial,
+                register,
+                values: window.to_bytes().to_vec(),
+                checksum_valid: true,
+            });
+
+            self.channels.send_and_wait(packet, timeout).await.map_err(|e| {
+                anyhow!(
+                    "inverter {} did not confirm AC charge window {} ({}): {}",
+                    datalog, num, shortname, e
+                )
+            })?;
+        }
+
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// Validates that every window's hours/minutes are in range and that no
+    /// two windows overlap, comparing spans in minutes-since-midnight.
+    fn validate(windows: &[ChargeWindow]) -> Result<()> {
+        if windows.is_empty() {
+            bail!("at least one AC charge window is required");
+        }
+
+        for w in windows {
+            if w.start_hour > 23 {
+                bail!("invalid AC charge start_hour {} (must be 0-23)", w.start_hour);
+            }
+            if w.start_minute > 59 {
+                bail!("invalid AC charge start_minute {} (must be 0-59)", w.start_minute);
+            }
+            if w.end_hour > 23 {
+                bail!("invalid AC charge end_hour {} (must be 0-23)", w.end_hour);
+            }
+            if w.end_minute > 59 {
+                bail!("invalid AC charge end_minute {} (must be 0-59)", w.end_minute);
+            }
+            if w.start_minutes() == w.end_minutes() {
+                bail!(
+                    "invalid AC charge window: start ({:02}:{:02}) and end ({:02}:{:02}) are identical",
+                    w.start_hour, w.start_minute, w.end_hour, w.end_minute
+                );
+            }
+        }
+
+        for (i, a) in windows.iter().enumerate() {
+            for b in &windows[i + 1..] {
+                if a.start_minutes() < b.end_minutes() && b.start_minutes() < a.end_minutes() {
+                    bail!(
+                        "AC charge windows overlap: {:02}:{:02}-{:02}:{:02} and {:02}:{:02}-{:02}:{:02}",
+                        a.start_hour, a.start_minute, a.end_hour, a.end_minute,
+                        b.start_hour, b.start_minute, b.end_hour, b.end_minute
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
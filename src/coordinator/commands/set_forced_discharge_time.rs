@@ -1,5 +1,11 @@
 use crate::prelude::*;
 
+use eg4::packet::{DeviceFunction, TranslatedData};
+
+/// Holding register that packs forced-discharge slot 1 as
+/// `[start_hour, start_minute, end_hour, end_minute]`.
+const FORCED_DISCHARGE_TIME_REGISTER: u16 = 68;
+
 pub struct SetForcedDischargeTime {
     channels: Channels,
     inverter: config::Inverter,
@@ -16,7 +22,79 @@ impl SetForcedDischargeTime {
     }
 
     pub async fn run(&self) -> Result<()> {
-        // Implementation will be added later
+        if self.inverter.read_only() {
+            bail!(
+                "Cannot set forced discharge time for inverter {} - inverter is in read-only mode",
+                self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default()
+            );
+        }
+
+        Self::validate(&self.values)?;
+
+        let datalog = self.inverter.datalog().expect("datalog must be set for set_forced_discharge_time command");
+        let serial = self.inverter.serial().expect("serial must be set for set_forced_discharge_time command");
+
+        let packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::WriteMulti,
+            inverter: serial,
+            register: FORCED_DISCHARGE_TIME_REGISTER,
+            values: self.values.to_vec(),
+            checksum_valid: true,
+        });
+
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        self.channels.send_and_wait(packet, timeout).await?;
+
+        // Read the schedule back to confirm the inverter actually stored it.
+        let read_packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter: serial,
+            register: FORCED_DISCHARGE_TIME_REGISTER,
+            values: vec![2, 0], // 2 registers (4 bytes) starting at offset 0
+            checksum_valid: true,
+        });
+
+        if let Packet::TranslatedData(td) = self.channels.send_and_wait(read_packet, timeout).await? {
+            if td.values != self.values {
+                bail!(
+                    "forced discharge time mismatch on read-back for register {}: wrote {:?}, read back {:?}",
+                    FORCED_DISCHARGE_TIME_REGISTER,
+                    self.values,
+                    td.values
+                );
+            }
+        }
+
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// Validates `[start_hour, start_minute, end_hour, end_minute]`: each
+    /// hour must be 0-23, each minute 0-59, and start must differ from end
+    /// so the window isn't empty.
+    fn validate(values: &[u8; 4]) -> Result<()> {
+        let [start_hour, start_minute, end_hour, end_minute] = *values;
+
+        if start_hour > 23 {
+            bail!("invalid forced discharge start_hour {} (must be 0-23)", start_hour);
+        }
+        if start_minute > 59 {
+            bail!("invalid forced discharge start_minute {} (must be 0-59)", start_minute);
+        }
+        if end_hour > 23 {
+            bail!("invalid forced discharge end_hour {} (must be 0-23)", end_hour);
+        }
+        if end_minute > 59 {
+            bail!("invalid forced discharge end_minute {} (must be 0-59)", end_minute);
+        }
+        if start_hour == end_hour && start_minute == end_minute {
+            bail!(
+                "invalid forced discharge window: start ({:02}:{:02}) and end ({:02}:{:02}) are identical",
+                start_hour, start_minute, end_hour, end_minute
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,6 @@
 use crate::prelude::*;
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{DeviceFunction, TranslatedData},
-};
+use eg4::packet::{DeviceFunction, TranslatedData};
 
 pub struct SetHold {
     channels: Channels,
@@ -38,21 +35,17 @@ impl SetHold {
             inverter: self.inverter.serial().expect("serial must be set for set_hold command"),
             register: self.register,
             values: self.value.to_le_bytes().to_vec(),
+            checksum_valid: true,
         });
 
-        let mut receiver = self.channels.from_inverter.subscribe();
-
         // Log the packet being sent
         if let Packet::TranslatedData(td) = &packet {
-            info!("[set_hold] Sending TranslatedData packet to inverter - function: {:?}, register: {}, datalog: {}", 
+            info!("[set_hold] Sending TranslatedData packet to inverter - function: {:?}, register: {}, datalog: {}",
                 td.device_function, self.register, td.datalog);
         }
 
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
-        }
-
-        let packet = receiver.wait_for_reply(&packet).await?;
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        let packet = self.channels.send_and_wait(packet, timeout).await?;
         if packet.value() != self.value {
             bail!(
                 "failed to set register {}, got back value {} (wanted {})",
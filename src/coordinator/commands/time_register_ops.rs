@@ -0,0 +1,143 @@
+use crate::prelude::*;
+
+use eg4::packet::{DeviceFunction, TranslatedData};
+
+use crate::register::RegisterParser;
+
+/// Identifies which numbered time-of-day schedule slot a `SetTimeRegister`/
+/// `ReadTimeRegister` call targets, carrying the window/slot number straight
+/// through from the originating `Command` (e.g. `Command::SetAcChargeTime(inverter,
+/// num, values)`) to the named register lookup in `resolve_register`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    AcCharge(u16),
+    AcFirst(u16),
+    ChargePriority(u16),
+    ForcedDischarge(u16),
+}
+
+impl Action {
+    /// The `RegisterParser` shortname this action's slot resolves to, e.g.
+    /// `ac_charge_time_1` for `AcCharge(1)`.
+    fn register_name(self) -> String {
+        match self {
+            Action::AcCharge(num) => format!("ac_charge_time_{}", num),
+            Action::AcFirst(num) => format!("ac_first_time_{}", num),
+            Action::ChargePriority(num) => format!("charge_priority_time_{}", num),
+            Action::ForcedDischarge(num) => format!("forced_discharge_time_{}", num),
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Action::AcCharge(_) => "AC charge time",
+            Action::AcFirst(_) => "AC first time",
+            Action::ChargePriority(_) => "charge priority time",
+            Action::ForcedDischarge(_) => "forced discharge time",
+        }
+    }
+}
+
+/// Resolves `action`'s slot to a concrete hold register via `config`'s
+/// `register_file`, shared by `SetTimeRegister` and `ReadTimeRegister` below.
+fn resolve_register(config: &ConfigWrapper, action: Action) -> Result<u16> {
+    let register_file = config
+        .register_file()
+        .ok_or_else(|| anyhow!("cannot resolve {} register: no register_file configured", action.description()))?;
+    let parser = RegisterParser::new(&register_file)?;
+    let shortname = action.register_name();
+    Ok(parser
+        .find_by_name(&shortname)
+        .ok_or_else(|| anyhow!("no register named '{}' in register file {}", shortname, register_file))?
+        .register_number)
+}
+
+pub struct SetTimeRegister {
+    channels: Channels,
+    inverter: config::Inverter,
+    config: ConfigWrapper,
+    action: Action,
+    values: [u8; 4],
+}
+
+impl SetTimeRegister {
+    pub fn new(channels: Channels, inverter: config::Inverter, config: ConfigWrapper, action: Action, values: [u8; 4]) -> Self {
+        Self {
+            channels,
+            inverter,
+            config,
+            action,
+            values,
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        if self.inverter.read_only() {
+            bail!(
+                "Cannot set {} for inverter {} - inverter is in read-only mode",
+                self.action.description(),
+                self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default()
+            );
+        }
+
+        let datalog = self.inverter.datalog().expect("datalog must be set for set_time_register command");
+        let serial = self.inverter.serial().expect("serial must be set for set_time_register command");
+        let register = resolve_register(&self.config, self.action)?;
+
+        let packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::WriteMulti,
+            inverter: serial,
+            register,
+            values: self.values.to_vec(),
+            checksum_valid: true,
+        });
+
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        self.channels.send_and_wait(packet, timeout).await.map_err(|e| {
+            anyhow!("inverter {} did not confirm {}: {}", datalog, self.action.description(), e)
+        })?;
+
+        Ok(())
+    }
+}
+
+pub struct ReadTimeRegister {
+    channels: Channels,
+    inverter: config::Inverter,
+    config: ConfigWrapper,
+    action: Action,
+}
+
+impl ReadTimeRegister {
+    pub fn new(channels: Channels, inverter: config::Inverter, config: ConfigWrapper, action: Action) -> Self {
+        Self {
+            channels,
+            inverter,
+            config,
+            action,
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let datalog = self.inverter.datalog().expect("datalog must be set for read_time_register command");
+        let serial = self.inverter.serial().expect("serial must be set for read_time_register command");
+        let register = resolve_register(&self.config, self.action)?;
+
+        let packet = Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter: serial,
+            register,
+            values: vec![2, 0], // 2 registers (4 bytes) starting at offset 0
+            checksum_valid: true,
+        });
+
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        self.channels.send_and_wait(packet, timeout).await.map_err(|e| {
+            anyhow!("inverter {} did not reply to {} read: {}", datalog, self.action.description(), e)
+        })?;
+
+        Ok(())
+    }
+}
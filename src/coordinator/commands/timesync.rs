@@ -2,10 +2,64 @@ use crate::prelude::*;
 
 use chrono::TimeZone;
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{DeviceFunction, TranslatedData},
-};
+use eg4::packet::{DeviceFunction, TranslatedData};
+
+/// A `chrono::TimeZone` that's resolved per-inverter at runtime: either the
+/// IANA zone from `Inverter::timezone`, or the system's local zone when
+/// unset.
+enum InverterZone {
+    Named(chrono_tz::Tz),
+    Local,
+}
+
+impl InverterZone {
+    fn for_inverter(inverter: &config::Inverter) -> Self {
+        match inverter.timezone() {
+            Some(tz) => Self::Named(tz),
+            None => Self::Local,
+        }
+    }
+
+    /// Interprets `(year, month, day, hour, minute, second)` as this zone's
+    /// local wall-clock time and converts it to UTC.
+    fn local_to_utc(&self, year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            Self::Named(tz) => tz
+                .with_ymd_and_hms(year, month, day, hour, minute, second)
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+            Self::Local => chrono::Local
+                .with_ymd_and_hms(year, month, day, hour, minute, second)
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        }
+    }
+
+    /// Converts a UTC instant to this zone's local wall-clock time.
+    fn utc_to_local(&self, now: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::FixedOffset> {
+        match self {
+            Self::Named(tz) => now.with_timezone(tz).fixed_offset(),
+            Self::Local => now.with_timezone(&chrono::Local).fixed_offset(),
+        }
+    }
+}
+
+/// Per-inverter resilience state for `TimeSync`, held across scheduler
+/// ticks so the error budget and backoff persist between runs instead of
+/// resetting every time a fresh `TimeSync` is constructed.
+#[derive(Default)]
+pub struct TimeSyncState {
+    consecutive_failures: usize,
+    backoff_until: Option<std::time::Instant>,
+    /// Set once `max_errors_in_row` is exceeded; `run` then stops attempting
+    /// resyncs for this inverter instead of retrying forever.
+    gave_up: bool,
+}
+
+/// Cap on the exponential backoff applied after consecutive failures, so a
+/// persistently broken inverter link doesn't push the retry interval out to
+/// the point it might as well have given up already.
+const MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(3600);
 
 /// TimeSync handles the synchronization of time between the system and the EG4 inverter.
 /// This is important for accurate logging and scheduling of operations.
@@ -16,7 +70,7 @@ pub struct TimeSync {
 
 impl TimeSync {
     /// Creates a new TimeSync instance for a specific inverter
-    /// 
+    ///
     /// # Arguments
     /// * `channels` - Communication channels for sending/receiving packets
     /// * `inverter` - The inverter configuration to sync time with
@@ -24,17 +78,67 @@ impl TimeSync {
         Self { channels, inverter }
     }
 
-    /// Executes the time synchronization process
-    /// 
-    /// This function:
-    /// 1. Checks if time sync is allowed (not in read-only mode)
-    /// 2. Reads the current time from the inverter
-    /// 3. Compares it with the system time
-    /// 4. Updates the inverter's time if the difference is significant
-    /// 
-    /// # Returns
-    /// * `Result<()>` - Ok if successful, error if any step fails
-    pub async fn run(&self) -> Result<()> {
+    /// Runs one policy-gated time sync attempt: skips entirely once `state`
+    /// has given up or is still within its backoff/`min_resync_interval`
+    /// window, otherwise calls `attempt` and updates `state` from the
+    /// result - resetting the failure count on success, or backing off
+    /// exponentially (capped at `MAX_BACKOFF`) and, once
+    /// `max_errors_in_row` is exceeded, giving up on this inverter.
+    pub async fn run(&self, state: &mut TimeSyncState) -> Result<()> {
+        let policy = self.inverter.timesync_policy();
+        let datalog = self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default();
+
+        if state.gave_up {
+            return Ok(());
+        }
+
+        let now = std::time::Instant::now();
+        if let Some(until) = state.backoff_until {
+            if now < until {
+                return Ok(());
+            }
+        }
+
+        match self.attempt().await {
+            Ok(_) => {
+                state.consecutive_failures = 0;
+                state.backoff_until = None;
+                Ok(())
+            }
+            Err(e) => {
+                state.consecutive_failures += 1;
+                let shift = state.consecutive_failures.min(6) as u32;
+                let backoff = policy.min_resync_interval()
+                    .saturating_mul(1u32 << shift)
+                    .min(MAX_BACKOFF);
+                state.backoff_until = Some(now + backoff);
+
+                if let Some(max) = policy.max_errors_in_row() {
+                    if state.consecutive_failures >= max {
+                        state.gave_up = true;
+                        error!(
+                            "TimeSync for inverter {} giving up after {} consecutive failures: {}",
+                            datalog, state.consecutive_failures, e
+                        );
+                        return Err(e);
+                    }
+                }
+
+                warn!(
+                    "TimeSync for inverter {} failed ({} in a row, retrying in {:?}): {}",
+                    datalog, state.consecutive_failures, backoff, e
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Performs a single read/compare/maybe-correct round-trip against the
+    /// inverter, returning the measured drift (inverter clock minus system
+    /// clock, before any correction). Broadcasts the same measurement over
+    /// `channels.from_inverter` as `ChannelData::TimeSyncDrift` so it can be
+    /// logged or graphed independently of whether a correction was applied.
+    async fn attempt(&self) -> Result<chrono::Duration> {
         // Create a packet to read the current time from register 12
         // Register 12 contains the inverter's current time in BCD format
         let packet = Packet::TranslatedData(TranslatedData {
@@ -43,97 +147,88 @@ impl TimeSync {
             inverter: self.inverter.serial().expect("serial must be set for timesync command"),
             register: 12,
             values: vec![3, 0],  // Read 3 registers (6 bytes) starting at offset 0
+            checksum_valid: true,
         });
 
-        let mut receiver = self.channels.from_inverter.subscribe();
-
-        // Send the read request to the inverter
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        let datalog = self.inverter.datalog().expect("datalog must be set for timesync command");
+
+        // Send the read request to the inverter and wait for its response
+        let Packet::TranslatedData(td) = self.channels.send_and_wait(packet, timeout).await? else {
+            bail!("unexpected reply packet type reading inverter {} clock", datalog);
+        };
+
+        // Extract time components from the response
+        // Values are in BCD format: [year, month, day, hour, minute, second]
+        let year = td.values[0] as u32;
+        let month = td.values[1] as u32;
+        let day = td.values[2] as u32;
+        let hour = td.values[3] as u32;
+        let minute = td.values[4] as u32;
+        let second = td.values[5] as u32;
+
+        let zone = InverterZone::for_inverter(&self.inverter);
+
+        // The inverter's clock is wall-clock time in `zone`, not UTC -
+        // localize it there (honoring that instant's DST offset) before
+        // comparing against "now". Inverter uses years since 2000.
+        let dt = zone.local_to_utc(2000 + year as i32, month, day, hour, minute, second);
+        let now = Utils::utc();
+
+        // Calculate the time difference between inverter and system
+        let time_diff = dt - now;
+        info!("Time sync for inverter {}: {}", datalog, time_diff);
+
+        if let Err(e) = self.channels.from_inverter.send(crate::eg4::inverter::ChannelData::TimeSyncDrift(datalog, time_diff)) {
+            warn!("failed to publish time sync drift for inverter {}: {}", datalog, e);
         }
 
-        // Wait for and process the inverter's response
-        if let Packet::TranslatedData(td) = receiver.wait_for_reply(&packet).await? {
-            // Extract time components from the response
-            // Values are in BCD format: [year, month, day, hour, minute, second]
-            let year = td.values[0] as u32;
-            let month = td.values[1] as u32;
-            let day = td.values[2] as u32;
-            let hour = td.values[3] as u32;
-            let minute = td.values[4] as u32;
-            let second = td.values[5] as u32;
-
-            // Convert inverter time to UTC DateTime
-            // Inverter uses years since 2000, so we add 2000 to get the actual year
-            let dt = chrono::Utc
-                .with_ymd_and_hms(2000 + year as i32, month, day, hour, minute, second)
-                .unwrap();
-
-            // Get current system time in UTC and adjust for local timezone offset
-            // This ensures we compare times in the same timezone
-            let offset_in_sec =
-                chrono::Duration::seconds(chrono::Local::now().offset().local_minus_utc() as i64);
-            let now = Utils::utc() + offset_in_sec;
-
-            // Calculate the time difference between inverter and system
-            let time_diff = dt - now;
-            info!(
-                "Time sync for inverter {}: {}",
-                self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default(),
-                time_diff
-            );
-
-            // Define thresholds for time synchronization
-            // Maximum allowed time difference (10 minutes) - prevents large jumps
-            let max_limit = chrono::Duration::seconds(600);
-            // Minimum time difference to trigger update (30 seconds) - prevents unnecessary updates
-            let min_limit = chrono::Duration::seconds(30);
-
-            // Skip time sync if inverter is in read-only mode to prevent accidental changes
-            if self.inverter.read_only() {
-                info!("Skipping time sync for inverter {} (read-only mode)",
-                    self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default());
-                return Ok(());
-            }
-
-            // Only update if time difference is significant but not too large
-            // This prevents both unnecessary updates and dangerous large time jumps
-            if (time_diff > min_limit && time_diff <= max_limit) || 
-               (time_diff < -min_limit && time_diff >= -max_limit) {
-                // Create and send the time update packet
-                let packet = self.set_time_packet(now);
-
-                if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-                    bail!("Failed to send packet to coordinator: {}", e);
-                }
+        // Skip time sync if inverter is in read-only mode to prevent accidental changes
+        if self.inverter.read_only() {
+            info!("Skipping time sync for inverter {} (read-only mode)", datalog);
+            return Ok(time_diff);
+        }
 
-                // Wait for confirmation of the time update
-                if let Packet::TranslatedData(_) = receiver.wait_for_reply(&packet).await? {
-                    debug!("time set ok");
-                } else {
-                    warn!("time set didn't get confirmation reply!");
-                }
-            } else if time_diff.abs() > max_limit {
-                // Log a warning if the time difference is too large
-                // This might indicate a problem that needs manual intervention
-                warn!(
-                    "Time difference of {} exceeds maximum allowed adjustment of 10 minutes. Manual intervention may be required.",
-                    time_diff
-                );
+        let policy = self.inverter.timesync_policy();
+        let max_adjustment = chrono::Duration::from_std(policy.max_adjustment())
+            .unwrap_or_else(|_| chrono::Duration::seconds(600));
+        // Minimum time difference to trigger update - prevents unnecessary updates
+        let min_limit = chrono::Duration::seconds(30);
+
+        // Only update if time difference is significant but not too large
+        // This prevents both unnecessary updates and dangerous large time jumps
+        if (time_diff > min_limit && time_diff <= max_adjustment) ||
+           (time_diff < -min_limit && time_diff >= -max_adjustment) {
+            // Create and send the time update packet
+            let packet = self.set_time_packet(zone.utc_to_local(now));
+
+            // Wait for confirmation of the time update
+            if let Packet::TranslatedData(_) = self.channels.send_and_wait(packet, timeout).await? {
+                debug!("time set ok");
+            } else {
+                warn!("time set didn't get confirmation reply!");
             }
+        } else if time_diff.abs() > max_adjustment {
+            // Log a warning if the time difference is too large
+            // This might indicate a problem that needs manual intervention
+            warn!(
+                "Time difference of {} exceeds configured max_adjustment of {:?}. Manual intervention may be required.",
+                time_diff, policy.max_adjustment()
+            );
         }
 
-        Ok(())
+        Ok(time_diff)
     }
 
     /// Creates a packet to set the inverter's time
-    /// 
+    ///
     /// # Arguments
-    /// * `now` - The current system time in UTC
-    /// 
+    /// * `now` - The current time, already localized to the inverter's
+    ///   configured timezone (or the system local zone when unset)
+    ///
     /// # Returns
     /// * `Packet` - A packet containing the new time values
-    fn set_time_packet(&self, now: chrono::DateTime<chrono::Utc>) -> Packet {
+    fn set_time_packet(&self, now: chrono::DateTime<chrono::FixedOffset>) -> Packet {
         use chrono::{Datelike, Timelike};
 
         Packet::TranslatedData(TranslatedData {
@@ -149,6 +244,7 @@ impl TimeSync {
                 now.minute() as u8,
                 now.second() as u8,
             ],
+            checksum_valid: true,
         })
     }
 }
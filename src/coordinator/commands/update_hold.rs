@@ -1,5 +1,4 @@
 use crate::prelude::*;
-use crate::eg4::inverter::WaitForReply;
 use crate::eg4::{
     packet::{Packet, RegisterBit, DeviceFunction, TranslatedData},
     inverter::ChannelData,
@@ -31,7 +30,7 @@ impl UpdateHold {
     }
 
     pub async fn run(&self) -> Result<()> {
-        let mut receiver = self.channels.from_inverter.subscribe();
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
 
         // First read the current value
         let read_packet = Packet::TranslatedData(TranslatedData {
@@ -40,14 +39,17 @@ impl UpdateHold {
             inverter: self.inverter.serial().expect("serial must be set"),
             register: self.register,
             values: vec![1, 0],
+            checksum_valid: true,
         });
 
+        let pending = self.channels.dispatcher.register(&read_packet)?;
+
         self.channels
             .to_inverter
             .send(ChannelData::Packet(read_packet.clone()))
             .map_err(|e| anyhow!("send(to_inverter) failed: {}", e))?;
 
-        let read_packet = receiver.wait_for_reply(&read_packet).await?;
+        let read_packet = pending.wait(timeout).await?;
         let current_value = read_packet.value();
         let new_value = if self.enable {
             current_value | (self.bit.clone() as u16)
@@ -62,14 +64,17 @@ impl UpdateHold {
             inverter: self.inverter.serial().expect("serial must be set"),
             register: self.register,
             values: new_value.to_le_bytes().to_vec(),
+            checksum_valid: true,
         });
 
+        let pending = self.channels.dispatcher.register(&write_packet)?;
+
         self.channels
             .to_inverter
             .send(ChannelData::Packet(write_packet.clone()))
             .map_err(|e| anyhow!("send(to_inverter) failed: {}", e))?;
 
-        let write_packet = receiver.wait_for_reply(&write_packet).await?;
+        let write_packet = pending.wait(timeout).await?;
         if write_packet.value() != new_value {
             bail!(
                 "failed to update register {:?}, got back value {} (wanted {})",
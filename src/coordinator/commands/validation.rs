@@ -1,8 +1,44 @@
 use crate::prelude::*;
+use thiserror::Error;
 
 /// Size of a register block. Both hold and input registers are organized in blocks of 40.
 pub const BLOCK_SIZE: u16 = 40;
 
+/// Why a register read was rejected by [`validate_register_block_boundary`].
+///
+/// Distinguishing these lets a caller react differently per case — e.g. `ReadInputs`
+/// auto-splits on [`RegisterError::CrossesBlockBoundary`] but a command that can't split
+/// (a single-block write) should reject [`RegisterError::CountExceedsBlock`] outright.
+#[derive(Error, Debug, Eq, PartialEq)]
+pub enum RegisterError {
+    #[error(
+        "cannot read across block boundary: register {register} count {count} would cross from block {start_block} to block {end_block} (each block is {block_size} registers)"
+    )]
+    CrossesBlockBoundary {
+        register: u16,
+        count: u16,
+        start_block: u16,
+        end_block: u16,
+        block_size: u16,
+    },
+
+    #[error("count {count} exceeds remaining registers in block: max readable from register {register} is {max}")]
+    CountExceedsBlock { register: u16, count: u16, max: u16 },
+
+    #[error("register {register} + count {count} overflows u16")]
+    RegisterOverflow { register: u16, count: u16 },
+
+    #[error(
+        "datalog {datalog} read rate limit exhausted: requested {requested} word(s), {available} available, refills in {retry_after_ms}ms"
+    )]
+    RateLimited {
+        datalog: Serial,
+        requested: u32,
+        available: u32,
+        retry_after_ms: u64,
+    },
+}
+
 /// Validates that a register read operation does not cross block boundaries.
 /// Both hold and input registers are organized in blocks of 40 registers each.
 /// Reading across block boundaries is not allowed by the protocol.
@@ -13,7 +49,7 @@ pub const BLOCK_SIZE: u16 = 40;
 ///
 /// # Returns
 /// * `Ok(())` if the read operation is valid
-/// * `Err` with descriptive message if the operation would cross block boundaries
+/// * `Err(RegisterError)` describing why it isn't
 ///
 /// # Examples
 /// ```
@@ -23,37 +59,33 @@ pub const BLOCK_SIZE: u16 = 40;
 /// // Invalid: Reading 11 registers starting at 35 (crosses from block 0 to 1)
 /// assert!(validate_register_block_boundary(35, 11).is_err());
 /// ```
-pub fn validate_register_block_boundary(register: u16, count: u16) -> Result<()> {
-    // Calculate the block number for start and end registers
+pub fn validate_register_block_boundary(register: u16, count: u16) -> std::result::Result<(), RegisterError> {
+    let end_register = register
+        .checked_add(count)
+        .and_then(|r| r.checked_sub(1))
+        .ok_or(RegisterError::RegisterOverflow { register, count })?;
+
     let start_block = register / BLOCK_SIZE;
-    let end_register = register + count - 1;
     let end_block = end_register / BLOCK_SIZE;
 
-    // Check if the read operation crosses a block boundary
     if start_block != end_block {
-        bail!(
-            "Invalid read operation: Cannot read across block boundary. Register {} count {} would cross from block {} to block {}. \
-            Each block is {} registers. Please limit your read to within a single block.",
+        return Err(RegisterError::CrossesBlockBoundary {
             register,
             count,
             start_block,
             end_block,
-            BLOCK_SIZE
-        );
+            block_size: BLOCK_SIZE,
+        });
     }
 
-    // Validate that count doesn't exceed remaining registers in the block
     let remaining_in_block = BLOCK_SIZE - (register % BLOCK_SIZE);
     if count > remaining_in_block {
-        bail!(
-            "Invalid read operation: Count {} exceeds remaining registers in block {}. \
-            Maximum readable registers from position {} is {}.",
-            count,
-            start_block,
+        return Err(RegisterError::CountExceedsBlock {
             register,
-            remaining_in_block
-        );
+            count,
+            max: remaining_in_block,
+        });
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
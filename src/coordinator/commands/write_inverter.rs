@@ -1,6 +1,9 @@
 use crate::prelude::*;
 use log::{info, error};
 use crate::coordinator::commands::time_register_ops;
+use crate::coordinator::commands::read_hold::ReadHold;
+use crate::coordinator::commands::set_ac_charge_time::{ChargeWindow, SetAcChargeTime};
+use crate::coordinator::commands::set_forced_discharge_time::SetForcedDischargeTime;
 use crate::coordinator::commands::set_hold::SetHold;
 use crate::coordinator::commands::write_param::WriteParam;
 use crate::coordinator::commands::time_register_ops::SetTimeRegister;
@@ -51,12 +54,23 @@ impl WriteInverter {
         self.set_hold(0x0103_u16, value).await
     }
 
-    /// Write operation: Sets AC charge time
+    /// Write operation: Sets AC charge window `num` (the slot from the
+    /// originating `set/ac_charge/{num}` topic, resolved to a register via
+    /// `ac_charge_time_{num}`)
     /// Blocked by read_only setting
-    pub async fn set_ac_charge_time(&self, values: [u8; 4]) -> Result<()> {
-        info!("Setting AC charge time to {:?} for inverter {}", values, self.inverter.datalog().unwrap_or_default());
+    pub async fn set_ac_charge_time(&self, num: u16, values: [u8; 4]) -> Result<()> {
+        info!("Setting AC charge time window {} to {:?} for inverter {}", num, values, self.inverter.datalog().unwrap_or_default());
         self.check_read_only()?;
-        self.set_time_register(time_register_ops::Action::AcCharge(0), values).await
+        SetAcChargeTime::new(
+            self.channels.clone(),
+            self.inverter.clone(),
+            self.config.clone(),
+            vec![(num, ChargeWindow::from_bytes(values))],
+        )
+        .run()
+        .await?;
+        info!("Successfully set AC charge time window {}", num);
+        Ok(())
     }
 
     /// Write operation: Sets AC first time
@@ -99,17 +113,43 @@ impl WriteInverter {
         self.set_hold(0x0101_u16, value).await
     }
 
-    /// Write operation: Sets forced discharge time
+    /// Write operation: Sets forced discharge time. `num` is the slot from
+    /// the originating `set/forced_discharge/{num}` topic; the inverter only
+    /// exposes one forced-discharge window (register
+    /// `FORCED_DISCHARGE_TIME_REGISTER`), so only `num == 0` is valid - the
+    /// same single-slot limitation `ReadForcedDischargeTime` already has.
     /// Blocked by read_only setting
-    pub async fn set_forced_discharge_time(&self, values: [u8; 4]) -> Result<()> {
+    pub async fn set_forced_discharge_time(&self, num: u16, values: [u8; 4]) -> Result<()> {
         info!("Setting forced discharge time to {:?} for inverter {}", values, self.inverter.datalog().unwrap_or_default());
         self.check_read_only()?;
-        self.set_time_register(time_register_ops::Action::ForcedDischarge(0), values).await
+        if num != 0 {
+            bail!("forced discharge time: only window 0 is supported, got {}", num);
+        }
+        SetForcedDischargeTime::new(self.channels.clone(), self.inverter.clone(), values)
+            .run()
+            .await?;
+        info!("Successfully set forced discharge time");
+        Ok(())
     }
 
     /// Write operation: Sets a holding register value
     /// Blocked by read_only setting
     pub async fn set_hold<U>(&self, register: U, value: u16) -> Result<()>
+    where
+        U: Into<u16> + Clone,
+    {
+        let reg = self.set_hold_raw(register, value).await?;
+        if self.inverter.write_verify() {
+            self.verify_hold_write(reg, value).await;
+        }
+        Ok(())
+    }
+
+    /// The write half of `set_hold`, with no read-back verification -
+    /// used directly by `Coordinator::update_hold`, which (when
+    /// `write_verify` is enabled) verifies only the single `RegisterBit`
+    /// it targeted rather than the whole-register check below.
+    pub(crate) async fn set_hold_raw<U>(&self, register: U, value: u16) -> Result<u16>
     where
         U: Into<u16> + Clone,
     {
@@ -125,7 +165,71 @@ impl WriteInverter {
         .run()
         .await?;
         info!("Successfully set hold register 0x{:04X} to {}", reg, value);
-        Ok(())
+        Ok(reg)
+    }
+
+    /// Opt-in (`write_verify`) read-back check for a holding-register write:
+    /// paces with the same `delay_ms` the read helpers use, reads the
+    /// register back, and publishes a matched/mismatched result to
+    /// `result/<datalog>/verify/hold/<register>`. Best-effort - a failed or
+    /// mismatched verification never fails the write itself, since by the
+    /// time this runs the write has already been acknowledged.
+    async fn verify_hold_write(&self, register: u16, expected: u16) {
+        let delay_ms = self.inverter.delay_ms();
+        if delay_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+        }
+
+        match ReadHold::new(self.channels.clone(), self.inverter.clone(), register, 1)
+            .run()
+            .await
+        {
+            Ok(packet) => {
+                let actual = packet.value();
+                self.publish_verification(register, expected, actual, actual == expected);
+            }
+            Err(e) => error!(
+                "write verification read-back of register 0x{:04X} failed for inverter {}: {}",
+                register,
+                self.inverter.datalog().unwrap_or_default(),
+                e
+            ),
+        }
+    }
+
+    /// Publishes one write-verification outcome. Used for both a whole
+    /// register (`verify_hold_write`) and a single bit within a register
+    /// (`Coordinator::update_hold`'s bit-scoped check).
+    pub(crate) fn publish_verification(&self, register: u16, expected: u16, actual: u16, matched: bool) {
+        if !matched {
+            error!(
+                "write verification mismatch on register 0x{:04X} for inverter {}: expected {}, got {}",
+                register,
+                self.inverter.datalog().unwrap_or_default(),
+                expected,
+                actual
+            );
+        }
+
+        let payload = serde_json::json!({
+            "register": register,
+            "expected": expected,
+            "actual": actual,
+            "matched": matched,
+        });
+        let message = mqtt::Message {
+            topic: format!(
+                "result/{}/verify/hold/{}",
+                self.inverter.datalog().map(|s| s.to_string()).unwrap_or_default(),
+                register
+            ),
+            retain: false,
+            payload: payload.to_string(),
+            ..Default::default()
+        };
+        if let Err(e) = self.channels.to_mqtt.send(mqtt::ChannelData::Message(message)) {
+            error!("failed to publish write verification: {}", e);
+        }
     }
 
     /// Write operation: Sets a parameter value
@@ -149,6 +253,48 @@ impl WriteInverter {
         Ok(())
     }
 
+    /// Write operation: best-effort sequential writes of a list of
+    /// `(register, value)` holding-register pairs, e.g. from a `set/batch`
+    /// MQTT payload. Blocked by read_only setting. This is NOT atomic: it
+    /// aborts on the first failing write, leaving any earlier writes in the
+    /// batch already applied to the inverter - there's no way to roll a
+    /// write back once the inverter has acknowledged it, so callers should
+    /// treat a failure as "applied up to here" rather than "applied none of
+    /// this", and re-read state before retrying.
+    pub async fn set_batch(&self, pairs: Vec<(u16, u16)>) -> Result<()> {
+        info!("Applying batch of {} register writes for inverter {}", pairs.len(), self.inverter.datalog().unwrap_or_default());
+        self.check_read_only()?;
+        for (register, value) in pairs {
+            self.set_hold(register, value).await?;
+        }
+        info!("Successfully applied batch write for inverter {}", self.inverter.datalog().unwrap_or_default());
+        Ok(())
+    }
+
+    /// Write operation: best-effort sequential writes of a full list of
+    /// start/end windows for one time-of-use schedule `kind`, e.g. from a
+    /// `set/schedule/{kind}` MQTT payload. Blocked by read_only setting.
+    /// Windows are numbered from 1 in the order given (`ac_charge`'s
+    /// `ac_charge_time_{num}` registers; `forced_discharge` only has one
+    /// window, so any entry past the first fails). As with `set_batch`,
+    /// this is NOT atomic - it aborts on the first failing window, leaving
+    /// any earlier windows in the schedule already applied.
+    pub async fn set_schedule(&self, kind: &str, windows: Vec<[u8; 4]>) -> Result<()> {
+        info!("Applying schedule '{}' with {} window(s) for inverter {}", kind, windows.len(), self.inverter.datalog().unwrap_or_default());
+        self.check_read_only()?;
+        for (index, values) in windows.into_iter().enumerate() {
+            match kind {
+                "ac_charge" => self.set_ac_charge_time(index as u16 + 1, values).await?,
+                "ac_first" => self.set_ac_first_time(values).await?,
+                "charge_priority" => self.set_charge_priority_time(values).await?,
+                "forced_discharge" => self.set_forced_discharge_time(index as u16, values).await?,
+                other => bail!("unknown schedule kind {:?}", other),
+            }
+        }
+        info!("Successfully applied schedule '{}' for inverter {}", kind, self.inverter.datalog().unwrap_or_default());
+        Ok(())
+    }
+
     /// Write operation: Sets a time register value
     /// Blocked by read_only setting
     pub async fn set_time_register(
@@ -1,10 +1,7 @@
 use crate::prelude::*;
 use log::{info, error};
 
-use eg4::{
-    inverter::WaitForReply,
-    packet::{WriteParam as WriteParamPacket, Packet},
-};
+use eg4::packet::{WriteParam as WriteParamPacket, Packet};
 
 use crate::coordinator::Channels;
 use crate::config;
@@ -42,15 +39,10 @@ impl WriteParam {
             values: self.value.to_le_bytes().to_vec(),
         });
 
-        let mut receiver = self.channels.from_inverter.subscribe();
-
         info!("Sending write param packet to coordinator");
-        if let Err(e) = self.channels.to_coordinator.send(crate::coordinator::ChannelData::SendPacket(packet.clone())) {
-            bail!("Failed to send packet to coordinator: {}", e);
-        }
-
         info!("Waiting for reply from inverter");
-        let packet = receiver.wait_for_reply(&packet).await?;
+        let timeout = std::time::Duration::from_secs(self.inverter.reply_timeout_secs());
+        let packet = self.channels.send_and_wait(packet, timeout).await?;
         // WriteParam packets seem to reply with 0 on success, very odd
         if packet.value() != 0 {
             error!("Failed to set register {} - received non-zero response: {}", self.register, packet.value());
@@ -0,0 +1,178 @@
+use crate::prelude::*;
+use crate::eg4::inverter;
+use crate::eg4::packet::{DeviceFunction, Packet};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Identifies which reply a pending request is waiting for.
+///
+/// `TranslatedData` replies are keyed on `device_function` as well as `register`
+/// because `ReadHold` and `ReadInput` share the same register space; the other
+/// packet types each have only one device on the wire per datalog/register.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum PendingKey {
+    TranslatedData(Serial, DeviceFunction, u16),
+    ReadParam(Serial, u16),
+    WriteParam(Serial, u16),
+    Heartbeat(Serial),
+}
+
+impl PendingKey {
+    fn for_packet(packet: &Packet) -> Self {
+        match packet {
+            Packet::TranslatedData(td) => Self::TranslatedData(td.datalog, td.device_function, td.register),
+            Packet::ReadParam(rp) => Self::ReadParam(rp.datalog, rp.register),
+            Packet::WriteParam(wp) => Self::WriteParam(wp.datalog, wp.register),
+            Packet::Heartbeat(hb) => Self::Heartbeat(hb.datalog),
+        }
+    }
+
+    fn datalog(&self) -> Serial {
+        match *self {
+            Self::TranslatedData(datalog, _, _) => datalog,
+            Self::ReadParam(datalog, _) => datalog,
+            Self::WriteParam(datalog, _) => datalog,
+            Self::Heartbeat(datalog) => datalog,
+        }
+    }
+}
+
+/// A registered wait for a reply, returned by [`Dispatcher::register`].
+///
+/// Register *before* sending the request so a reply that arrives in the gap between
+/// sending and awaiting still gets matched, then call [`Pending::wait`] for the result.
+/// If `wait` times out, or `Pending` is dropped without being waited on, the pending
+/// entry is evicted so a later, unrelated packet with the same key can't complete it.
+pub struct Pending {
+    key: PendingKey,
+    rx: oneshot::Receiver<Packet>,
+    dispatcher: Dispatcher,
+}
+
+impl Pending {
+    pub async fn wait(self, timeout: std::time::Duration) -> Result<Packet> {
+        match tokio::time::timeout(timeout, self.rx).await {
+            Ok(Ok(reply)) => Ok(reply),
+            Ok(Err(_)) => bail!("dispatcher: sender dropped without a reply"),
+            Err(_) => {
+                self.dispatcher.cancel(self.key);
+                bail!("dispatcher: timed out after {:?} waiting for a reply", timeout);
+            }
+        }
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.dispatcher.cancel(self.key);
+    }
+}
+
+/// Correlates outbound requests to their replies with per-request `oneshot` channels,
+/// instead of every command subscribing to the `from_inverter` broadcast and filtering
+/// out every packet that isn't theirs.
+///
+/// A command calls [`Dispatcher::register`] with the packet it is about to send *before*
+/// sending it, then awaits [`Pending::wait`]. A single consumer task (spawned by
+/// [`Dispatcher::run`]) drains `from_inverter` and completes whichever pending entry
+/// matches each incoming packet. Commands on different registers no longer see, or pay
+/// to filter out, each other's replies. A `Disconnect` or `Shutdown` on `from_inverter`
+/// drops the affected pending entries outright, so in-flight waiters fail immediately
+/// instead of sitting out their full timeout against a link that's already gone.
+///
+/// There is no polling anywhere in this path: registration happens synchronously before
+/// send, and completion is pushed to the waiter the moment `run`'s consumer task sees the
+/// matching reply, so latency is bounded by the link, not by a poll interval.
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    pending: Arc<Mutex<HashMap<PendingKey, oneshot::Sender<Packet>>>>,
+}
+
+impl std::fmt::Debug for Dispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.pending.lock().map(|p| p.len()).unwrap_or(0);
+        f.debug_struct("Dispatcher").field("pending", &len).finish()
+    }
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers interest in the reply to `request`. Call this before sending `request`
+    /// so the dispatcher can't miss a reply that arrives before you start waiting.
+    ///
+    /// Fails if another call is already waiting on the same `PendingKey` (same datalog,
+    /// device function and register) - the reply frame carries no per-call correlation
+    /// id, so the dispatcher has no way to tell two such replies apart, and silently
+    /// replacing the earlier sender would misdeliver or drop its caller's result. Callers
+    /// that legitimately need two requests for the same register in flight (e.g. two
+    /// `InverterClient`s polling the same register) must serialize them instead.
+    pub fn register(&self, request: &Packet) -> Result<Pending> {
+        let key = PendingKey::for_packet(request);
+        let (tx, rx) = oneshot::channel();
+
+        let mut pending = self.pending.lock().unwrap();
+        if pending.contains_key(&key) {
+            bail!("dispatcher: a request for {:?} is already in flight", key);
+        }
+        pending.insert(key, tx);
+        drop(pending);
+
+        Ok(Pending { key, rx, dispatcher: self.clone() })
+    }
+
+    fn cancel(&self, key: PendingKey) {
+        self.pending.lock().unwrap().remove(&key);
+    }
+
+    /// Drops every pending entry for `datalog`, so `Pending::wait` callers fail
+    /// immediately (sender dropped) instead of hanging until their timeout.
+    fn fail_for_datalog(&self, datalog: Serial) {
+        self.pending.lock().unwrap().retain(|key, _| key.datalog() != datalog);
+    }
+
+    /// Drops every pending entry, for a full shutdown.
+    fn fail_all(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+
+    /// Completes the pending entry matching `packet`, if any. A dropped receiver (the
+    /// waiter already timed out or was cancelled) just means the send below fails and
+    /// the stale entry is discarded here instead.
+    fn complete(&self, packet: Packet) {
+        let key = PendingKey::for_packet(&packet);
+        if let Some(tx) = self.pending.lock().unwrap().remove(&key) {
+            let _ = tx.send(packet);
+        }
+    }
+
+    /// Drains `from_inverter` for as long as the channel is open, completing pending
+    /// requests as their replies arrive. Intended to be spawned once by the coordinator.
+    pub async fn run(&self, mut from_inverter: inverter::Receiver) -> Result<()> {
+        loop {
+            match from_inverter.recv().await {
+                Ok(inverter::ChannelData::Packet(packet)) => self.complete(packet),
+                Ok(inverter::ChannelData::Disconnect(datalog)) => {
+                    warn!("dispatcher: inverter {} disconnected, failing its outstanding requests", datalog);
+                    self.fail_for_datalog(datalog);
+                }
+                Ok(inverter::ChannelData::Shutdown) => {
+                    info!("dispatcher: shutdown received, failing all outstanding requests");
+                    self.fail_all();
+                }
+                Ok(_) => {} // connection/heartbeat events don't affect pending requests
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("dispatcher: lagged behind from_inverter by {} messages", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    info!("dispatcher: from_inverter channel closed, exiting");
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,15 @@
 pub mod commands;
+pub mod dispatcher;
+pub mod supervisor;
+
+use supervisor::Supervisor;
 
 use crate::prelude::*;
 use crate::eg4::packet::{Register, RegisterBit};
-use crate::command::Command;
-use crate::datalog_writer::DatalogWriter;
+use crate::command::{Command, CommandResult};
+use crate::datalog_writer::{DatalogWriter, RotationConfig};
+use crate::kafka_register_sink::KafkaRegisterSink;
+use crate::sink::OutputSink;
 
 use crate::eg4::{
     packet::{DeviceFunction, TranslatedData, Packet},
@@ -20,7 +26,7 @@ use crate::eg4::inverter;
 use std::error::Error;
 
 // Sleep durations - keeping only the ones actively used
-const RETRY_DELAY_MS: u64 = 1000;    // 1 second
+pub(crate) const RETRY_DELAY_MS: u64 = 1000;    // 1 second
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum ChannelData {
@@ -61,6 +67,33 @@ pub struct PacketStats {
     pub serial_mismatches: u64,
     // Last message received per inverter
     pub last_messages: std::collections::HashMap<Serial, String>,
+    // Transport-level throughput/health, updated by the sender/reader tasks
+    // in `eg4::inverter` for per-inverter throughput and health dashboards.
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub write_errors: u64,
+    pub read_errors: u64,
+    pub reconnects: u64,
+    pub packets_decoded: u64,
+    /// How many times each named task supervised by [`Supervisor`] has been
+    /// restarted after exiting with an error or panicking.
+    pub task_restarts: std::collections::HashMap<String, u64>,
+    /// How many times each inverter's link has been proactively torn down
+    /// and reconnected after a run of consecutive modbus/serial errors,
+    /// keyed by datalog serial. See `Coordinator::record_link_error`.
+    pub forced_resets: std::collections::HashMap<Serial, u64>,
+}
+
+/// A cheap, `Copy` snapshot of `PacketStats`'s transport-level counters, for
+/// publishing to a stats/health topic without cloning the whole struct.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TransportStatsSnapshot {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub write_errors: u64,
+    pub read_errors: u64,
+    pub reconnects: u64,
+    pub packets_decoded: u64,
 }
 
 impl PacketStats {
@@ -101,6 +134,36 @@ impl PacketStats {
                 info!("      Last message: {}", last_msg);
             }
         }
+        info!("  Transport:");
+        info!("    Bytes sent: {}", self.bytes_sent);
+        info!("    Bytes received: {}", self.bytes_received);
+        info!("    Write errors: {}", self.write_errors);
+        info!("    Read errors: {}", self.read_errors);
+        info!("    Reconnects: {}", self.reconnects);
+        info!("    Packets decoded: {}", self.packets_decoded);
+        if !self.task_restarts.is_empty() {
+            info!("  Supervised task restarts:");
+            for (name, count) in &self.task_restarts {
+                info!("    {}: {}", name, count);
+            }
+        }
+        if !self.forced_resets.is_empty() {
+            info!("  Forced resets (error storms) by serial:");
+            for (serial, count) in &self.forced_resets {
+                info!("    {}: {}", serial, count);
+            }
+        }
+    }
+
+    pub fn transport_snapshot(&self) -> TransportStatsSnapshot {
+        TransportStatsSnapshot {
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            write_errors: self.write_errors,
+            read_errors: self.read_errors,
+            reconnects: self.reconnects,
+            packets_decoded: self.packets_decoded,
+        }
     }
 
     pub fn increment_serial_mismatches(&mut self) {
@@ -138,6 +201,90 @@ impl PacketStats {
         self.inverter_disconnections = other.inverter_disconnections.clone();
         self.serial_mismatches = other.serial_mismatches;
         self.last_messages = other.last_messages.clone();
+        self.bytes_sent = other.bytes_sent;
+        self.bytes_received = other.bytes_received;
+        self.write_errors = other.write_errors;
+        self.read_errors = other.read_errors;
+        self.reconnects = other.reconnects;
+        self.packets_decoded = other.packets_decoded;
+        self.task_restarts = other.task_restarts.clone();
+        self.forced_resets = other.forced_resets.clone();
+    }
+
+    /// Builds a machine-readable snapshot for the periodic telemetry task:
+    /// every counter in `self`, plus rates derived from the delta against
+    /// `previous` over `elapsed`, and the per-inverter `inverter_disconnections`
+    /// / `last_messages` maps broken out as a nested object keyed by serial.
+    pub fn telemetry_json(&self, previous: &PacketStats, elapsed: std::time::Duration) -> serde_json::Value {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let rate = |now: u64, prev: u64| now.saturating_sub(prev) as f64 / secs;
+
+        let total_errors = self.modbus_errors
+            + self.mqtt_errors
+            + self.influx_errors
+            + self.database_errors
+            + self.register_cache_errors;
+        let error_ratio = if self.packets_received > 0 {
+            total_errors as f64 / self.packets_received as f64
+        } else {
+            0.0
+        };
+
+        let inverters: serde_json::Map<String, serde_json::Value> = self
+            .inverter_disconnections
+            .iter()
+            .map(|(serial, count)| {
+                let entry = serde_json::json!({
+                    "disconnections": count,
+                    "last_message": self.last_messages.get(serial),
+                    "forced_resets": self.forced_resets.get(serial).copied().unwrap_or(0),
+                });
+                (serial.to_string(), entry)
+            })
+            .collect();
+
+        serde_json::json!({
+            "packets_received": self.packets_received,
+            "packets_sent": self.packets_sent,
+            "heartbeat_packets_received": self.heartbeat_packets_received,
+            "translated_data_packets_received": self.translated_data_packets_received,
+            "read_param_packets_received": self.read_param_packets_received,
+            "write_param_packets_received": self.write_param_packets_received,
+            "heartbeat_packets_sent": self.heartbeat_packets_sent,
+            "translated_data_packets_sent": self.translated_data_packets_sent,
+            "read_param_packets_sent": self.read_param_packets_sent,
+            "write_param_packets_sent": self.write_param_packets_sent,
+            "modbus_errors": self.modbus_errors,
+            "mqtt_errors": self.mqtt_errors,
+            "influx_errors": self.influx_errors,
+            "database_errors": self.database_errors,
+            "register_cache_errors": self.register_cache_errors,
+            "mqtt_messages_sent": self.mqtt_messages_sent,
+            "influx_writes": self.influx_writes,
+            "database_writes": self.database_writes,
+            "register_cache_writes": self.register_cache_writes,
+            "serial_mismatches": self.serial_mismatches,
+            "bytes_sent": self.bytes_sent,
+            "bytes_received": self.bytes_received,
+            "write_errors": self.write_errors,
+            "read_errors": self.read_errors,
+            "reconnects": self.reconnects,
+            "packets_decoded": self.packets_decoded,
+            "task_restarts": self.task_restarts,
+            "error_ratio": error_ratio,
+            "total_forced_resets": self.forced_resets.values().sum::<u64>(),
+            "rates": {
+                "packets_received_per_sec": rate(self.packets_received, previous.packets_received),
+                "packets_sent_per_sec": rate(self.packets_sent, previous.packets_sent),
+                "modbus_errors_per_sec": rate(self.modbus_errors, previous.modbus_errors),
+                "mqtt_errors_per_sec": rate(self.mqtt_errors, previous.mqtt_errors),
+                "influx_errors_per_sec": rate(self.influx_errors, previous.influx_errors),
+                "database_errors_per_sec": rate(self.database_errors, previous.database_errors),
+                "bytes_sent_per_sec": rate(self.bytes_sent, previous.bytes_sent),
+                "bytes_received_per_sec": rate(self.bytes_received, previous.bytes_received),
+            },
+            "inverters": inverters,
+        })
     }
 }
 
@@ -151,6 +298,48 @@ pub struct Coordinator {
     mqtt: Option<Arc<Mqtt>>,
     databases: Vec<Arc<Database>>,
     register_cache: Option<Arc<RegisterCache>>,
+    /// Every enabled `OutputSink`, fanned out to on each `TranslatedData`
+    /// packet. `influx` is the first registered member when InfluxDB is
+    /// enabled; future sinks (MQTT publisher, JSON-lines writer, ...) are
+    /// appended here without any other wiring changes.
+    sinks: Vec<Arc<dyn OutputSink>>,
+    /// Loaded register map consulted before any write packet is forwarded
+    /// to an inverter, so a `read_only`-marked register can't be scribbled
+    /// over regardless of which command generated the write.
+    register_parser: Option<Arc<crate::register::RegisterParser>>,
+    /// Owns the register cache and datalog writer's background tasks,
+    /// restarting either with backoff if it exits unexpectedly instead of
+    /// leaving the bridge running with a silently-dead subsystem.
+    supervisor: Arc<Supervisor>,
+    /// Live handle to each started inverter, keyed by datalog serial, so
+    /// `record_link_error` can call `force_reset` on a specific link
+    /// without re-deriving it from config. Populated once in `start()`.
+    inverter_handles: std::collections::HashMap<Serial, inverter::Inverter>,
+    /// Per-inverter consecutive-error bookkeeping driving the error-storm
+    /// reset in `record_link_error`. Only ever touched from the single main
+    /// loop task, so a plain `HashMap` (no lock) is enough.
+    error_storm_state: std::collections::HashMap<Serial, ErrorStormState>,
+}
+
+/// Tracks one inverter's run of consecutive modbus/serial errors for
+/// `Coordinator::record_link_error`: how many have landed within the
+/// current `error_storm_window_secs` window, and when this link was last
+/// force-reset (for `reset_cooldown_secs`).
+#[derive(Debug)]
+struct ErrorStormState {
+    consecutive_errors: u32,
+    window_start: std::time::Instant,
+    last_reset: Option<std::time::Instant>,
+}
+
+impl Default for ErrorStormState {
+    fn default() -> Self {
+        Self {
+            consecutive_errors: 0,
+            window_start: std::time::Instant::now(),
+            last_reset: None,
+        }
+    }
 }
 
 /// Manages all application components and their lifecycle
@@ -192,19 +381,24 @@ impl Components {
     }
 
     /// Gracefully stops all components in the correct order
-    /// 
+    ///
     /// The shutdown sequence is:
     /// 1. Coordinator (to stop processing new commands)
-    /// 2. InfluxDB (to stop data collection)
-    /// 3. MQTT (to stop message publishing)
-    /// 4. Databases (to stop data storage)
-    /// 5. Datalog writer (to stop logging)
+    /// 2. Coordinator's supervised tasks (register cache, datalog writer)
+    /// 3. InfluxDB (to stop data collection)
+    /// 4. MQTT (to stop message publishing)
+    /// 5. Databases (to stop data storage)
+    /// 6. Datalog writer (to stop logging)
     pub async fn stop(&mut self) {
         info!("Stopping all components...");
-        
+
         // Stop coordinator first to prevent new command processing
         self.coordinator.stop();
 
+        // Cancel and join the coordinator's supervised background tasks
+        // rather than leaving them to exit (or keep restarting) on their own.
+        self.coordinator.stop_supervised_tasks().await;
+
         // Stop optional components if they exist
         if let Some(influx) = &self.influx {
             influx.stop();
@@ -226,6 +420,13 @@ impl Components {
 impl Coordinator {
     pub fn new(config: Arc<ConfigWrapper>, channels: Channels) -> Self {
         let shared_stats = Arc::new(Mutex::new(PacketStats::default()));
+        let register_parser = config.register_file()
+            .as_ref()
+            .and_then(|file| crate::register::RegisterParser::new(file).ok())
+            .map(Arc::new);
+
+        let supervisor = Arc::new(Supervisor::new(shared_stats.clone()));
+
         Self {
             config,
             channels,
@@ -235,9 +436,46 @@ impl Coordinator {
             mqtt: None,
             databases: Vec::new(),
             register_cache: None,
+            sinks: Vec::new(),
+            register_parser,
+            supervisor,
+            inverter_handles: std::collections::HashMap::new(),
+            error_storm_state: std::collections::HashMap::new(),
         }
     }
 
+    /// Stops every task registered with the coordinator's [`Supervisor`]
+    /// (register cache, datalog writer), last-registered first, waiting for
+    /// each to actually exit. Called by `Components::stop` as part of the
+    /// orderly shutdown sequence.
+    pub async fn stop_supervised_tasks(&self) {
+        self.supervisor.stop_all().await;
+    }
+
+    /// Validates an outbound packet against the loaded register map before
+    /// it's forwarded to the inverter. Only `WriteSingle`/`WriteMulti`
+    /// packets touch registers; every other packet (reads, time-register
+    /// ops, etc.) passes through untouched. With no register file loaded
+    /// there's nothing to check against, so writes are allowed through as
+    /// before.
+    fn check_write_allowed(&self, packet: &Packet) -> Result<()> {
+        let Some(parser) = &self.register_parser else {
+            return Ok(());
+        };
+
+        let Packet::TranslatedData(td) = packet else {
+            return Ok(());
+        };
+
+        let count = match td.device_function {
+            DeviceFunction::WriteSingle => 1,
+            DeviceFunction::WriteMulti => (td.values.len() as u16 / 2).max(1),
+            _ => return Ok(()),
+        };
+
+        parser.can_write(td.register, count, false)
+    }
+
     pub fn stop(&self) {
         info!("Stopping coordinator...");
 
@@ -255,32 +493,62 @@ impl Coordinator {
         
         // Start with RegisterCache as it's a dependency for other components
         info!("  Creating RegisterCache...");
-        let register_cache = Arc::new(RegisterCache::new(self.channels.clone()));
+        let mut register_cache = RegisterCache::new(self.channels.clone());
+        if let Some(kafka) = self.config.register_cache_kafka() {
+            if kafka.enabled() {
+                info!("  Register cache will stream deltas to Kafka topic {}", kafka.topic());
+                let datalog = self.config.enabled_inverters().first().and_then(|i| i.datalog()).unwrap_or_default();
+                match KafkaRegisterSink::new(&kafka, datalog) {
+                    Ok(sink) => register_cache = register_cache.with_kafka(sink),
+                    Err(e) => error!("failed to start Kafka register sink, continuing without it: {}", e),
+                }
+            }
+        }
+        if let Some(snapshot) = self.config.register_cache_snapshot() {
+            if snapshot.enabled() {
+                info!("  Register cache will snapshot to {}", snapshot.path());
+                register_cache = register_cache.with_snapshot(&snapshot);
+            }
+        }
+        let register_cache = Arc::new(register_cache);
         self.register_cache = Some(register_cache.clone());
         
-        // Spawn the register cache task
-        tokio::spawn(async move {
-            if let Err(e) = register_cache.start().await {
-                error!("Register cache task failed: {}", e);
-            }
+        // Spawn the register cache task under supervision so it comes back
+        // with backoff if it ever exits unexpectedly instead of leaving the
+        // cache silently dead for the rest of the process.
+        self.supervisor.supervise("register_cache", move || {
+            let register_cache = register_cache.clone();
+            async move { register_cache.start().await }
         });
         
         // Initialize datalog writer if configured
         if let Some(path) = self.config.datalog_file() {
             info!("Creating datalog writer with path: {}", path);
-            let writer = DatalogWriter::new(&path, Arc::new(self.channels.clone()))?;
+            let rotation = RotationConfig {
+                max_size_bytes: self.config.datalog_max_size_bytes(),
+                max_age_secs: self.config.datalog_max_age_secs(),
+                max_generations: self.config.datalog_max_generations(),
+            };
+            let writer = DatalogWriter::new_with_rotation(&path, Arc::new(self.channels.clone()), self.config.register_file().as_deref(), rotation)?;
             let writer_arc = Arc::new(writer);
             self.datalog_writer = Some(writer_arc.clone());
             
-            // Spawn the datalog writer task
-            tokio::spawn(async move {
-                if let Err(e) = writer_arc.start().await {
-                    error!("Datalog writer task failed: {}", e);
-                }
+            // Spawn the datalog writer task under supervision, same as the
+            // register cache above.
+            self.supervisor.supervise("datalog_writer", move || {
+                let writer_arc = writer_arc.clone();
+                async move { writer_arc.start().await }
             });
             info!("Datalog writer initialized successfully");
         }
-        
+
+        // Spawn the periodic bridge-health telemetry task under the same
+        // supervision as the other long-lived subsystems, gated on the
+        // interval being non-zero. Whether each sink actually gets a
+        // snapshot is decided at spawn time, once MQTT/InfluxDB are known
+        // to be (or not be) configured below.
+        let telemetry_interval_secs = self.config.telemetry_interval_secs();
+
         // Initialize MQTT client if enabled
         if self.config.mqtt().enabled() {
             info!("Initializing MQTT");
@@ -292,9 +560,32 @@ impl Coordinator {
         if self.config.influx().enabled() {
             info!("Initializing InfluxDB");
             let influx = Arc::new(Influx::new((*self.config).clone(), self.channels.clone(), self.shared_stats.clone()));
+            self.sinks.push(influx.clone());
             self.influx = Some(influx);
         }
-        
+
+        // Now that MQTT/InfluxDB are known to be configured (or not), spawn
+        // the telemetry task if an interval was configured and at least one
+        // sink can actually receive it.
+        if telemetry_interval_secs > 0 {
+            let mqtt_enabled = self.mqtt.is_some();
+            let influx_enabled = self.influx.is_some();
+
+            if mqtt_enabled || influx_enabled {
+                let shared_stats = self.shared_stats.clone();
+                let channels = self.channels.clone();
+                let interval = std::time::Duration::from_secs(telemetry_interval_secs);
+
+                self.supervisor.supervise("telemetry", move || {
+                    Self::telemetry_task(shared_stats.clone(), channels.clone(), mqtt_enabled, influx_enabled, interval)
+                });
+            } else {
+                info!("telemetry_interval_secs is set but neither MQTT nor InfluxDB is enabled, skipping telemetry task");
+            }
+        } else {
+            info!("telemetry_interval_secs is 0, telemetry task disabled");
+        }
+
         // Initialize databases
         self.databases = self.config.databases()
             .iter()
@@ -366,15 +657,41 @@ impl Coordinator {
             .map(|inverter| Inverter::new((*self.config).clone(), &inverter, self.channels.clone()))
             .collect();
         
-        // Start each inverter
-        for inverter in inverters {
+        // Start each inverter, then keep a handle to it (keyed by datalog
+        // serial) so the main loop can force a proactive reconnect on an
+        // error storm without waiting for the OS to notice a half-open
+        // socket (see `record_link_error`).
+        for inverter in &inverters {
             if let Err(e) = inverter.start().await {
                 error!("Failed to start inverter: {}", e);
                 continue;
             }
         }
+        for inverter in inverters {
+            if let Some(datalog) = inverter.config().datalog() {
+                // Seed a retained `offline` availability message immediately,
+                // the same way the bridge-wide `<namespace>/LWT` topic is
+                // registered before the first successful MQTT connect -
+                // otherwise a dashboard watching this inverter's topic before
+                // it first connects sees nothing retained at all, rather than
+                // an explicit "not available yet".
+                if let Err(e) = self.publish_inverter_status(datalog, false) {
+                    error!("Failed to publish initial offline status for inverter {}: {}", datalog, e);
+                }
+                self.inverter_handles.insert(datalog, inverter);
+            }
+        }
         info!("All inverters started successfully");
 
+        // Spawn the request/reply dispatcher consumer task
+        let dispatcher = self.channels.dispatcher.clone();
+        let dispatcher_rx = self.channels.from_inverter.subscribe();
+        tokio::spawn(async move {
+            if let Err(e) = dispatcher.run(dispatcher_rx).await {
+                error!("Dispatcher task failed: {}", e);
+            }
+        });
+
         // Start the main loop to process inverter data
         let mut from_inverter_rx = self.channels.from_inverter.subscribe();
         let mut to_coordinator_rx = self.channels.to_coordinator.subscribe();
@@ -397,6 +714,13 @@ impl Coordinator {
                         }
                         Ok(eg4::inverter::ChannelData::Disconnect(datalog)) => {
                             info!("Inverter {} disconnected", datalog);
+                            if let Err(e) = self.publish_inverter_status(datalog, false) {
+                                error!("Failed to publish inverter {} offline status: {}", datalog, e);
+                            }
+                            // The inverter's own supervisor is already reconnecting this
+                            // link, so the error storm it may have been building toward
+                            // no longer applies - don't force a second reset once it's back.
+                            self.error_storm_state.remove(&datalog);
                         }
                         Ok(eg4::inverter::ChannelData::Shutdown) => {
                             info!("Received shutdown signal from inverter");
@@ -406,18 +730,23 @@ impl Coordinator {
                             debug!("Received heartbeat packet: {:?}", packet);
                         }
                         Ok(eg4::inverter::ChannelData::ModbusError(inverter, code, error)) => {
-                            error!("Modbus error from inverter {}: code {}, error: {:?}", 
+                            error!("Modbus error from inverter {}: code {}, error: {:?}",
                                 inverter.datalog().map(|s| s.to_string()).unwrap_or_default(),
                                 code,
                                 error
                             );
+                            self.record_link_error(&inverter);
                         }
                         Ok(eg4::inverter::ChannelData::SerialMismatch(inverter, expected, actual)) => {
-                            error!("Serial mismatch for inverter {}: expected {}, got {}", 
+                            error!("Serial mismatch for inverter {}: expected {}, got {}",
                                 inverter.datalog().map(|s| s.to_string()).unwrap_or_default(),
                                 expected,
                                 actual
                             );
+                            self.record_link_error(&inverter);
+                        }
+                        Ok(eg4::inverter::ChannelData::TimeSyncDrift(datalog, drift)) => {
+                            info!("Inverter {} clock drift: {}", datalog, drift);
                         }
                         Err(e) => {
                             error!("Error receiving from inverter channel: {}", e);
@@ -430,7 +759,9 @@ impl Coordinator {
                 msg = to_coordinator_rx.recv() => {
                     match msg {
                         Ok(ChannelData::SendPacket(packet)) => {
-                            if let Err(e) = self.channels.to_inverter.send(eg4::inverter::ChannelData::Packet(packet)) {
+                            if let Err(e) = self.check_write_allowed(&packet) {
+                                error!("Refusing to send packet to inverter: {}", e);
+                            } else if let Err(e) = self.channels.to_inverter.send(eg4::inverter::ChannelData::Packet(packet)) {
                                 error!("Failed to send packet to inverter: {}", e);
                             }
                         }
@@ -514,7 +845,12 @@ impl Coordinator {
     fn start_datalog_writer(&mut self) -> Result<()> {
         if let Some(path) = self.config.datalog_file() {
             info!("Creating datalog writer with path: {}", path);
-            let writer = DatalogWriter::new(&path, Arc::new(self.channels.clone()))?;
+            let rotation = RotationConfig {
+                max_size_bytes: self.config.datalog_max_size_bytes(),
+                max_age_secs: self.config.datalog_max_age_secs(),
+                max_generations: self.config.datalog_max_generations(),
+            };
+            let writer = DatalogWriter::new_with_rotation(&path, Arc::new(self.channels.clone()), self.config.register_file().as_deref(), rotation)?;
             self.datalog_writer = Some(Arc::new(writer));
             info!("Datalog writer initialized successfully");
         }
@@ -589,11 +925,11 @@ impl Coordinator {
         // Process the packet
         match packet {
             Packet::TranslatedData(td) => {
-                // Skip heartbeat packets for InfluxDB
+                // Skip heartbeat packets for output sinks
                 if !matches!(td.device_function, DeviceFunction::WriteSingle | DeviceFunction::WriteMulti) {
-                    // Send to InfluxDB
-                    if let Err(e) = self.send_to_influx(&td).await {
-                        error!("Failed to send data to InfluxDB: {}", e);
+                    // Fan out to every enabled output sink (InfluxDB, ...)
+                    if let Err(e) = self.send_to_sinks(&td).await {
+                        error!("Failed to send data to output sinks: {}", e);
                     }
                 }
 
@@ -660,20 +996,53 @@ impl Coordinator {
             return Ok(());
         }
 
+        let request_id = message.request_id().map(str::to_string);
+        // A v5 command carrying a Response Topic/Correlation Data gets its
+        // reply sent straight there instead of the hardcoded topics below,
+        // with the correlation data and any user properties (e.g. a
+        // requested-by tag) echoed back unchanged - see `Mqtt::sender_v5`.
+        let response_topic = message.response_topic.clone();
+        let correlation_data = message.correlation_data.clone();
+        let user_properties = message.user_properties.clone();
+
         for inverter in self.config.inverters_for_message(&message)? {
             match message.to_command(inverter) {
                 Ok(command) => {
                     info!("parsed command {:?}", command);
                     let result = self.process_command(command.clone()).await;
-                    if result.is_err() {
-                    let topic_reply = command.to_result_topic();
+
+                    // A structured ok/error acknowledgement goes out on every
+                    // command's `to_result_topic()`, not just on failure, so
+                    // a write actually applying (or a read completing) is
+                    // just as visible as one failing - see
+                    // `CommandResult::to_outcome_json`.
+                    let command_result = match &result {
+                        Ok(()) => CommandResult::Ok,
+                        Err(e) => CommandResult::from_error(e),
+                    };
                     let reply = mqtt::ChannelData::Message(mqtt::Message {
-                        topic: topic_reply,
+                        topic: command.to_result_topic(),
                         retain: false,
-                            payload: "FAIL".to_string(),
+                        payload: command_result.to_outcome_json(&command)?,
+                        response_topic: response_topic.clone(),
+                        correlation_data: correlation_data.clone(),
+                        user_properties: user_properties.clone(),
                     });
                     if self.channels.to_mqtt.send(reply).is_err() {
                         bail!("send(to_mqtt) failed - channel closed?");
+                    }
+
+                    if let Some(request_id) = &request_id {
+                        let reply = mqtt::ChannelData::Message(mqtt::Message {
+                            topic: format!("response/{}", request_id),
+                            retain: false,
+                            payload: command_result.to_json()?,
+                            response_topic: response_topic.clone(),
+                            correlation_data: correlation_data.clone(),
+                            user_properties: user_properties.clone(),
+                        });
+                        if self.channels.to_mqtt.send(reply).is_err() {
+                            bail!("send(to_mqtt) failed - channel closed?");
                         }
                     }
                 }
@@ -711,7 +1080,9 @@ impl Coordinator {
             Command::ReadForcedDischargeTime(inv, _) |
             Command::AcCharge(inv, _) |
             Command::ChargePriority(inv, _) |
-            Command::ForcedDischarge(inv, _) => inv.clone(),
+            Command::ForcedDischarge(inv, _) |
+            Command::SetBatch(inv, _) |
+            Command::SetSchedule(inv, _, _) => inv.clone(),
         };
 
         let write_inverter = commands::write_inverter::WriteInverter::new(
@@ -729,10 +1100,10 @@ impl Coordinator {
             Command::DischargeCutoffSocLimit(_, value) => write_inverter.set_discharge_cutoff_soc_limit(value).await,
             Command::SetHold(_, register, value) => write_inverter.set_hold(register, value).await,
             Command::WriteParam(_, register, value) => write_inverter.set_param(register, value).await,
-            Command::SetAcChargeTime(_, _, values) => write_inverter.set_ac_charge_time(values).await,
+            Command::SetAcChargeTime(_, num, values) => write_inverter.set_ac_charge_time(num, values).await,
             Command::SetAcFirstTime(_, _, values) => write_inverter.set_ac_first_time(values).await,
             Command::SetChargePriorityTime(_, _, values) => write_inverter.set_charge_priority_time(values).await,
-            Command::SetForcedDischargeTime(_, _, values) => write_inverter.set_forced_discharge_time(values).await,
+            Command::SetForcedDischargeTime(_, num, values) => write_inverter.set_forced_discharge_time(num, values).await,
             
             // Read operations - these are always allowed regardless of read_only mode
             Command::ReadInputs(_, block) => self.read_input_block(&inverter, block * 40, inverter.register_block_size()).await,
@@ -769,6 +1140,8 @@ impl Coordinator {
                     enable,
                 ).await
             },
+            Command::SetBatch(_, pairs) => write_inverter.set_batch(pairs).await,
+            Command::SetSchedule(_, kind, windows) => write_inverter.set_schedule(&kind, windows).await,
         }
     }
 
@@ -881,13 +1254,153 @@ impl Coordinator {
 
     async fn inverter_connected(&mut self, datalog: Serial) -> Result<()> {
         info!("Inverter {} connected", datalog);
+        self.publish_inverter_status(datalog, true)?;
         Ok(())
     }
 
-    async fn send_to_influx(&self, data: &TranslatedData) -> Result<()> {
-        if let Some(influx) = &self.influx {
-            let json = serde_json::to_value(data)?;
-            self.channels.to_influx.send(influx::ChannelData::InputData(json))?;
+    /// Mirrors one inverter's connection state to a retained
+    /// `<prefix>/inverter/<datalog>/status` MQTT message (`online`/`offline`),
+    /// so downstream systems (e.g. Home Assistant availability) can track
+    /// individual inverters the same way `<prefix>/LWT` tracks the bridge
+    /// itself. A no-op when MQTT isn't enabled.
+    fn publish_inverter_status(&self, datalog: Serial, online: bool) -> Result<()> {
+        if self.mqtt.is_none() {
+            return Ok(());
+        }
+
+        let message = mqtt::Message {
+            topic: format!("inverter/{}/status", datalog),
+            retain: true,
+            payload: (if online { "online" } else { "offline" }).to_string(),
+            ..Default::default()
+        };
+        self.channels.to_mqtt.send(mqtt::ChannelData::Message(message))?;
+        Ok(())
+    }
+
+    /// Counts one modbus/serial-mismatch error against `inverter`'s
+    /// rolling error-storm window; once `error_storm_threshold` consecutive
+    /// errors land within `error_storm_window_secs` (and at least
+    /// `reset_cooldown_secs` has passed since the last forced reset for
+    /// this serial), proactively tears down and reconnects its link via
+    /// `Inverter::force_reset` instead of waiting for the OS to notice a
+    /// half-open socket. `InputsStore` isn't held anywhere as live
+    /// per-inverter session state in this tree, so there's no cache to
+    /// clear alongside the socket - tearing down the connection is the
+    /// whole reset. A no-op if this inverter has no live handle (shouldn't
+    /// happen - every configured inverter gets one in `start()`).
+    fn record_link_error(&mut self, inverter: &config::Inverter) {
+        let Some(datalog) = inverter.datalog() else { return };
+        let now = std::time::Instant::now();
+        let window = std::time::Duration::from_secs(inverter.error_storm_window_secs());
+        let threshold = inverter.error_storm_threshold();
+        let cooldown = std::time::Duration::from_secs(inverter.reset_cooldown_secs());
+
+        let state = self.error_storm_state.entry(datalog).or_default();
+        if now.duration_since(state.window_start) > window {
+            state.consecutive_errors = 0;
+            state.window_start = now;
+        }
+        state.consecutive_errors += 1;
+
+        if state.consecutive_errors < threshold {
+            return;
+        }
+        if let Some(last_reset) = state.last_reset {
+            if now.duration_since(last_reset) < cooldown {
+                debug!(
+                    "inverter {}: error storm threshold reached but still within reset cooldown, skipping",
+                    datalog
+                );
+                return;
+            }
+        }
+
+        state.consecutive_errors = 0;
+        state.window_start = now;
+        state.last_reset = Some(now);
+
+        if let Some(handle) = self.inverter_handles.get(&datalog) {
+            handle.force_reset();
+        } else {
+            warn!("inverter {}: error storm detected but no live handle to reset", datalog);
+            return;
+        }
+
+        if let Ok(mut stats) = self.shared_stats.lock() {
+            *stats.forced_resets.entry(datalog).or_insert(0) += 1;
+        }
+    }
+
+    /// Runs forever, snapshotting `shared_stats` every `interval` and
+    /// publishing it as a JSON document to MQTT's `bridge/telemetry` topic
+    /// and/or a set of InfluxDB line-protocol points via `to_influx`,
+    /// whichever of `mqtt_enabled`/`influx_enabled` is set. Takes its state
+    /// by value rather than `&self` since it runs under
+    /// `Supervisor::supervise`, which needs a `'static` future it can
+    /// restart independently of the `Coordinator` that spawned it.
+    async fn telemetry_task(
+        shared_stats: Arc<Mutex<PacketStats>>,
+        channels: Channels,
+        mqtt_enabled: bool,
+        influx_enabled: bool,
+        interval: std::time::Duration,
+    ) -> Result<()> {
+        let mut ticker = tokio::time::interval(interval);
+        let mut previous = PacketStats::default();
+        let mut last_tick = std::time::Instant::now();
+
+        loop {
+            ticker.tick().await;
+
+            let current = {
+                let stats = shared_stats.lock().map_err(|_| anyhow!("shared_stats mutex poisoned"))?;
+                let mut snapshot = PacketStats::default();
+                snapshot.copy_from(&stats);
+                snapshot
+            };
+            let elapsed = last_tick.elapsed();
+
+            let mut telemetry = current.telemetry_json(&previous, elapsed);
+            if let Some(object) = telemetry.as_object_mut() {
+                object.insert("time".to_string(), serde_json::json!(Utils::utc().timestamp()));
+            }
+
+            if mqtt_enabled {
+                let message = mqtt::Message {
+                    topic: "bridge/telemetry".to_string(),
+                    retain: false,
+                    payload: telemetry.to_string(),
+                    ..Default::default()
+                };
+                if let Err(e) = channels.to_mqtt.send(mqtt::ChannelData::Message(message)) {
+                    error!("failed to publish bridge telemetry to MQTT: {}", e);
+                }
+            }
+
+            if influx_enabled {
+                if let Err(e) = channels.to_influx.send(influx::ChannelData::Stats(telemetry)) {
+                    error!("failed to publish bridge telemetry to InfluxDB: {}", e);
+                }
+            }
+
+            previous = current;
+            last_tick = std::time::Instant::now();
+        }
+    }
+
+    /// Fans a decoded `TranslatedData` packet out to every registered
+    /// `OutputSink` (InfluxDB today; any future sink registered in `sinks`).
+    async fn send_to_sinks(&self, data: &TranslatedData) -> Result<()> {
+        if self.sinks.is_empty() {
+            return Ok(());
+        }
+
+        let json = serde_json::to_value(data)?;
+        for sink in &self.sinks {
+            if let Err(e) = sink.write(&json).await {
+                error!("Failed to write to output sink: {}", e);
+            }
         }
         Ok(())
     }
@@ -904,11 +1417,9 @@ impl Coordinator {
             })
             .collect();
 
-        // Send each value to the register cache
-        for (i, value) in values_u16.into_iter().enumerate() {
-            let reg = register + i as u16;
-            self.channels.to_register_cache.send(register_cache::ChannelData::RegisterData(reg, value))?;
-        }
+        // Send the whole frame to the register cache in one hop instead of one
+        // ChannelData per register.
+        self.channels.to_register_cache.send(register_cache::ChannelData::RegisterDataBulk(register, values_u16))?;
         Ok(())
     }
 
@@ -927,13 +1438,59 @@ impl Coordinator {
     }
 
     async fn update_hold(&self, inverter: config::Inverter, register: Register, bit: RegisterBit, enable: bool) -> Result<()> {
+        let write_verify = inverter.write_verify();
+        let delay_ms = inverter.delay_ms();
         let write_inverter = commands::write_inverter::WriteInverter::new(
             self.channels.clone(),
-            inverter,
+            inverter.clone(),
             (*self.config).clone(),
         );
         let value = if enable { 1 } else { 0 };
-        write_inverter.set_hold(register, value).await
+        let reg: u16 = register.into();
+        write_inverter.set_hold_raw(reg, value).await?;
+
+        if write_verify {
+            self.verify_hold_bit(&write_inverter, inverter, reg, bit, enable, delay_ms)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Opt-in (`write_verify`) read-back check for a bit-level write made via
+    /// `update_hold`: paces with `delay_ms` like the other read helpers, reads
+    /// the register back, and compares only the targeted `bit` rather than
+    /// the whole register value - `update_hold` writes a bare 0/1, not the
+    /// register's other bits, so a whole-register comparison would always
+    /// mismatch.
+    async fn verify_hold_bit(
+        &self,
+        write_inverter: &commands::write_inverter::WriteInverter,
+        inverter: config::Inverter,
+        register: u16,
+        bit: RegisterBit,
+        enable: bool,
+        delay_ms: u64,
+    ) {
+        if delay_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+        }
+
+        match commands::read_hold::ReadHold::new(self.channels.clone(), inverter, register, 1)
+            .run()
+            .await
+        {
+            Ok(packet) => {
+                let actual = packet.value();
+                let expected_bit: u16 = if enable { 1 } else { 0 };
+                let actual_bit: u16 = if actual & (bit as u16) != 0 { 1 } else { 0 };
+                write_inverter.publish_verification(register, expected_bit, actual_bit, actual_bit == expected_bit);
+            }
+            Err(e) => error!(
+                "write verification read-back of register {} failed: {}",
+                register, e
+            ),
+        }
     }
 
     async fn read_time_register(&self, inverter: &config::Inverter, action: Action) -> Result<()> {
@@ -953,3 +1510,106 @@ impl Coordinator {
         coordinator.start().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+    use std::str::FromStr;
+    use tempfile::NamedTempFile;
+
+    fn test_inverter(serial: &str, datalog: &str) -> config::Inverter {
+        config::Inverter {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            port: 8000,
+            serial: Some(Serial::from_str(serial).unwrap()),
+            datalog: Some(Serial::from_str(datalog).unwrap()),
+            heartbeats: None,
+            publish_holdings_on_connect: None,
+            read_timeout: None,
+            use_tcp_nodelay: None,
+            register_block_size: None,
+            delay_ms: None,
+            read_only: None,
+            write_verify: None,
+            command_timeout_secs: None,
+            command_retries: None,
+            read_rate_limit_words: None,
+            read_rate_limit_window_secs: None,
+            tcp_connect_timeout_secs: None,
+            write_timeout_secs: None,
+            tcp_keepalive_secs: None,
+            reconnect_base_delay_secs: None,
+            reconnect_max_delay_secs: None,
+            reconnect_max_attempts: None,
+            reconnect_reset_secs: None,
+            reply_timeout_secs: Some(1),
+            error_storm_threshold: None,
+            error_storm_window_secs: None,
+            reset_cooldown_secs: None,
+            tls: Default::default(),
+            timezone: None,
+            timesync_policy: Default::default(),
+        }
+    }
+
+    /// Exercises `Command::SetAcChargeTime` through `Coordinator::process_command`
+    /// end-to-end, instead of just `SetAcChargeTime::run()` in isolation: the register
+    /// file below only defines `ac_charge_time_3`, so this only succeeds if the
+    /// command's window number actually reaches the register lookup rather than being
+    /// discarded in favor of a hardcoded window 0 - the bug this fixes.
+    #[tokio::test]
+    async fn set_ac_charge_time_command_uses_its_window_number() -> Result<()> {
+        let mut register_file = NamedTempFile::new()?;
+        write!(
+            register_file,
+            r#"{{"registers":[{{"register_type":"hold","register_map":[
+                {{"register_number":96,"name":"AC Charge Time 3","description":"d","datatype":"u16","shortname":"ac_charge_time_3"}}
+            ]}}]}}"#
+        )?;
+
+        let mut config_file = NamedTempFile::new()?;
+        write!(
+            config_file,
+            r#"
+inverters:
+  - host: "127.0.0.1"
+    port: 8000
+    serial: "1234567890"
+    datalog: "0987654321"
+mqtt: {{}}
+influx:
+  url: ""
+read_only: false
+register_file: "{}"
+"#,
+            register_file.path().display()
+        )?;
+
+        let config = Arc::new(ConfigWrapper::new(config_file.path().to_str().unwrap().to_string())?);
+        let channels = Channels::new();
+        let coordinator = Coordinator::new(config, channels.clone());
+
+        let dispatcher = channels.dispatcher.clone();
+        tokio::spawn(async move { dispatcher.run(channels.from_inverter.subscribe()).await });
+
+        // Stand in for the coordinator's real inverter link: echo every outbound
+        // packet straight back as the "reply", since all this test cares about is
+        // which register got addressed, not a real write acknowledgement.
+        let mut to_coordinator = coordinator.channels.to_coordinator.subscribe();
+        let from_inverter = coordinator.channels.from_inverter.clone();
+        tokio::spawn(async move {
+            if let Ok(ChannelData::SendPacket(packet)) = to_coordinator.recv().await {
+                let _ = from_inverter.send(crate::eg4::inverter::ChannelData::Packet(packet));
+            }
+        });
+
+        let inverter = test_inverter("1234567890", "0987654321");
+        coordinator
+            .process_command(Command::SetAcChargeTime(inverter, 3, [6, 0, 7, 0]))
+            .await?;
+
+        Ok(())
+    }
+}
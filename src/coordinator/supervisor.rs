@@ -0,0 +1,148 @@
+use crate::prelude::*;
+use crate::coordinator::PacketStats;
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+/// Exponential backoff between restart attempts for one supervised task,
+/// doubling from `base_delay` up to `max_delay`.
+#[derive(Clone, Copy, Debug)]
+pub struct RestartPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(crate::coordinator::RETRY_DELAY_MS),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+fn backoff_delay(attempt: u32, policy: RestartPolicy) -> Duration {
+    let factor = 1u32 << attempt.min(10);
+    policy.base_delay.saturating_mul(factor).min(policy.max_delay)
+}
+
+/// A handle to one task registered with [`Supervisor::supervise`], for
+/// `Supervisor::stop_all` to cancel and join.
+struct TaskHandle {
+    name: String,
+    abort_handle: Arc<Mutex<tokio::task::AbortHandle>>,
+    shutting_down: Arc<AtomicBool>,
+    supervisor_handle: JoinHandle<()>,
+}
+
+impl TaskHandle {
+    async fn stop(self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        self.abort_handle.lock().expect("abort_handle mutex poisoned").abort();
+        let _ = self.supervisor_handle.await;
+    }
+}
+
+/// Keeps a named set of long-lived subsystem tasks (register cache, datalog
+/// writer) alive for the life of the process: each is spawned through
+/// [`Supervisor::supervise`], which restarts it with [`RestartPolicy`]
+/// backoff whenever its future returns an error or panics, instead of the
+/// bare `tokio::spawn` + log-and-forget this replaces. `Components::stop`
+/// calls [`Supervisor::stop_all`] to cancel every task, last-registered
+/// first, and wait for it to actually exit.
+pub struct Supervisor {
+    shared_stats: Arc<Mutex<PacketStats>>,
+    policy: RestartPolicy,
+    tasks: Mutex<Vec<TaskHandle>>,
+}
+
+impl Supervisor {
+    pub fn new(shared_stats: Arc<Mutex<PacketStats>>) -> Self {
+        Self {
+            shared_stats,
+            policy: RestartPolicy::default(),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Spawns `make_task` immediately, then re-spawns it with backoff every
+    /// time the running future exits other than by [`Supervisor::stop_all`]
+    /// cancelling it. `name` is used for logging and as the key into
+    /// `PacketStats::task_restarts`.
+    pub fn supervise<F, Fut>(&self, name: &str, make_task: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let shutting_down = Arc::new(AtomicBool::new(false));
+        let first = tokio::spawn(make_task());
+        let abort_handle = Arc::new(Mutex::new(first.abort_handle()));
+
+        let name_owned = name.to_string();
+        let shared_stats = self.shared_stats.clone();
+        let policy = self.policy;
+        let shutting_down_clone = shutting_down.clone();
+        let abort_handle_clone = abort_handle.clone();
+
+        let supervisor_handle = tokio::spawn(async move {
+            let mut current = first;
+            let mut attempt: u32 = 0;
+
+            loop {
+                let result = (&mut current).await;
+
+                if shutting_down_clone.load(Ordering::SeqCst) {
+                    debug!("supervised task '{}' stopped", name_owned);
+                    return;
+                }
+
+                match result {
+                    Ok(Ok(())) => {
+                        info!("supervised task '{}' exited cleanly, not restarting", name_owned);
+                        return;
+                    }
+                    Ok(Err(e)) => error!("supervised task '{}' failed: {}", name_owned, e),
+                    Err(e) => error!("supervised task '{}' panicked: {}", name_owned, e),
+                }
+
+                if let Ok(mut stats) = shared_stats.lock() {
+                    *stats.task_restarts.entry(name_owned.clone()).or_insert(0) += 1;
+                }
+
+                let delay = backoff_delay(attempt, policy);
+                warn!("supervised task '{}' restarting in {:?} (attempt {})", name_owned, delay, attempt + 1);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+
+                current = tokio::spawn(make_task());
+                *abort_handle_clone.lock().expect("abort_handle mutex poisoned") = current.abort_handle();
+            }
+        });
+
+        self.tasks.lock().expect("tasks mutex poisoned").push(TaskHandle {
+            name: name.to_string(),
+            abort_handle,
+            shutting_down,
+            supervisor_handle,
+        });
+    }
+
+    /// Cancels every supervised task, in the reverse of the order they were
+    /// registered (so a task started as a dependency of a later one outlives
+    /// it during shutdown), and waits for each to actually exit.
+    pub async fn stop_all(&self) {
+        let tasks: Vec<TaskHandle> = {
+            let mut guard = self.tasks.lock().expect("tasks mutex poisoned");
+            std::mem::take(&mut *guard)
+        };
+
+        for task in tasks.into_iter().rev() {
+            info!("stopping supervised task '{}'", task.name);
+            task.stop().await;
+        }
+    }
+}
@@ -2,6 +2,157 @@ use crate::prelude::*;
 use sqlx::{any::AnyConnectOptions, Pool, Any, Executor};
 use std::sync::RwLock;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// A single bound value, type-erased over the few scalar types `row_fields`
+/// actually produces.
+#[derive(Debug, Clone)]
+enum BindValue {
+    I64(i64),
+    F64(f64),
+    String(String),
+}
+
+impl BindValue {
+    fn bind<'q>(
+        self,
+        query: sqlx::query::Query<'q, Any, sqlx::any::AnyArguments<'q>>,
+    ) -> sqlx::query::Query<'q, Any, sqlx::any::AnyArguments<'q>> {
+        match self {
+            BindValue::I64(v) => query.bind(v),
+            BindValue::F64(v) => query.bind(v),
+            BindValue::String(v) => query.bind(v),
+        }
+    }
+}
+
+/// Single source of truth for what gets written to the `inputs` table: each
+/// entry is a column name paired with its bound value, in insert order. The
+/// column list, the dialect-aware placeholder numbering, and the actual
+/// `.bind()` calls are all derived from this vector, so adding a field here
+/// is the only change needed and the three can never drift out of sync the
+/// way the old hand-maintained `columns()`/`values_for_*()`/`.bind()` chain
+/// could (and had: the non-MySQL placeholder list ran to `$91` while only
+/// 68 values were ever bound).
+fn row_fields(data: &eg4::packet::ReadInputAll) -> Vec<(&'static str, BindValue)> {
+    use BindValue::{F64, I64, String as Str};
+
+    vec![
+        ("status", I64(data.status as i64)),
+        ("v_pv_1", I64(data.v_pv_1.unwrap_or(0.0) as i64)),
+        ("v_pv_2", I64(data.v_pv_2.unwrap_or(0.0) as i64)),
+        ("v_pv_3", I64(data.v_pv_3.unwrap_or(0.0) as i64)),
+        ("v_bat", I64(data.v_bat.unwrap_or(0.0) as i64)),
+        ("soc", I64(data.soc as i64)),
+        ("soh", I64(data.soh as i64)),
+        ("internal_fault", I64(data.internal_fault as i64)),
+        ("p_pv", I64(data.p_pv as i64)),
+        ("p_pv_1", I64(data.p_pv_1 as i64)),
+        ("p_pv_2", I64(data.p_pv_2 as i64)),
+        ("p_pv_3", I64(data.p_pv_3 as i64)),
+        ("p_battery", F64(data.p_battery as f64)),
+        ("p_charge", I64(data.p_charge as i64)),
+        ("p_discharge", I64(data.p_discharge as i64)),
+        ("v_ac_r", I64(data.v_ac_r as i64)),
+        ("v_ac_s", I64(data.v_ac_s as i64)),
+        ("v_ac_t", I64(data.v_ac_t as i64)),
+        ("f_ac", F64(data.f_ac as f64)),
+        ("p_inv", I64(data.p_inv as i64)),
+        ("p_rec", I64(data.p_rec as i64)),
+        ("pf", F64(data.pf as f64)),
+        ("v_eps_r", I64(data.v_eps_r as i64)),
+        ("v_eps_s", I64(data.v_eps_s as i64)),
+        ("v_eps_t", I64(data.v_eps_t as i64)),
+        ("f_eps", F64(data.f_eps as f64)),
+        ("p_eps", I64(data.p_eps as i64)),
+        ("s_eps", I64(data.s_eps as i64)),
+        ("p_grid", F64(data.p_grid as f64)),
+        ("p_to_grid", I64(data.p_to_grid as i64)),
+        ("p_to_user", I64(data.p_to_user as i64)),
+        ("e_pv_day", I64(data.e_pv_day as i64)),
+        ("e_pv_day_1", I64(data.e_pv_day_1 as i64)),
+        ("e_pv_day_2", I64(data.e_pv_day_2 as i64)),
+        ("e_pv_day_3", I64(data.e_pv_day_3 as i64)),
+        ("e_inv_day", I64(data.e_inv_day as i64)),
+        ("e_rec_day", I64(data.e_rec_day as i64)),
+        ("e_chg_day", I64(data.e_chg_day as i64)),
+        ("e_dischg_day", I64(data.e_dischg_day as i64)),
+        ("e_eps_day", I64(data.e_eps_day as i64)),
+        ("e_to_grid_day", I64(data.e_to_grid_day as i64)),
+        ("e_to_user_day", I64(data.e_to_user_day as i64)),
+        ("v_bus_1", I64(data.v_bus_1 as i64)),
+        ("v_bus_2", I64(data.v_bus_2 as i64)),
+        ("e_pv_all", I64(data.e_pv_all as i64)),
+        ("e_pv_all_1", I64(data.e_pv_all_1 as i64)),
+        ("e_pv_all_2", I64(data.e_pv_all_2 as i64)),
+        ("e_pv_all_3", I64(data.e_pv_all_3 as i64)),
+        ("e_inv_all", I64(data.e_inv_all as i64)),
+        ("e_rec_all", I64(data.e_rec_all as i64)),
+        ("e_chg_all", I64(data.e_chg_all as i64)),
+        ("e_dischg_all", I64(data.e_dischg_all as i64)),
+        ("e_eps_all", I64(data.e_eps_all as i64)),
+        ("e_to_grid_all", I64(data.e_to_grid_all as i64)),
+        ("e_to_user_all", I64(data.e_to_user_all as i64)),
+        ("fault_code", I64(data.fault_code as i64)),
+        ("warning_code", I64(data.warning_code as i64)),
+        ("t_inner", F64(data.t_inner as f64)),
+        ("t_rad_1", F64(data.t_rad_1 as f64)),
+        ("t_rad_2", F64(data.t_rad_2 as f64)),
+        ("t_bat", F64(data.t_bat as f64)),
+        ("runtime", I64(data.runtime as i64)),
+        ("bms_event_1", I64(data.bms_event_1 as i64)),
+        ("bms_event_2", I64(data.bms_event_2 as i64)),
+        ("bms_fw_update_state", I64(data.bms_fw_update_state as i64)),
+        ("cycle_count", I64(data.cycle_count as i64)),
+        ("vbat_inv", I64(data.vbat_inv as i64)),
+        ("datalog", Str(data.datalog.to_string())),
+    ]
+}
+
+/// Backends cap how many bound parameters a single statement may carry -
+/// SQLite at 999, Postgres at 65535 (MySQL has no comparable hard limit, so
+/// it reuses Postgres' figure as a generous ceiling). Dividing by
+/// `binds_per_row` (taken from `row_fields`'s length) gives the largest
+/// batch that can still go out as one `INSERT`.
+fn max_rows_per_statement(database: &DatabaseType, binds_per_row: usize) -> usize {
+    let param_limit = match database {
+        DatabaseType::SQLite => 999,
+        DatabaseType::MySQL | DatabaseType::Postgres => 65535,
+    };
+    (param_limit / binds_per_row).max(1)
+}
+
+/// Transient errors - the pool/connection itself dropped rather than the
+/// query being rejected - are worth rebuilding the pool and retrying for;
+/// anything else (constraint violations, bad SQL, ...) would just fail
+/// again forever and should be dropped instead.
+fn is_transient(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Io(io_err)) => matches!(
+            io_err.kind(),
+            std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+        ),
+        Some(sqlx::Error::PoolClosed) | Some(sqlx::Error::PoolTimedOut) => true,
+        _ => false,
+    }
+}
+
+/// Full-jitter exponential backoff for reconnect attempts: doubles from 1s
+/// up to a 5 minute cap. Mirrors `eg4::inverter::reconnect_delay`'s jitter
+/// approach (this tree has no `rand` dependency, so the sub-second clock
+/// reading is used as a cheap source of randomness) so a fleet of databases
+/// failing together doesn't reconnect in lockstep.
+fn reconnect_backoff(attempt: u32) -> std::time::Duration {
+    let base_ms = 1000u64.saturating_mul(1u64 << attempt.min(9));
+    let capped_ms = base_ms.min(5 * 60 * 1000).max(1);
+    let sample_ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    std::time::Duration::from_millis(sample_ns % capped_ms)
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChannelData {
@@ -11,6 +162,7 @@ pub enum ChannelData {
 
 pub type Sender = broadcast::Sender<ChannelData>;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum DatabaseType {
     MySQL,
     Postgres,
@@ -22,6 +174,9 @@ pub struct Database {
     config: config::Database,
     channels: Channels,
     pool: Arc<RwLock<Option<Pool<Any>>>>,
+    /// Consecutive transient-failure count since the last successful
+    /// insert, driving `reconnect_backoff`'s delay; reset to 0 on success.
+    reconnect_attempt: Arc<AtomicU32>,
 }
 
 impl Database {
@@ -30,6 +185,7 @@ impl Database {
             config,
             channels,
             pool: Arc::new(RwLock::new(None)),
+            reconnect_attempt: Arc::new(AtomicU32::new(0)),
         }
     }
 
@@ -62,17 +218,90 @@ impl Database {
     }
 
     async fn connect(&self) -> Result<()> {
-        let options = AnyConnectOptions::from_str(self.config.url())?;
-        let pool = sqlx::any::AnyPoolOptions::new()
+        let options = self.connect_options()?;
+        let mut pool_options = sqlx::any::AnyPoolOptions::new()
             .max_connections(5)
             .min_connections(1)
-            .acquire_timeout(std::time::Duration::from_secs(30))
-            .connect_with(options)
-            .await?;
+            .acquire_timeout(std::time::Duration::from_secs(30));
+
+        // SQLite defaults to serializing all access, which the batched
+        // writer readily trips over as "database is locked"; WAL mode plus
+        // a busy timeout lets a writer wait out a brief lock instead of
+        // failing immediately. A no-op for MySQL/Postgres - the hook is
+        // simply not installed for them.
+        if self.database()? == DatabaseType::SQLite {
+            let wal = self.config.sqlite_wal();
+            let busy_timeout_ms = self.config.sqlite_busy_timeout_ms();
+            pool_options = pool_options.after_connect(move |conn, _meta| {
+                Box::pin(async move {
+                    conn.execute(&*format!("PRAGMA busy_timeout = {}", busy_timeout_ms)).await?;
+                    if wal {
+                        conn.execute("PRAGMA journal_mode = WAL").await?;
+                    }
+                    conn.execute("PRAGMA synchronous = NORMAL").await?;
+                    conn.execute("PRAGMA foreign_keys = ON").await?;
+                    Ok(())
+                })
+            });
+        }
+
+        let pool = pool_options.connect_with(options).await?;
         *self.pool.write().map_err(|_| anyhow::anyhow!("Failed to acquire write lock"))? = Some(pool);
         Ok(())
     }
 
+    /// Builds the `AnyConnectOptions` to connect with, applying `config.tls`
+    /// via the backend-specific options type for MySQL/Postgres (`AnyConnectOptions`
+    /// itself has no generic SSL knobs) before converting back to `Any`.
+    /// SQLite has no transport to secure, so it's parsed as-is.
+    fn connect_options(&self) -> Result<AnyConnectOptions> {
+        match self.database()? {
+            DatabaseType::SQLite => Ok(AnyConnectOptions::from_str(self.config.url())?),
+            DatabaseType::Postgres => {
+                use sqlx::postgres::{PgConnectOptions, PgSslMode};
+                use std::str::FromStr;
+
+                let tls = self.config.tls();
+                let mut options = PgConnectOptions::from_str(self.config.url())?
+                    .ssl_mode(match tls.mode() {
+                        config::DatabaseTlsMode::Disable => PgSslMode::Disable,
+                        config::DatabaseTlsMode::Prefer => PgSslMode::Prefer,
+                        config::DatabaseTlsMode::Require => PgSslMode::Require,
+                        config::DatabaseTlsMode::VerifyCa => PgSslMode::VerifyCa,
+                        config::DatabaseTlsMode::VerifyFull => PgSslMode::VerifyFull,
+                    });
+                if let Some(ca) = tls.ca_cert_path() {
+                    options = options.ssl_root_cert(ca);
+                }
+                if let (Some(cert), Some(key)) = (tls.client_cert_path(), tls.client_key_path()) {
+                    options = options.ssl_client_cert(cert).ssl_client_key(key);
+                }
+                Ok(AnyConnectOptions::from(options))
+            }
+            DatabaseType::MySQL => {
+                use sqlx::mysql::{MySqlConnectOptions, MySqlSslMode};
+                use std::str::FromStr;
+
+                let tls = self.config.tls();
+                let mut options = MySqlConnectOptions::from_str(self.config.url())?
+                    .ssl_mode(match tls.mode() {
+                        config::DatabaseTlsMode::Disable => MySqlSslMode::Disabled,
+                        config::DatabaseTlsMode::Prefer => MySqlSslMode::Preferred,
+                        config::DatabaseTlsMode::Require => MySqlSslMode::Required,
+                        config::DatabaseTlsMode::VerifyCa => MySqlSslMode::VerifyCa,
+                        config::DatabaseTlsMode::VerifyFull => MySqlSslMode::VerifyIdentity,
+                    });
+                if let Some(ca) = tls.ca_cert_path() {
+                    options = options.ssl_ca(ca);
+                }
+                if let (Some(cert), Some(key)) = (tls.client_cert_path(), tls.client_key_path()) {
+                    options = options.ssl_client_cert(cert).ssl_client_key(key);
+                }
+                Ok(AnyConnectOptions::from(options))
+            }
+        }
+    }
+
     pub async fn connection(&self) -> Result<Pool<Any>> {
         match &*self.pool.read().map_err(|_| anyhow::anyhow!("Failed to acquire read lock"))? {
             Some(pool) => Ok(pool.clone()),
@@ -97,46 +326,41 @@ impl Database {
         Ok(())
     }
 
+    /// Accumulates incoming `ReadInputAll` packets and flushes them as a
+    /// single multi-row `INSERT` once `batch_size` rows are buffered or
+    /// `flush_interval` elapses, whichever comes first - trading a little
+    /// latency for far fewer round-trips under a fleet of inverters. On
+    /// `Shutdown` any partially-filled buffer is flushed before returning so
+    /// no readings are dropped.
     async fn inserter(&self) -> Result<()> {
         let mut receiver = self.channels.to_database.subscribe();
 
         // wait for database to be ready
         self.connect().await?;
 
-        let query = format!(
-            "INSERT INTO inputs ({}) VALUES {}",
-            self.columns(),
-            match self.database()? {
-                DatabaseType::MySQL => Database::values_for_mysql(),
-                _ => Database::values_for_not_mysql(),
-            }
-        );
+        let database = self.database()?;
+        let mut buffer: Vec<Box<eg4::packet::ReadInputAll>> = Vec::with_capacity(self.config.batch_size());
+        let mut interval = tokio::time::interval(self.config.flush_interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
-            use ChannelData::*;
-
-            match receiver.recv().await? {
-                Shutdown => break,
-                ReadInputAll(data) => {
-                    let mut retry_count = 0;
-                    let max_retries = 3;
-                    let mut backoff = 1;
-                    
-                    while retry_count < max_retries {
-                        match self.insert(&query, &data).await {
-                            Ok(_) => break,
-                            Err(err) => {
-                                error!("INSERT failed: {:?} - retrying in {}s", err, backoff);
-                                tokio::time::sleep(std::time::Duration::from_secs(backoff)).await;
-                                retry_count += 1;
-                                backoff *= 2;
+            tokio::select! {
+                msg = crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToDatabase) => {
+                    match msg? {
+                        ChannelData::Shutdown => {
+                            self.flush(database, &mut buffer).await;
+                            break;
+                        }
+                        ChannelData::ReadInputAll(data) => {
+                            buffer.push(data);
+                            if buffer.len() >= self.config.batch_size() {
+                                self.flush(database, &mut buffer).await;
                             }
                         }
                     }
-                    
-                    if retry_count == max_retries {
-                        error!("Failed to insert data after {} retries", max_retries);
-                    }
+                }
+                _ = interval.tick() => {
+                    self.flush(database, &mut buffer).await;
                 }
             }
         }
@@ -144,112 +368,136 @@ impl Database {
         Ok(())
     }
 
-    async fn insert(&self, query: &str, data: &eg4::packet::ReadInputAll) -> Result<()> {
+    /// Drains `buffer`, writing it out as one or more batched `INSERT`s
+    /// (split only if the row count would exceed the backend's bound
+    /// parameter limit). A transient error (the pool/connection itself is
+    /// dead) rebuilds the pool and retries that chunk with backoff forever,
+    /// since the alternative is silently losing readings for as long as the
+    /// outage lasts; a permanent error (e.g. a constraint violation) is
+    /// logged and the chunk is dropped instead of retried.
+    async fn flush(&self, database: DatabaseType, buffer: &mut Vec<Box<eg4::packet::ReadInputAll>>) {
+        if buffer.is_empty() {
+            return;
+        }
+
+        let rows: Vec<_> = buffer.drain(..).collect();
+
+        let binds_per_row = row_fields(&rows[0]).len();
+
+        for chunk in rows.chunks(max_rows_per_statement(&database, binds_per_row)) {
+            loop {
+                match self.insert_batch(database, chunk).await {
+                    Ok(_) => {
+                        self.reconnect_attempt.store(0, Ordering::Relaxed);
+                        break;
+                    }
+                    Err(err) if is_transient(&err) => {
+                        let attempt = self.reconnect_attempt.fetch_add(1, Ordering::Relaxed);
+                        let delay = reconnect_backoff(attempt);
+                        error!("transient database error, reconnecting in {:?}: {:?}", delay, err);
+                        tokio::time::sleep(delay).await;
+                        if let Err(e) = self.connect().await {
+                            error!("failed to reconnect to database: {:?}", e);
+                        }
+                    }
+                    Err(err) => {
+                        error!("permanent error inserting batch of {} row(s), dropping: {:?}", chunk.len(), err);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds and executes a single `INSERT ... VALUES (...), (...), ...`
+    /// covering every row in `chunk`, inside one transaction so a failure
+    /// rolls back the whole batch instead of leaving it partially written.
+    /// The column list, placeholders, and binds all come from `row_fields`,
+    /// so they can't drift out of sync with each other.
+    async fn insert_batch(&self, database: DatabaseType, chunk: &[Box<eg4::packet::ReadInputAll>]) -> Result<()> {
+        let first_row_fields = row_fields(&chunk[0]);
+        let columns: Vec<&str> = first_row_fields.iter().map(|(name, _)| *name).collect();
+
+        let query = format!(
+            "INSERT INTO inputs ({}) VALUES {}",
+            columns.join(", "),
+            Database::placeholders_for_batch(database, chunk.len(), columns.len()),
+        );
+
         let pool = self.connection().await?;
-        let mut conn = pool.acquire().await?;
-
-        // Convert values that might overflow to i64 for SQLite compatibility
-        sqlx::query(query)
-            .bind(data.status as i64)
-            .bind(data.v_pv_1.unwrap_or(0.0) as i64)
-            .bind(data.v_pv_2.unwrap_or(0.0) as i64)
-            .bind(data.v_pv_3.unwrap_or(0.0) as i64)
-            .bind(data.v_bat.unwrap_or(0.0) as i64)
-            .bind(data.soc as i64)
-            .bind(data.soh as i64)
-            .bind(data.internal_fault as i64)
-            .bind(data.p_pv as i64)
-            .bind(data.p_pv_1 as i64)
-            .bind(data.p_pv_2 as i64)
-            .bind(data.p_pv_3 as i64)
-            .bind(data.p_battery as f64)
-            .bind(data.p_charge as i64)
-            .bind(data.p_discharge as i64)
-            .bind(data.v_ac_r as i64)
-            .bind(data.v_ac_s as i64)
-            .bind(data.v_ac_t as i64)
-            .bind(data.f_ac as f64)
-            .bind(data.p_inv as i64)
-            .bind(data.p_rec as i64)
-            .bind(data.pf as f64)
-            .bind(data.v_eps_r as i64)
-            .bind(data.v_eps_s as i64)
-            .bind(data.v_eps_t as i64)
-            .bind(data.f_eps as f64)
-            .bind(data.p_eps as i64)
-            .bind(data.s_eps as i64)
-            .bind(data.p_grid as f64)
-            .bind(data.p_to_grid as i64)
-            .bind(data.p_to_user as i64)
-            .bind(data.e_pv_day as i64)
-            .bind(data.e_pv_day_1 as i64)
-            .bind(data.e_pv_day_2 as i64)
-            .bind(data.e_pv_day_3 as i64)
-            .bind(data.e_inv_day as i64)
-            .bind(data.e_rec_day as i64)
-            .bind(data.e_chg_day as i64)
-            .bind(data.e_dischg_day as i64)
-            .bind(data.e_eps_day as i64)
-            .bind(data.e_to_grid_day as i64)
-            .bind(data.e_to_user_day as i64)
-            .bind(data.v_bus_1 as i64)
-            .bind(data.v_bus_2 as i64)
-            .bind(data.e_pv_all as i64)
-            .bind(data.e_pv_all_1 as i64)
-            .bind(data.e_pv_all_2 as i64)
-            .bind(data.e_pv_all_3 as i64)
-            .bind(data.e_inv_all as i64)
-            .bind(data.e_rec_all as i64)
-            .bind(data.e_chg_all as i64)
-            .bind(data.e_dischg_all as i64)
-            .bind(data.e_eps_all as i64)
-            .bind(data.e_to_grid_all as i64)
-            .bind(data.e_to_user_all as i64)
-            .bind(data.fault_code as i64)
-            .bind(data.warning_code as i64)
-            .bind(data.t_inner as f64)
-            .bind(data.t_rad_1 as f64)
-            .bind(data.t_rad_2 as f64)
-            .bind(data.t_bat as f64)
-            .bind(data.runtime as i64)
-            .bind(data.bms_event_1 as i64)
-            .bind(data.bms_event_2 as i64)
-            .bind(data.bms_fw_update_state as i64)
-            .bind(data.cycle_count as i64)
-            .bind(data.vbat_inv as i64)
-            .bind(data.datalog.to_string())
-            .persistent(true)
-            .execute(&mut *conn)
-            .await?;
+        let mut tx = pool.begin().await?;
+
+        let mut statement = sqlx::query(&query);
+        for data in chunk {
+            for (_, value) in row_fields(data) {
+                statement = value.bind(statement);
+            }
+        }
+        statement.persistent(true).execute(&mut *tx).await?;
+
+        tx.commit().await?;
 
         Ok(())
     }
 
-    fn columns(&self) -> &'static str {
-        "status, v_pv_1, v_pv_2, v_pv_3, v_bat, soc, soh, internal_fault, p_pv, p_pv_1, p_pv_2,
-        p_pv_3, p_battery, p_charge, p_discharge, v_ac_r, v_ac_s, v_ac_t, f_ac, p_inv, p_rec, pf,
-        v_eps_r, v_eps_s, v_eps_t, f_eps, p_eps, s_eps, p_grid, p_to_grid, p_to_user, e_pv_day,
-        e_pv_day_1, e_pv_day_2, e_pv_day_3, e_inv_day, e_rec_day, e_chg_day, e_dischg_day,
-        e_eps_day, e_to_grid_day, e_to_user_day, v_bus_1, v_bus_2, e_pv_all, e_pv_all_1,
-        e_pv_all_2, e_pv_all_3, e_inv_all, e_rec_all, e_chg_all, e_dischg_all, e_eps_all,
-        e_to_grid_all, e_to_user_all, fault_code, warning_code, t_inner, t_rad_1, t_rad_2, t_bat,
-        runtime, bms_event_1, bms_event_2, bms_fw_update_state, cycle_count, vbat_inv, datalog"
-    }
-
-    fn values_for_mysql() -> &'static str {
-        r#"(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
-    }
-
-    fn values_for_not_mysql() -> &'static str {
-        r#"($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
-            $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28,
-            $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42,
-            $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53, $54, $55, $56,
-            $57, $58, $59, $60, $61, $62, $63, $64, $65, $66, $67, $68, $69, $70,
-            $71, $72, $73, $74, $75, $76, $77, $78, $79, $80, $81, $82, $83, $84,
-            $85, $86, $87, $88, $89, $90, $91)"#
+    /// Numbers placeholders across `rows` row-tuples of `binds_per_row`
+    /// fields each, for `database`: MySQL repeats `(?, ?, ...)` groups (its
+    /// driver doesn't support numbered parameters), while Postgres/SQLite
+    /// number continuously across the whole statement (`($1,...,$N),
+    /// ($N+1,...,$2N), ...`) since each bind slot must have a distinct
+    /// index.
+    fn placeholders_for_batch(database: DatabaseType, rows: usize, binds_per_row: usize) -> String {
+        let row_group = |start: usize| -> String {
+            match database {
+                DatabaseType::MySQL => format!("({})", vec!["?"; binds_per_row].join(", ")),
+                DatabaseType::Postgres | DatabaseType::SQLite => {
+                    let placeholders: Vec<String> = (start..start + binds_per_row)
+                        .map(|n| format!("${}", n))
+                        .collect();
+                    format!("({})", placeholders.join(", "))
+                }
+            }
+        };
+
+        (0..rows)
+            .map(|row| row_group(row * binds_per_row + 1))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_match_column_and_bind_count() {
+        // A stand-in for `row_fields(...).len()` without needing to
+        // construct a full `ReadInputAll` - what matters here is that
+        // `placeholders_for_batch` numbers exactly `rows * binds_per_row`
+        // slots, matching however many columns/binds the caller has.
+        let binds_per_row = 68;
+
+        for (database, rows) in [
+            (DatabaseType::Postgres, 3),
+            (DatabaseType::SQLite, 3),
+            (DatabaseType::MySQL, 3),
+        ] {
+            let placeholders = Database::placeholders_for_batch(database, rows, binds_per_row);
+            // Every placeholder but the very first is preceded by a comma -
+            // `rows * binds_per_row - 1` of them in total, whether they're
+            // separating values within a row-group or separating groups.
+            let placeholder_count = placeholders.matches(',').count() + 1;
+            assert_eq!(placeholder_count, rows * binds_per_row);
+            assert_eq!(placeholders.matches('(').count(), rows);
+        }
+    }
+
+    #[test]
+    fn max_rows_per_statement_respects_backend_limits() {
+        assert_eq!(max_rows_per_statement(&DatabaseType::SQLite, 68), 999 / 68);
+        assert_eq!(max_rows_per_statement(&DatabaseType::Postgres, 68), 65535 / 68);
+        // Never rounds down to 0 even for a pathologically wide row.
+        assert_eq!(max_rows_per_statement(&DatabaseType::SQLite, 10_000), 1);
     }
 }
@@ -1,13 +1,40 @@
 use crate::prelude::*;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use log::{info, error, warn};
 use crate::eg4::packet::Packet;
 use crate::eg4::inverter::ChannelData;
 use crate::channels::Channels;
+use crate::coordinator::commands::{parse_hold::parse_hold_register, parse_input::parse_input_register};
+use crate::register::RegisterParser;
+
+/// Controls when `DatalogWriter` rolls `datalog.jsonl` into a gzip-compressed
+/// generation (`datalog.jsonl.1.gz`, `datalog.jsonl.2.gz`, ...).
+#[derive(Debug, Clone, Copy)]
+pub struct RotationConfig {
+    /// Roll once the current segment reaches this many bytes.
+    pub max_size_bytes: u64,
+    /// Roll once the current segment is older than this, regardless of size.
+    /// `None` disables age-based rotation.
+    pub max_age_secs: Option<u64>,
+    /// Number of rotated generations to keep; older ones are deleted.
+    pub max_generations: u32,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        Self {
+            max_size_bytes: 100 * 1024 * 1024,
+            max_age_secs: None,
+            max_generations: 5,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct DatalogWriter {
@@ -15,12 +42,61 @@ pub struct DatalogWriter {
     path: String,
     values_written: Arc<Mutex<u64>>,
     channels: Arc<Channels>,
+    register_parser: Option<Arc<RegisterParser>>,
+    rotation: RotationConfig,
+    // Size and age of the *current* segment, reset on every roll. Kept
+    // separate from `values_written`, which is a cumulative, never-reset total.
+    segment_bytes: Arc<Mutex<u64>>,
+    segment_opened_at: Arc<Mutex<Instant>>,
 }
 
 impl DatalogWriter {
     pub fn new(path: &str, channels: Arc<Channels>) -> Result<Self> {
+        Self::new_with_register_file(path, channels, None)
+    }
+
+    /// Same as `new`, but also decodes raw values via `register_file` (a
+    /// `RegisterParser`-compatible JSON definition) when one is given,
+    /// storing the typed result alongside the raw hex dump.
+    pub fn new_with_register_file(path: &str, channels: Arc<Channels>, register_file: Option<&str>) -> Result<Self> {
+        Self::new_with_rotation(path, channels, register_file, RotationConfig::default())
+    }
+
+    /// Same as `new_with_register_file`, but also takes the rotation policy
+    /// (size/age thresholds and generation count) instead of the defaults.
+    pub fn new_with_rotation(
+        path: &str,
+        channels: Arc<Channels>,
+        register_file: Option<&str>,
+        rotation: RotationConfig,
+    ) -> Result<Self> {
         info!("Opening datalog file at {}", path);
-        
+
+        let file = Self::open_segment(path)?;
+        let segment_bytes = file.metadata()?.len();
+
+        let register_parser = register_file.and_then(|f| {
+            RegisterParser::new(f)
+                .map_err(|e| error!("Failed to load register file {} for datalog decoding: {}", f, e))
+                .ok()
+        }).map(Arc::new);
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+            path: path.to_string(),
+            values_written: Arc::new(Mutex::new(0)),
+            channels,
+            register_parser,
+            rotation,
+            segment_bytes: Arc::new(Mutex::new(segment_bytes)),
+            segment_opened_at: Arc::new(Mutex::new(Instant::now())),
+        })
+    }
+
+    /// Opens (creating if necessary) the datalog file in append mode with
+    /// 0644 permissions, creating its parent directory if needed. Used both
+    /// for the initial open and to reopen a fresh segment after a roll.
+    fn open_segment(path: &str) -> Result<std::fs::File> {
         // Ensure the directory exists
         if let Some(parent) = Path::new(path).parent() {
             std::fs::create_dir_all(parent)?;
@@ -50,13 +126,38 @@ impl DatalogWriter {
         }
 
         info!("Successfully opened datalog file with permissions 0644");
+        Ok(file)
+    }
 
-        Ok(Self {
-            file: Arc::new(Mutex::new(file)),
-            path: path.to_string(),
-            values_written: Arc::new(Mutex::new(0)),
-            channels,
-        })
+    /// Gzip-compresses the current segment into generation 1
+    /// (`<path>.1.gz`), shifting older generations up and dropping any past
+    /// `rotation.max_generations`, then reopens a fresh segment at `path`.
+    fn rotate(&self) -> Result<std::fs::File> {
+        for gen in (1..self.rotation.max_generations).rev() {
+            let src = format!("{}.{}.gz", self.path, gen);
+            let dst = format!("{}.{}.gz", self.path, gen + 1);
+            if Path::new(&src).exists() {
+                std::fs::rename(&src, &dst)?;
+            }
+        }
+
+        let oldest = format!("{}.{}.gz", self.path, self.rotation.max_generations + 1);
+        if Path::new(&oldest).exists() {
+            std::fs::remove_file(&oldest)?;
+        }
+
+        let rotated_gz = format!("{}.1.gz", self.path);
+        {
+            let mut input = std::fs::File::open(&self.path)?;
+            let output = std::fs::File::create(&rotated_gz)?;
+            let mut encoder = GzEncoder::new(output, Compression::default());
+            std::io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?;
+        }
+        std::fs::remove_file(&self.path)?;
+
+        info!("Rotated datalog file {} into {}", self.path, rotated_gz);
+        Self::open_segment(&self.path)
     }
 
     pub fn write_hold_data(&self, serial: Serial, datalog: Serial, data: &[(u16, u16)]) -> Result<()> {
@@ -92,6 +193,27 @@ impl DatalogWriter {
         }
         json_data.insert("raw_data".to_string(), serde_json::Value::Object(raw_data));
 
+        if let Some(parser) = &self.register_parser {
+            let mut decoded_data = serde_json::Map::new();
+            for (register, value) in data {
+                let decoded = if register_type == "hold" {
+                    parse_hold_register(*register, *value as u32, parser)
+                } else {
+                    parse_input_register(*register, *value as u32, parser)
+                };
+
+                if let Some(decoded) = decoded {
+                    decoded_data.insert(decoded.name, serde_json::json!({
+                        "value": decoded.value,
+                        "unit": decoded.unit,
+                    }));
+                }
+            }
+            if !decoded_data.is_empty() {
+                json_data.insert("decoded".to_string(), serde_json::Value::Object(decoded_data));
+            }
+        }
+
         let json_value = serde_json::Value::Object(json_data);
         let json_string = serde_json::to_string(&json_value)?;
         
@@ -102,13 +224,30 @@ impl DatalogWriter {
                     error!("Failed to flush datalog file {}: {}", self.path, e);
                     return Err(e.into());
                 }
-                
+
                 // Update and log the number of values written
                 let mut values_written = self.values_written.lock().map_err(|_| anyhow::anyhow!("Failed to lock values counter"))?;
                 *values_written += data.len() as u64;
-                info!("Successfully wrote {} registers to datalog file for inverter {} (datalog {}). Total values stored: {}", 
+                info!("Successfully wrote {} registers to datalog file for inverter {} (datalog {}). Total values stored: {}",
                     data.len(), serial, datalog, *values_written);
-                
+
+                // Roll the file if it's grown past its size/age limit, still
+                // under `file`'s lock so concurrent writers stay consistent.
+                let mut segment_bytes = self.segment_bytes.lock().map_err(|_| anyhow::anyhow!("Failed to lock segment byte counter"))?;
+                *segment_bytes += json_string.len() as u64 + 1;
+
+                let mut segment_opened_at = self.segment_opened_at.lock().map_err(|_| anyhow::anyhow!("Failed to lock segment age counter"))?;
+                let age_exceeded = self
+                    .rotation
+                    .max_age_secs
+                    .is_some_and(|max| segment_opened_at.elapsed().as_secs() >= max);
+
+                if *segment_bytes >= self.rotation.max_size_bytes || age_exceeded {
+                    *file = self.rotate()?;
+                    *segment_bytes = 0;
+                    *segment_opened_at = Instant::now();
+                }
+
                 Ok(())
             },
             Err(e) => {
@@ -3,14 +3,13 @@ use crate::eg4::packet::{Packet, TcpFrameFactory, WriteParam, ReadParam};
 use crate::eg4::packet_decoder::PacketDecoder;
 
 use {
-    async_trait::async_trait,
     serde::{Serialize, Serializer},
-    tokio::io::{AsyncReadExt, AsyncWriteExt},
+    tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     std::time::Duration,
     net2::TcpStreamExt,
     std::sync::{Arc, Mutex},
     std::time::{Instant, SystemTime},
-    std::sync::atomic::{AtomicU64, Ordering},
+    std::sync::atomic::{AtomicU32, AtomicU64, Ordering},
 };
 
 use crate::coordinator::PacketStats;
@@ -24,79 +23,19 @@ pub enum ChannelData {
     Heartbeat(Packet),
     ModbusError(config::Inverter, u8, crate::eg4::packet::ModbusError),
     SerialMismatch(config::Inverter, Serial, Serial),
+    /// A `TimeSync` drift measurement (inverter clock minus system clock),
+    /// published before any correction is applied so it can be logged or
+    /// graphed as a metric.
+    TimeSyncDrift(Serial, chrono::Duration),
 }
 pub type Sender = broadcast::Sender<ChannelData>;
 pub type Receiver = broadcast::Receiver<ChannelData>;
 
-// WaitForReply {{{
-#[async_trait]
-pub trait WaitForReply {
-    #[cfg(not(feature = "mocks"))]
-    const TIMEOUT: u64 = 30;
-
-    #[cfg(feature = "mocks")]
-    const TIMEOUT: u64 = 0; // fail immediately in tests
-
-    async fn wait_for_reply(&mut self, packet: &Packet) -> Result<Packet>;
-}
-#[async_trait]
-impl WaitForReply for Receiver {
-    async fn wait_for_reply(&mut self, packet: &Packet) -> Result<Packet> {
-        let start = std::time::Instant::now();
-        let timeout_duration = std::time::Duration::from_secs(Self::TIMEOUT);
-
-        loop {
-            if start.elapsed() >= timeout_duration {
-                bail!("Timeout waiting for reply to {:?} after {} seconds", packet, Self::TIMEOUT);
-            }
-
-            match (packet, self.try_recv()) {
-                (
-                    Packet::TranslatedData(td),
-                    Ok(ChannelData::Packet(Packet::TranslatedData(reply))),
-                ) => {
-                    if td.datalog == reply.datalog
-                        && td.register == reply.register
-                        && td.device_function == reply.device_function
-                    {
-                        return Ok(Packet::TranslatedData(reply));
-                    }
-                }
-                (Packet::ReadParam(rp), Ok(ChannelData::Packet(Packet::ReadParam(reply)))) => {
-                    if rp.datalog == reply.datalog && rp.register == reply.register {
-                        return Ok(Packet::ReadParam(reply));
-                    }
-                }
-                (Packet::WriteParam(wp), Ok(ChannelData::Packet(Packet::WriteParam(reply)))) => {
-                    if wp.datalog == reply.datalog && wp.register == reply.register {
-                        return Ok(Packet::WriteParam(reply));
-                    }
-                }
-                (Packet::Heartbeat(hb), Ok(ChannelData::Packet(Packet::Heartbeat(reply)))) => {
-                    if hb.datalog == reply.datalog {
-                        return Ok(Packet::Heartbeat(reply));
-                    }
-                }
-                (_, Ok(ChannelData::Packet(_))) => {} // Mismatched packet, continue waiting
-                (_, Ok(ChannelData::Heartbeat(_))) => { info!("heartbeat_rx from") } // Heartbeat received, continue waiting
-                (_, Ok(ChannelData::Connected(_))) => {} // Connection status update, continue waiting
-                (_, Ok(ChannelData::Disconnect(inverter_datalog))) => {
-                    if inverter_datalog == packet.datalog() {
-                        bail!("Inverter {} disconnected while waiting for reply", inverter_datalog);
-                    }
-                }
-                (_, Ok(ChannelData::Shutdown)) => bail!("Channel shutdown received while waiting for reply"),
-                (_, Ok(ChannelData::ModbusError(_, _, _))) => {} // Modbus error, continue waiting
-                (_, Ok(ChannelData::SerialMismatch(_, _, _))) => {} // Serial mismatch, continue waiting
-                (_, Err(broadcast::error::TryRecvError::Empty)) => {
-                    // Channel empty, sleep briefly before retrying
-                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
-                }
-                (_, Err(err)) => bail!("Channel error while waiting for reply: {:?}", err),
-            }
-        }
-    }
-} // }}}
+/// Either a plain TCP stream half or a TLS-wrapped one, boxed so `connect`
+/// can hand `sender`/`inverter_periodic_reader` the same type regardless of
+/// which transport was negotiated.
+type BoxedReader = Box<dyn AsyncRead + Unpin + Send>;
+type BoxedWriter = Box<dyn AsyncWrite + Unpin + Send>;
 
 // Serial {{{
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -223,12 +162,102 @@ pub struct Inverter {
     channels: Channels,
     shared_stats: Arc<Mutex<PacketStats>>,
     message_timestamps: Arc<MessageTimestamps>,
+    connected: Arc<std::sync::atomic::AtomicBool>,
+    /// The live sender/receiver task handles, so `stop()` can wait for them
+    /// to actually finish (flushing in-flight writes, draining the decoder
+    /// on EOF) instead of just sleeping and hoping. An async mutex, since
+    /// `supervise()` holds it for as long as the connection stays up.
+    task_handles: Arc<tokio::sync::Mutex<Vec<tokio::task::JoinHandle<Result<()>>>>>,
+    /// Set by `stop()` so `supervise()` treats a subsequent task exit as the
+    /// requested shutdown rather than a dropped connection to reconnect.
+    shutting_down: Arc<std::sync::atomic::AtomicBool>,
+    /// Number of reconnect cycles in a row that haven't yet reached a stable
+    /// connection (reset to 0 by `supervise()`'s own `reconnect_reset_secs`
+    /// logic, same as the backoff delay it drives), so a caller can query
+    /// link health (e.g. a Home Assistant availability/diagnostic topic)
+    /// without reaching into backoff internals.
+    consecutive_failures: Arc<AtomicU32>,
+    /// `AbortHandle`s for the live sender/receiver tasks, mirrored from
+    /// `task_handles` every time `supervise()` installs a new pair. Kept
+    /// behind a plain (non-async) `Mutex` specifically so `force_reset()`
+    /// never has to contend with `task_handles`'s async lock, which
+    /// `supervise()` holds for as long as the connection stays up.
+    abort_handles: Arc<Mutex<Vec<tokio::task::AbortHandle>>>,
 }
 
-const READ_TIMEOUT_SECS: u64 = 1; // Multiplier for read_timeout from config
-const WRITE_TIMEOUT_SECS: u64 = 5; // Timeout for write operations
-const RECONNECT_DELAY_SECS: u64 = 5; // Delay before reconnection attempts
-const TCP_KEEPALIVE_SECS: u64 = 60; // TCP keepalive interval
+/// Full-jitter exponential backoff: picks uniformly at random between 0 and
+/// `base_secs` doubled each attempt, capped at `max_secs`, so a fleet of
+/// inverters that drop together doesn't reconnect in lockstep. `base_secs`/
+/// `max_secs` come from the inverter's `reconnect_base_delay_secs()`/
+/// `reconnect_max_delay_secs()` config.
+fn reconnect_delay(attempt: u32, base_secs: u64, max_secs: u64) -> Duration {
+    let base = base_secs.saturating_mul(1u64 << attempt.min(6));
+    let capped_ms = base.min(max_secs).saturating_mul(1000).max(1);
+    // No `rand` dependency in this tree, so use the sub-second clock reading
+    // as a cheap source of randomness for the jitter sample.
+    let sample_ns = SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    Duration::from_millis(sample_ns % capped_ms)
+}
+
+/// Wraps a connected TCP stream in TLS per `tls`'s settings: trusts
+/// `ca_cert_path` if set, falling back to the platform's native root store;
+/// presents a client certificate when both `client_cert_path` and
+/// `client_key_path` are set; verifies the peer against `tls.server_name()`
+/// (or `host` if unset). `connect`'s `reader`/`writer` are already boxed
+/// `AsyncRead`/`AsyncWrite` trait objects specifically so this can sit
+/// underneath them transparently — `sender`/`receiver` don't know or care
+/// whether the link is plaintext or TLS.
+async fn wrap_tls(
+    stream: tokio::net::TcpStream,
+    tls: &config::InverterTls,
+    host: &str,
+) -> Result<tokio_rustls::client::TlsStream<tokio::net::TcpStream>> {
+    let mut roots = rustls::RootCertStore::empty();
+    if let Some(path) = tls.ca_cert_path() {
+        let pem = std::fs::read(path)
+            .map_err(|e| anyhow!("failed to read TLS ca_cert_path {}: {}", path, e))?;
+        for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+            roots.add(cert?)?;
+        }
+    } else {
+        for cert in rustls_native_certs::load_native_certs()? {
+            roots.add(cert)?;
+        }
+    }
+
+    let builder = rustls::ClientConfig::builder().with_root_certificates(roots);
+
+    let client_config = match (tls.client_cert_path(), tls.client_key_path()) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert_pem = std::fs::read(cert_path)
+                .map_err(|e| anyhow!("failed to read TLS client_cert_path {}: {}", cert_path, e))?;
+            let certs = rustls_pemfile::certs(&mut cert_pem.as_slice())
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let key_pem = std::fs::read(key_path)
+                .map_err(|e| anyhow!("failed to read TLS client_key_path {}: {}", key_path, e))?;
+            let key = rustls_pemfile::private_key(&mut key_pem.as_slice())?
+                .ok_or_else(|| anyhow!("no private key found in {}", key_path))?;
+
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| anyhow!("invalid TLS client certificate/key: {}", e))?
+        }
+        _ => builder.with_no_client_auth(),
+    };
+
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(client_config));
+    let server_name = rustls::pki_types::ServerName::try_from(tls.server_name(host).to_owned())
+        .map_err(|_| anyhow!("invalid TLS server name {:?}", tls.server_name(host)))?;
+
+    connector
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| anyhow!("TLS handshake with {} failed: {}", host, e))
+}
 
 impl Inverter {
     pub fn new(config: ConfigWrapper, inverter: &config::Inverter, channels: Channels) -> Self {
@@ -275,6 +304,11 @@ impl Inverter {
             channels,
             shared_stats: Arc::new(Mutex::new(PacketStats::default())),
             message_timestamps,
+            connected: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            task_handles: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            shutting_down: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            consecutive_failures: Arc::new(AtomicU32::new(0)),
+            abort_handles: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -285,57 +319,239 @@ impl Inverter {
             channels,
             shared_stats,
             message_timestamps: Arc::new(MessageTimestamps::new()),
+            connected: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            task_handles: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            shutting_down: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            consecutive_failures: Arc::new(AtomicU32::new(0)),
+            abort_handles: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Whether the inverter's TCP link is currently up. Flips to `false` the
+    /// moment the sender or receiver task exits (error, idle timeout, or EOF)
+    /// and back to `true` once the supervisor's reconnect succeeds; useful
+    /// for surfacing link health (e.g. a Home Assistant availability topic).
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    /// Number of reconnect cycles in a row that haven't yet reached a stable
+    /// connection. Zero means either currently connected and stable, or not
+    /// yet attempted a reconnect since the last stable connection.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::SeqCst)
+    }
+
     pub fn config(&self) -> config::Inverter {
         self.config
             .inverter_with_host(&self.host)
             .expect("can't find my inverter")
     }
 
+    /// Proactively tears down the live connection by aborting its
+    /// sender/receiver tasks, rather than waiting for the OS to notice a
+    /// half-open socket. `supervise()`'s select is already awaiting these
+    /// same tasks, so an abort here is picked up exactly like an ordinary
+    /// dropped connection: it broadcasts `ChannelData::Disconnect` and
+    /// reconnects with backoff. Used by the coordinator when consecutive
+    /// modbus/serial errors suggest the link is wedged even though the
+    /// socket itself hasn't died. A no-op if the connection isn't currently
+    /// up (nothing to abort).
+    pub fn force_reset(&self) {
+        let datalog = self.config().datalog().map(|s| s.to_string()).unwrap_or_default();
+        let handles = self.abort_handles.lock().expect("abort_handles mutex poisoned");
+        if handles.is_empty() {
+            debug!("inverter {}: force_reset requested but no live connection to reset", datalog);
+            return;
+        }
+        warn!("inverter {}: forcing socket reset after sustained modbus/serial errors", datalog);
+        for handle in handles.iter() {
+            handle.abort();
+        }
+    }
+
     pub async fn start(&self) -> Result<()> {
         let config = self.config();
         let datalog = config.datalog().map(|s| s.to_string()).unwrap_or_default();
         let host = config.host();
         let port = config.port();
         debug!("Starting inverter {} at {}:{}", datalog, host, port);
-        
-        let mut attempt = 1;
-        while let Err(e) = self.connect().await {
-            error!("inverter {}: Connection attempt {} failed: {}", datalog, attempt, e);
-            debug!(
-                "inverter {}: Connection attempt {} failed with error: {:?}", 
-                datalog, 
-                attempt, 
-                e
-            );
-            info!(
-                "inverter {}: reconnecting in {}s (attempt {})", 
-                datalog,
-                RECONNECT_DELAY_SECS,
-                attempt
-            );
-            tokio::time::sleep(std::time::Duration::from_secs(RECONNECT_DELAY_SECS)).await;
-            attempt += 1;
-        }
+
+        let (sender_handle, receiver_handle) = self
+            .connect_with_backoff(0)
+            .await
+            .ok_or_else(|| anyhow!("inverter {}: failed to establish initial connection", datalog))?;
+        self.connected.store(true, Ordering::SeqCst);
 
         debug!("inverter {}: Successfully established connection at {}:{}", datalog, host, port);
         info!("inverter {}: Successfully started and connected", datalog);
+
+        // Hand the live connection off to a supervisor that watches for the
+        // sender/receiver tasks dying (read/write error, idle timeout, EOF)
+        // and transparently reconnects with backoff, rather than leaving the
+        // inverter silently disconnected for the rest of the process.
+        let inverter = self.clone();
+        tokio::spawn(async move {
+            inverter.supervise(sender_handle, receiver_handle).await;
+        });
+
         Ok(())
     }
 
+    /// Retries `connect()` with full-jitter exponential backoff, starting
+    /// the attempt count at `start_attempt` so a supervisor can carry a
+    /// flapping connection's backoff forward across reconnect cycles.
+    /// Returns `None` once `reconnect_max_attempts` is exhausted (unset
+    /// means retry forever).
+    async fn connect_with_backoff(&self, start_attempt: u32) -> Option<(tokio::task::JoinHandle<Result<()>>, tokio::task::JoinHandle<Result<()>>)> {
+        let config = self.config();
+        let datalog = config.datalog().map(|s| s.to_string()).unwrap_or_default();
+        let max_attempts = config.reconnect_max_attempts();
+        let mut attempt = start_attempt;
+
+        loop {
+            if self.shutting_down.load(Ordering::SeqCst) {
+                debug!("inverter {}: abandoning reconnect, shutdown requested", datalog);
+                return None;
+            }
+
+            match self.connect().await {
+                Ok(handles) => return Some(handles),
+                Err(e) => {
+                    error!("inverter {}: connection attempt {} failed: {}", datalog, attempt + 1, e);
+
+                    if let Some(max) = max_attempts {
+                        if attempt + 1 >= max {
+                            error!("inverter {}: giving up after {} failed reconnect attempt(s)", datalog, attempt + 1);
+                            return None;
+                        }
+                    }
+
+                    let delay = reconnect_delay(attempt, config.reconnect_base_delay_secs(), config.reconnect_max_delay_secs());
+                    info!("inverter {}: reconnecting in {:?} (attempt {})", datalog, delay, attempt + 1);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Watches the sender/receiver tasks for a live connection; when either
+    /// one exits, marks the inverter disconnected, broadcasts
+    /// `ChannelData::Disconnect` so the dispatcher fails in-flight requests
+    /// fast instead of leaving them to hang until their timeout, and
+    /// reconnects with backoff before resuming supervision of the new
+    /// connection. The backoff attempt count carries across reconnect
+    /// cycles (so a flapping link keeps backing off instead of retrying at
+    /// full speed every time), and only resets to zero once a connection
+    /// has stayed up past `reconnect_reset_secs`. If `reconnect_max_attempts`
+    /// is set and exhausted, supervision stops and the inverter is left
+    /// disconnected. Exits quietly, without reconnecting, once `stop()` has
+    /// set `shutting_down` - that task exit is the requested shutdown, not a
+    /// dropped link.
+    async fn supervise(
+        &self,
+        sender_handle: tokio::task::JoinHandle<Result<()>>,
+        receiver_handle: tokio::task::JoinHandle<Result<()>>,
+    ) {
+        let datalog = self.config().datalog().expect("datalog must be set");
+        let mut cycle_attempt: u32 = 0;
+        let mut connected_at = Instant::now();
+
+        *self.abort_handles.lock().expect("abort_handles mutex poisoned") =
+            vec![sender_handle.abort_handle(), receiver_handle.abort_handle()];
+        *self.task_handles.lock().await = vec![sender_handle, receiver_handle];
+
+        loop {
+            {
+                // Held only for the select, so `stop()` can take the
+                // handles the moment one of them actually exits; never
+                // cleared out from under it, so a concurrent `stop()` always
+                // sees the current (live or just-finished) pair.
+                let mut handles = self.task_handles.lock().await;
+                tokio::select! {
+                    _ = &mut handles[0] => {}
+                    _ = &mut handles[1] => {}
+                }
+            }
+
+            if self.shutting_down.load(Ordering::SeqCst) {
+                debug!("inverter {}: supervisor exiting for shutdown", datalog);
+                self.connected.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            {
+                let handles = self.task_handles.lock().await;
+                for handle in handles.iter() {
+                    handle.abort();
+                }
+            }
+            self.connected.store(false, Ordering::SeqCst);
+
+            if connected_at.elapsed() >= Duration::from_secs(self.config().reconnect_reset_secs()) {
+                cycle_attempt = 0;
+                self.consecutive_failures.store(0, Ordering::SeqCst);
+            }
+
+            warn!("inverter {}: connection lost (attempt {}), reconnecting", datalog, cycle_attempt + 1);
+            if let Err(e) = self.channels.from_inverter.send(ChannelData::Disconnect(datalog)) {
+                warn!("inverter {}: failed to broadcast disconnect: {}", datalog, e);
+            }
+
+            match self.connect_with_backoff(cycle_attempt).await {
+                Some((new_sender, new_receiver)) => {
+                    *self.abort_handles.lock().expect("abort_handles mutex poisoned") =
+                        vec![new_sender.abort_handle(), new_receiver.abort_handle()];
+                    *self.task_handles.lock().await = vec![new_sender, new_receiver];
+                    connected_at = Instant::now();
+                    cycle_attempt += 1;
+                    self.consecutive_failures.store(cycle_attempt, Ordering::SeqCst);
+                    self.connected.store(true, Ordering::SeqCst);
+                    if let Ok(mut stats) = self.shared_stats.lock() {
+                        stats.reconnects += 1;
+                    }
+                    info!("inverter {}: reconnected", datalog);
+                }
+                None => {
+                    error!("inverter {}: exhausted reconnect attempts, leaving disconnected", datalog);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Sends a shutdown signal to the sender/receiver tasks and waits for
+    /// them to actually exit (flushing in-flight writes, draining the
+    /// decoder on EOF) before returning, aborting only if they don't finish
+    /// within a few seconds.
     pub async fn stop(&self) {
-        info!("Stopping inverter {}...", self.config().datalog().map(|s| s.to_string()).unwrap_or_default());
-        
-        // Send shutdown signal
+        let datalog = self.config().datalog().map(|s| s.to_string()).unwrap_or_default();
+        info!("Stopping inverter {}...", datalog);
+
+        self.shutting_down.store(true, Ordering::SeqCst);
         let _ = self.channels.to_inverter.send(ChannelData::Shutdown);
-        
-        // Give tasks time to process shutdown
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        let handles = std::mem::take(&mut *self.task_handles.lock().await);
+        const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+        for mut handle in handles {
+            match tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut handle).await {
+                Ok(Ok(Ok(()))) => debug!("inverter {}: task exited cleanly", datalog),
+                Ok(Ok(Err(e))) => warn!("inverter {}: task exited with error: {}", datalog, e),
+                Ok(Err(e)) => warn!("inverter {}: task panicked: {}", datalog, e),
+                Err(_) => {
+                    warn!("inverter {}: task didn't exit within {:?}, aborting", datalog, SHUTDOWN_TIMEOUT);
+                    handle.abort();
+                }
+            }
+        }
     }
 
-    pub async fn connect(&self) -> Result<()> {
+    /// Opens the TCP connection and spawns its sender/receiver tasks,
+    /// returning their handles so a caller (the reconnect supervisor) can
+    /// detect when the link dies.
+    async fn connect(&self) -> Result<(tokio::task::JoinHandle<Result<()>>, tokio::task::JoinHandle<Result<()>>)> {
         debug!("Starting connect method for inverter at {}:{}", self.host, self.config().port());
         let inverter_config = self.config();
         debug!(
@@ -349,45 +565,47 @@ impl Inverter {
         debug!("Resolved host and port: {:?}", inverter_hp);
 
         // Attempt TCP connection with timeout
-        debug!("Attempting TCP connection with {}s timeout", WRITE_TIMEOUT_SECS * 2);
+        let connect_timeout_secs = inverter_config.tcp_connect_timeout_secs();
+        debug!("Attempting TCP connection with {}s timeout", connect_timeout_secs);
         let stream = match tokio::time::timeout(
-            Duration::from_secs(WRITE_TIMEOUT_SECS * 2),
+            Duration::from_secs(connect_timeout_secs),
             tokio::net::TcpStream::connect(inverter_hp)
         ).await {
             Ok(Ok(stream)) => {
-                debug!("TCP connection successfully established to {}:{}", 
-                    inverter_config.host(), 
+                debug!("TCP connection successfully established to {}:{}",
+                    inverter_config.host(),
                     inverter_config.port()
                 );
                 stream
             },
             Ok(Err(e)) => {
-                error!("Failed to connect to inverter at {}:{}: {}", 
-                    inverter_config.host(), 
-                    inverter_config.port(), 
+                error!("Failed to connect to inverter at {}:{}: {}",
+                    inverter_config.host(),
+                    inverter_config.port(),
                     e
                 );
                 debug!("Detailed TCP connection error: {:?}", e);
                 bail!("Failed to connect to inverter: {}", e);
             },
             Err(_) => {
-                error!("Connection timeout after {} seconds when connecting to {}:{}", 
-                    WRITE_TIMEOUT_SECS * 2,
+                error!("Connection timeout after {} seconds when connecting to {}:{}",
+                    connect_timeout_secs,
                     inverter_config.host(),
                     inverter_config.port()
                 );
-                bail!("Connection timeout after {} seconds", WRITE_TIMEOUT_SECS * 2);
+                bail!("Connection timeout after {} seconds", connect_timeout_secs);
             },
         };
 
         // Configure TCP socket
         debug!("Configuring TCP socket options");
+        let tcp_keepalive_secs = inverter_config.tcp_keepalive_secs();
         let std_stream = stream.into_std()?;
-        if let Err(e) = std_stream.set_keepalive(Some(Duration::new(TCP_KEEPALIVE_SECS, 0))) {
+        if let Err(e) = std_stream.set_keepalive(Some(Duration::new(tcp_keepalive_secs, 0))) {
             warn!("Failed to set TCP keepalive: {}", e);
             debug!("Detailed TCP keepalive error: {:?}", e);
         } else {
-            debug!("TCP keepalive set to {} seconds", TCP_KEEPALIVE_SECS);
+            debug!("TCP keepalive set to {} seconds", tcp_keepalive_secs);
         }
         
         let stream = tokio::net::TcpStream::from_std(std_stream)?;
@@ -404,7 +622,17 @@ impl Inverter {
 
         debug!("TCP socket configuration complete");
 
-        let (reader, writer) = stream.into_split();
+        let (reader, writer): (BoxedReader, BoxedWriter) = if inverter_config.tls().enabled() {
+            debug!("Wrapping TCP stream in TLS for {}", inverter_config.host());
+            let tls_stream = wrap_tls(stream, inverter_config.tls(), inverter_config.host())
+                .await
+                .map_err(|e| anyhow!("TLS setup failed for inverter {}: {}", inverter_config.host(), e))?;
+            let (reader, writer) = tokio::io::split(tls_stream);
+            (Box::new(reader), Box::new(writer))
+        } else {
+            let (reader, writer) = stream.into_split();
+            (Box::new(reader), Box::new(writer))
+        };
         debug!("TCP stream split into reader and writer");
 
         // Clone necessary parts for the tasks
@@ -418,26 +646,39 @@ impl Inverter {
         let receiver_host = self.host.clone();
         let sender_timestamps = self.message_timestamps.clone();
         let receiver_timestamps = self.message_timestamps.clone();
+        let sender_task_handles = self.task_handles.clone();
+        let receiver_task_handles = self.task_handles.clone();
+        let sender_shutting_down = self.shutting_down.clone();
+        let receiver_shutting_down = self.shutting_down.clone();
+
+        let connected_flag = self.connected.clone();
 
         // Start sender and receiver tasks
-        let _sender_handle = tokio::spawn(async move {
+        let sender_handle = tokio::spawn(async move {
             let inverter = Inverter {
                 config: sender_config,
                 host: sender_host,
                 channels: sender_channels,
                 shared_stats: sender_stats,
                 message_timestamps: sender_timestamps,
+                connected: connected_flag,
+                task_handles: sender_task_handles,
+                shutting_down: sender_shutting_down,
             };
             inverter.sender(writer).await
         });
 
-        let _receiver_handle = tokio::spawn(async move {
+        let connected_flag = self.connected.clone();
+        let receiver_handle = tokio::spawn(async move {
             let inverter = Inverter {
                 config: receiver_config,
                 host: receiver_host,
                 channels: receiver_channels,
                 shared_stats: receiver_stats,
                 message_timestamps: receiver_timestamps,
+                connected: connected_flag,
+                task_handles: receiver_task_handles,
+                shutting_down: receiver_shutting_down,
             };
             inverter.inverter_periodic_reader(reader).await
         });
@@ -472,14 +713,14 @@ impl Inverter {
                 inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default());
         }
 
-        // Store the task handles in the inverter for later use if needed
         debug!("Both sender and receiver tasks started successfully");
-        Ok(())
+        Ok((sender_handle, receiver_handle))
     }
 
-    async fn sender(&self, mut writer: tokio::net::tcp::OwnedWriteHalf) -> Result<()> {
+    async fn sender(&self, mut writer: BoxedWriter) -> Result<()> {
         let mut receiver = self.channels.to_inverter.subscribe();
         let inverter_config = self.config();
+        let write_timeout_secs = inverter_config.write_timeout_secs();
         let frame_factory = TcpFrameFactory::new(inverter_config.datalog().expect("datalog must be set"));
 
         loop {
@@ -494,10 +735,14 @@ impl Inverter {
 
                             // Use timeout for write operations
                             match tokio::time::timeout(
-                                Duration::from_secs(WRITE_TIMEOUT_SECS),
+                                Duration::from_secs(write_timeout_secs),
                                 writer.write_all(&bytes)
                             ).await {
                                 Ok(Ok(_)) => {
+                                    if let Ok(mut stats) = self.shared_stats.lock() {
+                                        stats.bytes_sent += bytes.len() as u64;
+                                    }
+
                                     // Log packet details only after successful write
                                     match &packet {
                                         Packet::Heartbeat(hb) => {
@@ -519,11 +764,24 @@ impl Inverter {
                                     
                                     // Ensure data is actually sent
                                     if let Err(_e) = writer.flush().await {
+                                        if let Ok(mut stats) = self.shared_stats.lock() {
+                                            stats.write_errors += 1;
+                                        }
                                         bail!("Failed to write to socket for {}", inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default());
                                     }
                                 }
-                                Ok(Err(_e)) => bail!("Failed to write packet for {}", inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default()),
-                                Err(_) => bail!("Write timeout after {} seconds for {}", WRITE_TIMEOUT_SECS, inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default()),
+                                Ok(Err(_e)) => {
+                                    if let Ok(mut stats) = self.shared_stats.lock() {
+                                        stats.write_errors += 1;
+                                    }
+                                    bail!("Failed to write packet for {}", inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default());
+                                }
+                                Err(_) => {
+                                    if let Ok(mut stats) = self.shared_stats.lock() {
+                                        stats.write_errors += 1;
+                                    }
+                                    bail!("Write timeout after {} seconds for {}", write_timeout_secs, inverter_config.datalog().map(|s| s.to_string()).unwrap_or_default());
+                                }
                             }
                         }
                         ChannelData::Shutdown => {
@@ -547,7 +805,7 @@ impl Inverter {
         Ok(())
     }
 
-    async fn inverter_periodic_reader(&self, mut socket: tokio::net::tcp::OwnedReadHalf) -> Result<()> {
+    async fn inverter_periodic_reader(&self, mut socket: BoxedReader) -> Result<()> {
         use std::time::Duration;
         use tokio::time::timeout;
         use {bytes::BytesMut, tokio_util::codec::Decoder};
@@ -591,7 +849,7 @@ impl Inverter {
                 read_result = async {
                     if inverter_config.read_timeout() > 0 {
                         timeout(
-                            Duration::from_secs(inverter_config.read_timeout() * READ_TIMEOUT_SECS),
+                            Duration::from_secs(inverter_config.read_timeout()),
                             socket.read_buf(&mut buf)
                         ).await
                     } else {
@@ -600,10 +858,24 @@ impl Inverter {
                 } => {
                     let len = match read_result {
                         Ok(Ok(n)) => n,
-                        Ok(Err(_e)) => bail!("Read error"),
-                        Err(_) => bail!("No data received for {} seconds", inverter_config.read_timeout() * READ_TIMEOUT_SECS),
+                        Ok(Err(_e)) => {
+                            if let Ok(mut stats) = self.shared_stats.lock() {
+                                stats.read_errors += 1;
+                            }
+                            bail!("Read error")
+                        }
+                        Err(_) => {
+                            if let Ok(mut stats) = self.shared_stats.lock() {
+                                stats.read_errors += 1;
+                            }
+                            bail!("No data received for {} seconds", inverter_config.read_timeout())
+                        }
                     };
 
+                    if let Ok(mut stats) = self.shared_stats.lock() {
+                        stats.bytes_received += len as u64;
+                    }
+
                     if len == 0 {
                         // Try to process any remaining data before disconnecting
                         while let Some(packet) = decoder.decode_eof(&mut buf)? {
@@ -654,6 +926,7 @@ impl Inverter {
                         // Track received packet
                         if let Ok(mut stats) = self.shared_stats.lock() {
                             stats.packets_received += 1;
+                            stats.packets_decoded += 1;
                             match &packet {
                                 Packet::Heartbeat(_) => stats.heartbeat_packets_received += 1,
                                 Packet::TranslatedData(_) => stats.translated_data_packets_received += 1,
@@ -0,0 +1,3 @@
+pub mod inverter;
+pub mod packet;
+pub mod packet_decoder;
@@ -0,0 +1,4355 @@
+use crate::prelude::*;
+
+use enum_dispatch::*;
+use nom_derive::{Nom, Parse};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::Serialize;
+use log::error;
+use std::convert::TryFrom;
+
+#[derive(Clone, Debug)]
+pub enum ReadInput {
+    ReadInputAll(Box<ReadInputAll>),
+    ReadInput1(ReadInput1),
+    ReadInput2(ReadInput2),
+    ReadInput3(ReadInput3),
+    ReadInput4(ReadInput4),
+    ReadInput5(ReadInput5),
+    ReadInput6(ReadInput6),
+}
+
+// {{{ status flag decoding
+//
+// `fault_code`/`warning_code`/`bms_event_1`/`bms_event_2` are bitmaps; the
+// tables below name every bit we know about so `decode_flags()` can turn
+// them into readable labels instead of leaving callers to decode the raw
+// integer by hand. This mirrors the NUT UPS driver idiom of walking a
+// status table and committing whichever flags are set (`status_init()` /
+// `status_set("OL")` / `status_commit()`), just against our own bitmap
+// instead of a `status` accumulator string.
+//
+// Bit positions are best-effort from field reports against LuxPower/EG4
+// hybrid inverters; reserved or not-yet-identified bits still surface as
+// `unknown_bit_N` rather than being silently dropped.
+pub const FAULT_BITS: &[(u32, &str)] = &[
+    (1 << 0, "bat_voltage_high"),
+    (1 << 1, "bat_voltage_low"),
+    (1 << 2, "bus_voltage_high"),
+    (1 << 3, "bus_voltage_low"),
+    (1 << 4, "over_current"),
+    (1 << 5, "over_temperature"),
+    (1 << 6, "grid_voltage_high"),
+    (1 << 7, "grid_voltage_low"),
+    (1 << 8, "grid_frequency_high"),
+    (1 << 9, "grid_frequency_low"),
+    (1 << 10, "pv_voltage_high"),
+    (1 << 11, "isolation_fault"),
+    (1 << 12, "dc_injection_high"),
+    (1 << 13, "gfci_fault"),
+    (1 << 14, "relay_fault"),
+    (1 << 15, "fan_fault"),
+    (1 << 16, "eeprom_fault"),
+    (1 << 17, "bat_disconnected"),
+    (1 << 18, "over_load"),
+    (1 << 19, "neutral_fault"),
+    (1 << 20, "parallel_comm_fault"),
+    (1 << 21, "ac_input_fault"),
+];
+
+pub const WARNING_BITS: &[(u32, &str)] = &[
+    (1 << 0, "fan_stuck"),
+    (1 << 1, "over_temperature"),
+    (1 << 2, "bat_voltage_high"),
+    (1 << 3, "bat_voltage_low"),
+    (1 << 4, "bat_open"),
+    (1 << 5, "bat_low_soc"),
+    (1 << 6, "pv_voltage_high"),
+    (1 << 7, "over_load"),
+    (1 << 8, "eeprom_fault"),
+    (1 << 9, "rtc_fault"),
+    (1 << 10, "input_consistent_fault"),
+    (1 << 11, "ac_over_current"),
+    (1 << 12, "communication_fault"),
+    (1 << 13, "reserved_13"),
+    (1 << 14, "fan_warning"),
+    (1 << 15, "parallel_num_out_of_range"),
+];
+
+pub const BMS_FAULT_BITS: &[(u16, &str)] = &[
+    (1 << 0, "cell_over_voltage"),
+    (1 << 1, "cell_under_voltage"),
+    (1 << 2, "pack_over_voltage"),
+    (1 << 3, "pack_under_voltage"),
+    (1 << 4, "charge_over_current"),
+    (1 << 5, "discharge_over_current"),
+    (1 << 6, "over_temperature"),
+    (1 << 7, "under_temperature"),
+    (1 << 8, "cell_imbalance"),
+    (1 << 9, "short_circuit"),
+];
+
+pub const BMS_WARNING_BITS: &[(u16, &str)] = &[
+    (1 << 0, "cell_voltage_high"),
+    (1 << 1, "cell_voltage_low"),
+    (1 << 2, "pack_voltage_high"),
+    (1 << 3, "pack_voltage_low"),
+    (1 << 4, "charge_current_high"),
+    (1 << 5, "discharge_current_high"),
+    (1 << 6, "temperature_high"),
+    (1 << 7, "temperature_low"),
+    (1 << 8, "low_soc"),
+    (1 << 9, "communication_fault"),
+    (BMS_WATCHDOG_TIMER_EXPIRE_BIT, "watchdog_timer_expire"),
+    (BMS_SAFETY_TIMER_EXPIRE_BIT, "safety_timer_expire"),
+];
+
+/// `bms_event_2` bit driving `BatteryHealth::WatchdogTimerExpire`.
+const BMS_WATCHDOG_TIMER_EXPIRE_BIT: u16 = 1 << 10;
+/// `bms_event_2` bit driving `BatteryHealth::SafetyTimerExpire`.
+const BMS_SAFETY_TIMER_EXPIRE_BIT: u16 = 1 << 11;
+
+/// Walks `code` against every bit in `table`, collecting the label for each
+/// set bit it recognizes. Any set bit the table doesn't name still shows up
+/// as `"unknown_bit_N"` so nothing is silently lost.
+fn decode_status_bits<T>(code: T, table: &[(T, &str)]) -> Vec<String>
+where
+    T: Copy + Into<u64> + std::ops::BitAnd<Output = T> + PartialEq + Default,
+{
+    let mut flags = Vec::new();
+    let mut known: u64 = 0;
+
+    for &(mask, label) in table {
+        known |= mask.into();
+        if code & mask != T::default() {
+            flags.push(label.to_string());
+        }
+    }
+
+    let code: u64 = code.into();
+    let bits = std::mem::size_of::<T>() * 8;
+    for bit in 0..bits {
+        let mask = 1u64 << bit;
+        if code & mask != 0 && known & mask == 0 {
+            flags.push(format!("unknown_bit_{}", bit));
+        }
+    }
+
+    flags
+}
+
+/// Decoded view of a `fault_code`/`warning_code` pair, alongside their raw
+/// values so neither representation is lost.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusReport {
+    pub fault_code: u32,
+    pub fault_flags: Vec<String>,
+    pub warning_code: u32,
+    pub warning_flags: Vec<String>,
+}
+
+/// Decoded view of a `bms_event_1`/`bms_event_2` pair (the BMS's own
+/// fault/warning words, distinct from the inverter's `fault_code`/
+/// `warning_code`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BmsStatusReport {
+    pub bms_event_1: u16,
+    pub bms_fault_flags: Vec<String>,
+    pub bms_event_2: u16,
+    pub bms_warning_flags: Vec<String>,
+}
+
+/// Non-fatal battery condition, mirroring the Linux kernel's
+/// `power_supply` health states (`POWER_SUPPLY_HEALTH_*`). Unlike
+/// `ReadInputAll::validate()`, which only rejects out-of-range packets,
+/// this is meant to be published alongside normal telemetry so dashboards
+/// can alert on it without re-implementing the threshold logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryHealth {
+    Good,
+    Overheat,
+    Cold,
+    Dead,
+    OverVoltage,
+    UnderVoltage,
+    NoBattery,
+    WatchdogTimerExpire,
+    SafetyTimerExpire,
+    CellImbalance,
+    Unknown,
+}
+
+/// `BatteryHealth` plus the specific reasons driving it, decoded from
+/// `fault_code`/`warning_code` and `bms_event_1`/`bms_event_2` the same way
+/// `ReadInputAll::decode_flags`/`decode_bms_flags` do, so downstream
+/// consumers get a normalized verdict without re-decoding vendor
+/// bitfields themselves.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatteryHealthReport {
+    pub health: BatteryHealth,
+    pub reasons: Vec<String>,
+}
+
+/// Thresholds driving `BatteryHealth` classification. Cell voltage limits
+/// come from the packet itself (`charge_volt_ref`/`dischg_cut_volt`); only
+/// the temperature and cycle-count limits need configuring here.
+#[derive(Debug, Clone, Copy)]
+pub struct BatteryHealthThresholds {
+    pub max_cell_temp_c: f64,
+    pub min_cell_temp_c: f64,
+    pub max_cycle_count: u16,
+}
+
+impl Default for BatteryHealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_cell_temp_c: 55.0,
+            min_cell_temp_c: -10.0,
+            max_cycle_count: 6000,
+        }
+    }
+}
+
+/// Shared by `ReadInputAll::health`/`ReadInput3::health`, since both
+/// structs carry the same battery-status fields.
+#[allow(clippy::too_many_arguments)]
+fn classify_battery_health(
+    bat_count: u16,
+    bat_capacity: u16,
+    max_cell_temp: f64,
+    min_cell_temp: f64,
+    max_cell_voltage: f64,
+    min_cell_voltage: f64,
+    charge_volt_ref: f64,
+    dischg_cut_volt: f64,
+    cycle_count: u16,
+    bms_event_2: u16,
+    thresholds: &BatteryHealthThresholds,
+) -> BatteryHealth {
+    if bat_count == 0 || bat_capacity == 0 {
+        return BatteryHealth::NoBattery;
+    }
+    if bms_event_2 & BMS_WATCHDOG_TIMER_EXPIRE_BIT != 0 {
+        return BatteryHealth::WatchdogTimerExpire;
+    }
+    if bms_event_2 & BMS_SAFETY_TIMER_EXPIRE_BIT != 0 {
+        return BatteryHealth::SafetyTimerExpire;
+    }
+    if cycle_count >= thresholds.max_cycle_count {
+        return BatteryHealth::Dead;
+    }
+    if max_cell_temp > thresholds.max_cell_temp_c {
+        return BatteryHealth::Overheat;
+    }
+    if min_cell_temp < thresholds.min_cell_temp_c {
+        return BatteryHealth::Cold;
+    }
+    if max_cell_voltage <= 0.0 && min_cell_voltage <= 0.0 {
+        return BatteryHealth::Unknown;
+    }
+    if max_cell_voltage > charge_volt_ref {
+        return BatteryHealth::OverVoltage;
+    }
+    if min_cell_voltage < dischg_cut_volt {
+        return BatteryHealth::UnderVoltage;
+    }
+
+    BatteryHealth::Good
+}
+
+/// Like `classify_battery_health`, but also decodes `fault_code`/
+/// `warning_code`/`bms_event_1`/`bms_event_2` into a list of reasons, and
+/// adds a cell-imbalance check (`max_cell_voltage - min_cell_voltage`
+/// against `imbalance_threshold_v`) that `classify_battery_health` doesn't
+/// do, since that's judged separately from the rest of the status bits.
+#[allow(clippy::too_many_arguments)]
+fn classify_battery_health_report(
+    bat_count: u16,
+    bat_capacity: u16,
+    max_cell_temp: f64,
+    min_cell_temp: f64,
+    max_cell_voltage: f64,
+    min_cell_voltage: f64,
+    charge_volt_ref: f64,
+    dischg_cut_volt: f64,
+    cycle_count: u16,
+    fault_code: u32,
+    warning_code: u32,
+    bms_event_1: u16,
+    bms_event_2: u16,
+    imbalance_threshold_v: f64,
+    thresholds: &BatteryHealthThresholds,
+) -> BatteryHealthReport {
+    let mut health = classify_battery_health(
+        bat_count,
+        bat_capacity,
+        max_cell_temp,
+        min_cell_temp,
+        max_cell_voltage,
+        min_cell_voltage,
+        charge_volt_ref,
+        dischg_cut_volt,
+        cycle_count,
+        bms_event_2,
+        thresholds,
+    );
+
+    let mut reasons = Vec::new();
+    reasons.extend(decode_status_bits(fault_code, FAULT_BITS));
+    reasons.extend(decode_status_bits(warning_code, WARNING_BITS));
+    reasons.extend(decode_status_bits(bms_event_1, BMS_FAULT_BITS));
+    reasons.extend(decode_status_bits(bms_event_2, BMS_WARNING_BITS));
+
+    let cell_voltage_delta = max_cell_voltage - min_cell_voltage;
+    if cell_voltage_delta > imbalance_threshold_v {
+        reasons.push(format!(
+            "cell_imbalance: {:.3}V spread exceeds {:.3}V threshold",
+            cell_voltage_delta, imbalance_threshold_v
+        ));
+        if health == BatteryHealth::Good {
+            health = BatteryHealth::CellImbalance;
+        }
+    }
+
+    BatteryHealthReport { health, reasons }
+}
+// }}}
+
+// {{{ per-module battery tracking
+//
+// `ReadInput3` parses four parallel battery modules/strings
+// (`v_bat_1..4`/`i_bat_1..4`/`t_bat_1..4`/`soc_1..4`/`soh_1..4`) but they'd
+// otherwise stay flattened scalars. `BatteryModule` groups one string's
+// telemetry together, the way multi-cell fuel-gauge drivers track
+// per-cell state instead of only the pack aggregate, so individual
+// strings can be watched for divergence/aging.
+
+/// One parallel battery module/string's telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BatteryModule {
+    /// 0-based string index (0..=3).
+    pub index: usize,
+    pub v_bat: f64,
+    pub i_bat: f64,
+    pub t_bat: f64,
+    pub soc: u8,
+    pub soh: u8,
+}
+
+/// Builds the list of present battery modules from the four parallel
+/// fields `ReadInput3` parses, skipping any module whose fields are all
+/// zero (an absent string rather than a real reading of zero everywhere).
+pub fn battery_modules(
+    v_bat: [f64; 4],
+    i_bat: [f64; 4],
+    t_bat: [f64; 4],
+    soc: [u8; 4],
+    soh: [u8; 4],
+) -> Vec<BatteryModule> {
+    (0..4)
+        .filter(|&idx| {
+            v_bat[idx] != 0.0 || i_bat[idx] != 0.0 || t_bat[idx] != 0.0 || soc[idx] != 0 || soh[idx] != 0
+        })
+        .map(|idx| BatteryModule {
+            index: idx,
+            v_bat: v_bat[idx],
+            i_bat: i_bat[idx],
+            t_bat: t_bat[idx],
+            soc: soc[idx],
+            soh: soh[idx],
+        })
+        .collect()
+}
+
+/// Per-pack summary derived from `battery_modules`: the weakest module's
+/// SOH, the widest inter-module SOC/temperature spread, and which module
+/// is the outlier (furthest from the average SOC of the others). `None`
+/// fields mean fewer than two modules were present to compare.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BatteryModuleStats {
+    pub worst_module_soh: Option<u8>,
+    pub max_module_soc_spread: Option<u8>,
+    pub max_module_temp_spread: Option<f64>,
+    pub outlier_module_index: Option<usize>,
+}
+
+/// Computes `BatteryModuleStats` for a set of modules already built by
+/// `battery_modules`.
+pub fn battery_module_stats(modules: &[BatteryModule]) -> BatteryModuleStats {
+    let worst_module_soh = modules.iter().map(|m| m.soh).min();
+
+    if modules.len() < 2 {
+        return BatteryModuleStats {
+            worst_module_soh,
+            ..Default::default()
+        };
+    }
+
+    let min_soc = modules.iter().map(|m| m.soc).min().unwrap();
+    let max_soc = modules.iter().map(|m| m.soc).max().unwrap();
+    let min_temp = modules
+        .iter()
+        .map(|m| m.t_bat)
+        .fold(f64::INFINITY, f64::min);
+    let max_temp = modules
+        .iter()
+        .map(|m| m.t_bat)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let mean_soc = modules.iter().map(|m| m.soc as f64).sum::<f64>() / modules.len() as f64;
+    let outlier_module_index = modules
+        .iter()
+        .max_by(|a, b| {
+            (a.soc as f64 - mean_soc)
+                .abs()
+                .total_cmp(&(b.soc as f64 - mean_soc).abs())
+        })
+        .map(|m| m.index);
+
+    BatteryModuleStats {
+        worst_module_soh,
+        max_module_soc_spread: Some(max_soc - min_soc),
+        max_module_temp_spread: Some(Utils::round(max_temp - min_temp, 1)),
+        outlier_module_index,
+    }
+}
+// }}}
+
+// {{{ field units/metadata
+//
+// `Utils::le_u16_div*` parse attributes scale raw register words into
+// engineering values, but the unit itself (volts vs amps vs percent) only
+// lives in field names and doc comments. `field_units()` below gives output
+// layers (Home Assistant discovery, InfluxDB tagging, ...) a single static
+// table to read instead of each one hard-coding its own name->unit mapping.
+
+/// Physical unit of a decoded field, for output layers that need to attach
+/// units/descriptions automatically instead of hard-coding their own map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    Volt,
+    Ampere,
+    Watt,
+    VoltAmpere,
+    Hertz,
+    KilowattHour,
+    Celsius,
+    Percent,
+    Second,
+    Milliohm,
+    Dimensionless,
+}
+
+/// `(field_name, unit, human_description)` for every field of
+/// `ReadInputAll`, in declaration order. `time`/`datalog` are bookkeeping
+/// fields, not decoded telemetry, and are omitted.
+pub const READ_INPUT_ALL_FIELD_UNITS: &[(&str, Unit, &str)] = &[
+    ("status", Unit::Dimensionless, "Inverter status code"),
+    ("v_pv_1", Unit::Volt, "PV string 1 voltage"),
+    ("v_pv_2", Unit::Volt, "PV string 2 voltage"),
+    ("v_pv_3", Unit::Volt, "PV string 3 voltage"),
+    ("v_bat", Unit::Volt, "Battery voltage"),
+    ("soc", Unit::Percent, "State of charge, as reported by the BMS"),
+    ("soh", Unit::Percent, "State of health, as reported by the BMS"),
+    ("internal_fault", Unit::Dimensionless, "Internal fault bitmask"),
+    ("p_pv", Unit::Watt, "Total PV input power"),
+    ("p_pv_1", Unit::Watt, "PV string 1 input power"),
+    ("p_pv_2", Unit::Watt, "PV string 2 input power"),
+    ("p_pv_3", Unit::Watt, "PV string 3 input power"),
+    ("p_battery", Unit::Watt, "Net battery power (charge minus discharge)"),
+    ("p_charge", Unit::Watt, "Battery charge power"),
+    ("p_discharge", Unit::Watt, "Battery discharge power"),
+    ("v_ac_r", Unit::Volt, "AC grid voltage, phase R"),
+    ("v_ac_s", Unit::Volt, "AC grid voltage, phase S"),
+    ("v_ac_t", Unit::Volt, "AC grid voltage, phase T"),
+    ("f_ac", Unit::Hertz, "AC grid frequency"),
+    ("p_inv", Unit::Watt, "Inverter output power"),
+    ("p_rec", Unit::Watt, "Rectifier input power"),
+    ("pf", Unit::Dimensionless, "Power factor"),
+    ("v_eps_r", Unit::Volt, "EPS output voltage, phase R"),
+    ("v_eps_s", Unit::Volt, "EPS output voltage, phase S"),
+    ("v_eps_t", Unit::Volt, "EPS output voltage, phase T"),
+    ("f_eps", Unit::Hertz, "EPS output frequency"),
+    ("p_eps", Unit::Watt, "EPS output power"),
+    ("s_eps", Unit::VoltAmpere, "EPS apparent power"),
+    ("p_grid", Unit::Watt, "Net grid power (import minus export)"),
+    ("p_to_grid", Unit::Watt, "Power exported to grid"),
+    ("p_to_user", Unit::Watt, "Power imported from grid"),
+    ("e_pv_day", Unit::KilowattHour, "Total PV energy generated today"),
+    ("e_pv_day_1", Unit::KilowattHour, "PV string 1 energy generated today"),
+    ("e_pv_day_2", Unit::KilowattHour, "PV string 2 energy generated today"),
+    ("e_pv_day_3", Unit::KilowattHour, "PV string 3 energy generated today"),
+    ("e_inv_day", Unit::KilowattHour, "Inverter output energy today"),
+    ("e_rec_day", Unit::KilowattHour, "Rectifier input energy today"),
+    ("e_chg_day", Unit::KilowattHour, "Battery charge energy today"),
+    ("e_dischg_day", Unit::KilowattHour, "Battery discharge energy today"),
+    ("e_eps_day", Unit::KilowattHour, "EPS output energy today"),
+    ("e_to_grid_day", Unit::KilowattHour, "Energy exported to grid today"),
+    ("e_to_user_day", Unit::KilowattHour, "Energy imported from grid today"),
+    ("v_bus_1", Unit::Volt, "DC bus 1 voltage"),
+    ("v_bus_2", Unit::Volt, "DC bus 2 voltage"),
+    ("e_pv_all", Unit::KilowattHour, "Total lifetime PV energy generated"),
+    ("e_pv_all_1", Unit::KilowattHour, "PV string 1 lifetime energy generated"),
+    ("e_pv_all_2", Unit::KilowattHour, "PV string 2 lifetime energy generated"),
+    ("e_pv_all_3", Unit::KilowattHour, "PV string 3 lifetime energy generated"),
+    ("e_inv_all", Unit::KilowattHour, "Inverter lifetime output energy"),
+    ("e_rec_all", Unit::KilowattHour, "Rectifier lifetime input energy"),
+    ("e_chg_all", Unit::KilowattHour, "Battery lifetime charge energy"),
+    ("e_dischg_all", Unit::KilowattHour, "Battery lifetime discharge energy"),
+    ("e_eps_all", Unit::KilowattHour, "EPS lifetime output energy"),
+    ("e_to_grid_all", Unit::KilowattHour, "Lifetime energy exported to grid"),
+    ("e_to_user_all", Unit::KilowattHour, "Lifetime energy imported from grid"),
+    ("fault_code", Unit::Dimensionless, "Inverter fault bitmask (see decode_flags)"),
+    ("warning_code", Unit::Dimensionless, "Inverter warning bitmask (see decode_flags)"),
+    ("t_inner", Unit::Celsius, "Internal ambient temperature"),
+    ("t_rad_1", Unit::Celsius, "Heatsink 1 temperature"),
+    ("t_rad_2", Unit::Celsius, "Heatsink 2 temperature"),
+    ("t_bat", Unit::Celsius, "Battery temperature"),
+    ("runtime", Unit::Second, "Inverter uptime"),
+    ("max_chg_curr", Unit::Ampere, "Maximum charge current"),
+    ("max_dischg_curr", Unit::Ampere, "Maximum discharge current"),
+    ("charge_volt_ref", Unit::Volt, "Charge voltage reference"),
+    ("dischg_cut_volt", Unit::Volt, "Discharge cut-off voltage"),
+    ("bat_status_0", Unit::Dimensionless, "Battery status word 0"),
+    ("bat_status_1", Unit::Dimensionless, "Battery status word 1"),
+    ("bat_status_2", Unit::Dimensionless, "Battery status word 2"),
+    ("bat_status_3", Unit::Dimensionless, "Battery status word 3"),
+    ("bat_status_4", Unit::Dimensionless, "Battery status word 4"),
+    ("bat_status_5", Unit::Dimensionless, "Battery status word 5"),
+    ("bat_status_6", Unit::Dimensionless, "Battery status word 6"),
+    ("bat_status_7", Unit::Dimensionless, "Battery status word 7"),
+    ("bat_status_8", Unit::Dimensionless, "Battery status word 8"),
+    ("bat_status_9", Unit::Dimensionless, "Battery status word 9"),
+    ("bat_status_inv", Unit::Dimensionless, "Battery inverter status word"),
+    ("bat_count", Unit::Dimensionless, "Number of battery packs reporting in"),
+    ("bat_capacity", Unit::Dimensionless, "Rated battery capacity"),
+    ("bat_current", Unit::Ampere, "Battery current (negative on discharge)"),
+    ("bms_event_1", Unit::Dimensionless, "BMS fault bitmask (see decode_bms_flags)"),
+    ("bms_event_2", Unit::Dimensionless, "BMS warning bitmask (see decode_bms_flags)"),
+    ("max_cell_voltage", Unit::Volt, "Highest individual cell voltage"),
+    ("min_cell_voltage", Unit::Volt, "Lowest individual cell voltage"),
+    ("max_cell_temp", Unit::Celsius, "Highest individual cell temperature"),
+    ("min_cell_temp", Unit::Celsius, "Lowest individual cell temperature"),
+    ("cell_voltage_delta", Unit::Volt, "max_cell_voltage minus min_cell_voltage"),
+    ("cell_temp_delta", Unit::Celsius, "max_cell_temp minus min_cell_temp"),
+    ("cell_imbalance_alarm", Unit::Dimensionless, "Set when cell_voltage_delta exceeds the imbalance threshold"),
+    ("t_bat_1", Unit::Celsius, "Battery module 1 temperature"),
+    ("t_bat_2", Unit::Celsius, "Battery module 2 temperature"),
+    ("t_bat_3", Unit::Celsius, "Battery module 3 temperature"),
+    ("t_bat_4", Unit::Celsius, "Battery module 4 temperature"),
+    ("v_bat_1", Unit::Volt, "Battery module 1 voltage"),
+    ("v_bat_2", Unit::Volt, "Battery module 2 voltage"),
+    ("v_bat_3", Unit::Volt, "Battery module 3 voltage"),
+    ("v_bat_4", Unit::Volt, "Battery module 4 voltage"),
+    ("i_bat_1", Unit::Ampere, "Battery module 1 current"),
+    ("i_bat_2", Unit::Ampere, "Battery module 2 current"),
+    ("i_bat_3", Unit::Ampere, "Battery module 3 current"),
+    ("i_bat_4", Unit::Ampere, "Battery module 4 current"),
+    ("soc_1", Unit::Percent, "Battery module 1 SOC"),
+    ("soc_2", Unit::Percent, "Battery module 2 SOC"),
+    ("soc_3", Unit::Percent, "Battery module 3 SOC"),
+    ("soc_4", Unit::Percent, "Battery module 4 SOC"),
+    ("soh_1", Unit::Percent, "Battery module 1 SOH"),
+    ("soh_2", Unit::Percent, "Battery module 2 SOH"),
+    ("soh_3", Unit::Percent, "Battery module 3 SOH"),
+    ("soh_4", Unit::Percent, "Battery module 4 SOH"),
+    ("battery_modules", Unit::Dimensionless, "Present battery modules, grouped per-string (see BatteryModule)"),
+    ("worst_module_soh", Unit::Percent, "Weakest battery module's SOH"),
+    ("max_module_soc_spread", Unit::Percent, "Widest inter-module SOC spread"),
+    ("max_module_temp_spread", Unit::Celsius, "Widest inter-module temperature spread"),
+    ("outlier_module_index", Unit::Dimensionless, "Index of the module furthest from the pack's average SOC"),
+    ("bms_fw_update_state", Unit::Dimensionless, "BMS firmware update state"),
+    ("cycle_count", Unit::Dimensionless, "Battery charge/discharge cycle count"),
+    ("vbat_inv", Unit::Volt, "Inverter's own measurement of battery voltage"),
+    ("gen_status", Unit::Dimensionless, "Generator status code"),
+    ("gen_power_factor", Unit::Watt, "Generator power factor"),
+    ("gen_current", Unit::Ampere, "Generator output current"),
+    ("gen_power_limit", Unit::Watt, "Generator power limit setting"),
+    ("gen_connect_status", Unit::Dimensionless, "Generator connection status"),
+    ("gen_control_mode", Unit::Dimensionless, "Generator control mode"),
+    ("gen_dispatch_mode", Unit::Dimensionless, "Generator dispatch mode"),
+    ("v_bus_half", Unit::Volt, "Half DC bus voltage"),
+    ("v_gen", Unit::Volt, "Generator voltage"),
+    ("f_gen", Unit::Hertz, "Generator frequency"),
+    ("p_gen", Unit::Watt, "Generator power"),
+    ("e_gen_day", Unit::KilowattHour, "Generator energy today"),
+    ("e_gen_all", Unit::KilowattHour, "Generator lifetime energy"),
+    ("v_eps_l1", Unit::Volt, "Split-phase EPS voltage, L1"),
+    ("v_eps_l2", Unit::Volt, "Split-phase EPS voltage, L2"),
+    ("p_eps_l1", Unit::Watt, "Split-phase EPS power, L1"),
+    ("p_eps_l2", Unit::Watt, "Split-phase EPS power, L2"),
+    ("s_eps_l1", Unit::VoltAmpere, "Split-phase EPS apparent power, L1"),
+    ("s_eps_l2", Unit::VoltAmpere, "Split-phase EPS apparent power, L2"),
+    ("e_eps_l1_day", Unit::KilowattHour, "Split-phase EPS energy today, L1"),
+    ("e_eps_l2_day", Unit::KilowattHour, "Split-phase EPS energy today, L2"),
+    ("e_eps_l1_all", Unit::KilowattHour, "Split-phase EPS lifetime energy, L1"),
+    ("e_eps_l2_all", Unit::KilowattHour, "Split-phase EPS lifetime energy, L2"),
+    ("i_eps_l1", Unit::Ampere, "Split-phase EPS current, L1"),
+    ("i_eps_l2", Unit::Ampere, "Split-phase EPS current, L2"),
+    ("pf_eps_l1", Unit::Dimensionless, "Split-phase EPS power factor, L1"),
+    ("pf_eps_l2", Unit::Dimensionless, "Split-phase EPS power factor, L2"),
+    ("f_eps_l1", Unit::Hertz, "Split-phase EPS frequency, L1"),
+    ("f_eps_l2", Unit::Hertz, "Split-phase EPS frequency, L2"),
+    ("bat_cell_count", Unit::Dimensionless, "Number of battery cells in series"),
+    ("bat_parallel_count", Unit::Dimensionless, "Number of parallel battery strings"),
+    ("under_freq_start", Unit::Hertz, "Under-frequency load-shed start point"),
+    ("under_freq_end", Unit::Hertz, "Under-frequency load-shed end point"),
+    ("under_freq_slope", Unit::Hertz, "Under-frequency load-shed power slope"),
+    ("max_compensation", Unit::Dimensionless, "Maximum frequency/voltage ride-through compensation"),
+    ("chg_power_pct", Unit::Percent, "Charge power limit"),
+    ("dischg_power_pct", Unit::Percent, "Discharge power limit"),
+    ("ac_charge_pct", Unit::Percent, "AC charge power limit"),
+    ("chg_priority_pct", Unit::Percent, "Charge priority SOC threshold"),
+    ("forced_dischg_pct", Unit::Percent, "Forced discharge power limit"),
+    ("inv_power_pct", Unit::Percent, "Inverter output power limit"),
+    ("ac_chg_start_v", Unit::Volt, "AC charging starting battery voltage"),
+    ("ac_chg_end_v", Unit::Volt, "AC charging cut-off battery voltage"),
+    ("ac_chg_start_soc", Unit::Percent, "AC charging starting SOC"),
+    ("ac_chg_end_soc", Unit::Percent, "AC charging stop SOC"),
+    ("bat_low_v", Unit::Volt, "Battery undervoltage alarm point"),
+    ("bat_low_back_v", Unit::Volt, "Battery undervoltage alarm recovery point"),
+    ("bat_low_soc", Unit::Percent, "Battery undervoltage alarm point (SOC)"),
+    ("bat_low_back_soc", Unit::Percent, "Battery undervoltage alarm recovery point (SOC)"),
+    ("bat_low_utility_v", Unit::Volt, "Battery voltage to switch to mains point"),
+    ("bat_low_utility_soc", Unit::Percent, "Battery SOC to switch to mains point"),
+    ("ac_chg_curr", Unit::Ampere, "AC charge current limit"),
+    ("ongrid_eod_v", Unit::Volt, "On-grid end-of-discharge voltage"),
+    ("soc_volt1", Unit::Volt, "SOC curve voltage point 1"),
+    ("soc_volt2", Unit::Volt, "SOC curve voltage point 2"),
+    ("soc_pct1", Unit::Percent, "SOC curve percentage point 1"),
+    ("soc_pct2", Unit::Percent, "SOC curve percentage point 2"),
+    ("soc_inner_resistance", Unit::Milliohm, "SOC curve internal resistance"),
+    ("soc_ocv", Unit::Percent, "Open-circuit-voltage SOC estimate (see calculate_soc_from_ocv)"),
+    ("soc_estimated", Unit::Percent, "SOC estimated by coulomb-counting battery current (see CoulombCounter)"),
+    ("soc_drift", Unit::Percent, "soc_estimated minus the BMS-reported SOC"),
+    ("v_bat_avg", Unit::Volt, "Moving average of battery voltage"),
+    ("bat_current_avg", Unit::Ampere, "Moving average of battery current"),
+    ("t_bat_avg", Unit::Celsius, "Moving average of battery temperature"),
+    ("soc_avg", Unit::Percent, "Moving average of BMS-reported SOC"),
+    ("max_grid_input_power", Unit::Watt, "Maximum grid input power"),
+    ("gen_rated_power", Unit::Watt, "Generator rated power"),
+    ("function_bit_flags", Unit::Dimensionless, "Combined function-enable bitmask"),
+    ("afci_threshold", Unit::Dimensionless, "AFCI arc-fault detection threshold"),
+    ("volt_watt_v1", Unit::Volt, "Volt-watt curve voltage point 1"),
+    ("volt_watt_v2", Unit::Volt, "Volt-watt curve voltage point 2"),
+    ("volt_watt_delay", Unit::Second, "Volt-watt response delay"),
+    ("volt_watt_p2", Unit::Volt, "Volt-watt curve power point 2"),
+    ("grid_voltage_high_pure", Unit::Volt, "Grid overvoltage limit"),
+    ("grid_voltage_low_pure", Unit::Volt, "Grid undervoltage limit"),
+    ("grid_freq_high_pure", Unit::Hertz, "Grid over-frequency limit"),
+    ("grid_freq_low_pure", Unit::Hertz, "Grid under-frequency limit"),
+    ("grid_volt_high_delay", Unit::Second, "Grid overvoltage trip delay"),
+    ("grid_volt_low_delay", Unit::Second, "Grid undervoltage trip delay"),
+    ("grid_freq_high_delay", Unit::Second, "Grid over-frequency trip delay"),
+    ("grid_freq_low_delay", Unit::Second, "Grid under-frequency trip delay"),
+    ("grid_volt_recover_high", Unit::Volt, "Grid voltage high recovery threshold"),
+    ("grid_volt_recover_low", Unit::Volt, "Grid voltage low recovery threshold"),
+    ("grid_freq_recover_high", Unit::Hertz, "Grid frequency high recovery threshold"),
+    ("grid_freq_recover_low", Unit::Hertz, "Grid frequency low recovery threshold"),
+    ("grid_volt_recover_delay", Unit::Second, "Grid voltage recovery delay"),
+    ("grid_freq_recover_delay", Unit::Second, "Grid frequency recovery delay"),
+    ("island_detect_time", Unit::Second, "Islanding detection time"),
+    ("pf_cmd_memory_en", Unit::Dimensionless, "Power-factor command memory enable"),
+    ("pf_cmd_memory_pf", Unit::Dimensionless, "Remembered power-factor command value"),
+    ("pf_cmd_memory_p_ref", Unit::Dimensionless, "Remembered power-factor command reference power"),
+    ("pf_cmd_memory_v_ref", Unit::Dimensionless, "Remembered power-factor command reference voltage"),
+    ("pf_cmd_memory_q_ref", Unit::Dimensionless, "Remembered power-factor command reference reactive power"),
+];
+// }}}
+
+/// Default `cell_voltage_delta` (volts) above which
+/// `ReadInputAll::calculate_derived_values` sets `cell_imbalance_alarm` —
+/// a weak cell or failing balancer typically shows up here well before the
+/// BMS itself trips a hard fault.
+pub const CELL_IMBALANCE_VOLTAGE_THRESHOLD_V: f64 = 0.05;
+
+// {{{ ReadInputAll
+#[derive(PartialEq, Clone, Debug, Serialize, Nom)]
+#[nom(LittleEndian)]
+pub struct ReadInputAll {
+    pub status: u16,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_1: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_2: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_3: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_bat: Option<f64>,
+
+    pub soc: i8,
+    pub soh: i8,
+
+    pub internal_fault: u16,
+
+    #[nom(Ignore)]
+    pub p_pv: u16,
+    pub p_pv_1: u16,
+    pub p_pv_2: u16,
+    pub p_pv_3: u16,
+    #[nom(Ignore)]
+    pub p_battery: i32,
+    pub p_charge: u16,
+    pub p_discharge: u16,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_r: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_s: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_t: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_ac: f64,
+
+    pub p_inv: u16,
+    pub p_rec: u16,
+
+    #[nom(SkipBefore(2))] // IinvRMS
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_r: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_s: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_t: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps: f64,
+    pub p_eps: u16,
+    pub s_eps: u16,
+    #[nom(Ignore)]
+    pub p_grid: i32,
+    pub p_to_grid: u16,
+    pub p_to_user: u16,
+
+    #[nom(Ignore)]
+    pub e_pv_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_2: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_3: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_inv_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_rec_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_chg_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_dischg_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_to_grid_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_to_user_day: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bus_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bus_2: f64,
+
+    #[nom(Ignore)]
+    pub e_pv_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_1: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_2: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_3: f64,
+
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_inv_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_rec_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_chg_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_dischg_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_to_grid_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_to_user_all: f64,
+
+    pub fault_code: u32,
+    pub warning_code: u32,
+
+    pub t_inner: u16,
+    pub t_rad_1: u16,
+    pub t_rad_2: u16,
+    pub t_bat: u16,
+    #[nom(SkipBefore(2))] // reserved - radiator 3?
+    pub runtime: u32,
+    // 18 bytes of auto_test stuff here I'm not doing yet
+    #[nom(SkipBefore(18))] // auto_test stuff, TODO..
+    #[nom(SkipBefore(2))] // bat_brand, bat_com_type
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub max_chg_curr: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub max_dischg_curr: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub charge_volt_ref: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub dischg_cut_volt: f64,
+
+    pub bat_status_0: u16,
+    pub bat_status_1: u16,
+    pub bat_status_2: u16,
+    pub bat_status_3: u16,
+    pub bat_status_4: u16,
+    pub bat_status_5: u16,
+    pub bat_status_6: u16,
+    pub bat_status_7: u16,
+    pub bat_status_8: u16,
+    pub bat_status_9: u16,
+    pub bat_status_inv: u16,
+
+    pub bat_count: u16,
+    pub bat_capacity: u16,
+
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub bat_current: f64,
+
+    pub bms_event_1: u16, // FaultCode_BMS
+    pub bms_event_2: u16, // WarningCode_BMS
+
+    // TODO: probably floats but need non-zero sample data to check. just guessing at the div100.
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub max_cell_voltage: f64,
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub min_cell_voltage: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub max_cell_temp: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub min_cell_temp: f64,
+
+    /// `max_cell_voltage - min_cell_voltage`, an early indicator of a weak
+    /// cell or failing balancer well before the BMS trips a hard fault on
+    /// it. Not present on the wire.
+    #[nom(Ignore)]
+    pub cell_voltage_delta: f64,
+    /// `max_cell_temp - min_cell_temp`. Not present on the wire.
+    #[nom(Ignore)]
+    pub cell_temp_delta: f64,
+    /// Set when `cell_voltage_delta` exceeds
+    /// `CELL_IMBALANCE_VOLTAGE_THRESHOLD_V` (or a caller-supplied threshold
+    /// via `calculate_derived_values_with_imbalance_threshold`). Not
+    /// present on the wire.
+    #[nom(Ignore)]
+    pub cell_imbalance_alarm: bool,
+
+    /// Per-string telemetry for the four parallel battery modules
+    /// `ReadInput3` parses (`v_bat_1..4` etc below), pulled in from there.
+    /// Not present on the wire as their own fields.
+    #[nom(Ignore)]
+    pub t_bat_1: f64,
+    #[nom(Ignore)]
+    pub t_bat_2: f64,
+    #[nom(Ignore)]
+    pub t_bat_3: f64,
+    #[nom(Ignore)]
+    pub t_bat_4: f64,
+    #[nom(Ignore)]
+    pub v_bat_1: f64,
+    #[nom(Ignore)]
+    pub v_bat_2: f64,
+    #[nom(Ignore)]
+    pub v_bat_3: f64,
+    #[nom(Ignore)]
+    pub v_bat_4: f64,
+    #[nom(Ignore)]
+    pub i_bat_1: f64,
+    #[nom(Ignore)]
+    pub i_bat_2: f64,
+    #[nom(Ignore)]
+    pub i_bat_3: f64,
+    #[nom(Ignore)]
+    pub i_bat_4: f64,
+    #[nom(Ignore)]
+    pub soc_1: u8,
+    #[nom(Ignore)]
+    pub soc_2: u8,
+    #[nom(Ignore)]
+    pub soc_3: u8,
+    #[nom(Ignore)]
+    pub soc_4: u8,
+    #[nom(Ignore)]
+    pub soh_1: u8,
+    #[nom(Ignore)]
+    pub soh_2: u8,
+    #[nom(Ignore)]
+    pub soh_3: u8,
+    #[nom(Ignore)]
+    pub soh_4: u8,
+
+    /// Present battery modules built from the fields above by
+    /// `battery_modules` (absent strings, i.e. all-zero, are skipped).
+    /// Populated in `calculate_derived_values`. Not present on the wire.
+    #[nom(Ignore)]
+    pub battery_modules: Vec<BatteryModule>,
+    /// Weakest module's SOH, from `battery_module_stats`. Not on the wire.
+    #[nom(Ignore)]
+    pub worst_module_soh: Option<u8>,
+    /// Widest inter-module SOC spread. Not present on the wire.
+    #[nom(Ignore)]
+    pub max_module_soc_spread: Option<u8>,
+    /// Widest inter-module temperature spread. Not present on the wire.
+    #[nom(Ignore)]
+    pub max_module_temp_spread: Option<f64>,
+    /// Index of the module furthest from the pack's average SOC. Not
+    /// present on the wire.
+    #[nom(Ignore)]
+    pub outlier_module_index: Option<usize>,
+
+    pub bms_fw_update_state: u16,
+
+    pub cycle_count: u16,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub vbat_inv: f64,
+
+    // Generator and bus voltage data (previously skipped 14 bytes)
+    pub gen_status: u16,          // Generator status (0=Off, 1=Starting, 2=Running, 3=Stopping, 4=Error)
+    pub gen_power_factor: u16,    // Generator power factor (0-1000, divide by 1000 for actual value)
+    pub gen_current: u16,         // Generator current in Amps (0-100A)
+    pub gen_power_limit: u16,     // Generator power limit setting in Watts (0-10000W)
+    pub gen_connect_status: u16,  // Generator connection status (0=Disconnected, 1=Connected)
+    pub gen_control_mode: u16,    // Generator control mode (0=Auto, 1=Manual, 2=Test)
+    pub gen_dispatch_mode: u16,   // Generator dispatch mode (0=Off, 1=On, 2=Auto)
+
+    // Half bus voltage data (previously skipped 2 bytes)
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bus_half: f64,         // Half bus voltage (V) - Expected range: 0-1000V
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_gen: f64,              // Generator voltage (V) - Expected range: 180-270V
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_gen: f64,              // Generator frequency (Hz) - Expected range: 45-65Hz when running
+    pub p_gen: u16,              // Generator power output (W) - Expected range: 0-10000W
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_gen_day: f64,          // Generator daily energy production (kWh) - Expected range: 0-1000kWh
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_gen_all: f64,          // Generator total energy production (kWh) - Expected range: 0-999999kWh
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_l1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_l2: f64,
+    pub p_eps_l1: u16,
+    pub p_eps_l2: u16,
+    pub s_eps_l1: u16,
+    pub s_eps_l2: u16,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_l1_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_l2_day: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_l1_all: f64,
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_l2_all: f64,
+
+    // Additional EPS values
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_eps_l1: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_eps_l2: f64,
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf_eps_l1: f64,
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf_eps_l2: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps_l1: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps_l2: f64,
+
+    // following are for influx capability only
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    #[nom(Ignore)]
+    pub datalog: Serial,
+
+    // Battery configuration from ReadInput5
+    pub bat_cell_count: u16,          // Number of battery cells in series
+    pub bat_parallel_count: u16,      // Number of battery cells in parallel
+    pub under_freq_start: u16,        // Underfrequency load reduction starting point
+    pub under_freq_end: u16,          // Underfrequency derating end point
+    pub under_freq_slope: u16,        // Underfrequency load shedding slope
+    pub max_compensation: u16,        // Maximum compensation amount for specific load
+    pub chg_power_pct: u16,          // Charging power percentage setting
+    pub dischg_power_pct: u16,       // Discharge power percentage setting
+    pub ac_charge_pct: u16,          // AC charge percentage setting
+    pub chg_priority_pct: u16,       // Charging priority percentage setting
+    pub forced_dischg_pct: u16,      // Forced discharge percentage setting
+    pub inv_power_pct: u16,          // Inverter active power percentage setting
+    
+    // AC charging parameters
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ac_chg_start_v: f64,         // AC charging starting battery voltage
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ac_chg_end_v: f64,           // AC charging cut off battery voltage
+    pub ac_chg_start_soc: u16,       // AC charging starting SOC
+    pub ac_chg_end_soc: u16,         // AC charging stops SOC
+    
+    // Battery voltage parameters
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_v: f64,              // Battery undervoltage alarm point
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_back_v: f64,         // Battery undervoltage alarm recovery point
+    pub bat_low_soc: u16,            // Battery undervoltage alarm point (SOC)
+    pub bat_low_back_soc: u16,       // Battery undervoltage alarm recovery point (SOC)
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_utility_v: f64,      // Battery undervoltage to mains voltage point
+    pub bat_low_utility_soc: u16,    // Battery undervoltage to mains SOC
+    
+    // Additional parameters
+    pub ac_chg_curr: u16,            // AC charge current
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ongrid_eod_v: f64,           // On-grid EOD voltage
+    
+    // SOC curve data
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub soc_volt1: f64,              // SOC curve voltage point 1
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub soc_volt2: f64,              // SOC curve voltage point 2
+    pub soc_pct1: u16,               // SOC percentage point 1
+    pub soc_pct2: u16,               // SOC percentage point 2
+    pub soc_inner_resistance: u16,    // SOC curve inner resistance
+
+    /// Open-circuit-voltage SOC estimate: `v_bat` compensated for IR drop
+    /// against `bat_current`, then interpolated against the curve above
+    /// (see `calculate_soc_from_ocv`). Cross-checks the BMS-reported `soc`
+    /// from a different angle than `soc_estimated`'s coulomb count, which
+    /// is useful for LFP packs where reported SOC tends to drift on the
+    /// flat part of the curve. Not present on the wire.
+    #[nom(Ignore)]
+    pub soc_ocv: Option<f64>,
+
+    /// SOC estimated by coulomb-counting `bat_current` over time (see
+    /// `CoulombCounter`), seeded/recalibrated from the reported SOC
+    /// whenever the pack looks full or empty. `None` until `ReadInputs` has
+    /// seen at least one `ReadInput3`. Not present on the wire.
+    #[nom(Ignore)]
+    pub soc_estimated: Option<f64>,
+    /// `soc_estimated - soc_1` (the module-level SOC reported by the BMS),
+    /// for spotting BMS SOC miscalibration. Not present on the wire.
+    #[nom(Ignore)]
+    pub soc_drift: Option<f64>,
+
+    /// Moving average of `v_bat` over `ReadInputSmoothing`'s window
+    /// (default `DEFAULT_ROLLING_AVERAGE_WINDOW` samples), so a single
+    /// noisy ADC sample doesn't pollute charts/alerting. `None` until
+    /// `ReadInputs` has seen at least one `ReadInput1`. Not on the wire.
+    #[nom(Ignore)]
+    pub v_bat_avg: Option<f64>,
+    /// Moving average of `bat_current`. `None` until `ReadInputs` has seen
+    /// at least one `ReadInput3`. Not present on the wire.
+    #[nom(Ignore)]
+    pub bat_current_avg: Option<f64>,
+    /// Moving average of `t_bat`. `None` until `ReadInputs` has seen at
+    /// least one `ReadInput2`. Not present on the wire.
+    #[nom(Ignore)]
+    pub t_bat_avg: Option<f64>,
+    /// Moving average of `soc`. `None` until `ReadInputs` has seen at
+    /// least one `ReadInput1`. Not present on the wire.
+    #[nom(Ignore)]
+    pub soc_avg: Option<f64>,
+
+    // Power settings
+    pub max_grid_input_power: u16,    // Maximum grid input power
+    pub gen_rated_power: u16,         // Generator rated power
+    
+    // Function enable flags
+    pub function_bit_flags: u16,      // Combined function enable flags
+    
+    // Additional settings
+    pub afci_threshold: u16,          // AFCI arc threshold
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub volt_watt_v1: f64,           // Volt-Watt V1 setting
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub volt_watt_v2: f64,           // Volt-Watt V2 setting
+    pub volt_watt_delay: u16,        // Volt-Watt delay time
+    pub volt_watt_p2: u16,           // Volt-Watt P2 setting
+
+    // Extended system parameters from ReadInput6
+    pub grid_voltage_high_pure: u16,     // Grid voltage high pure limit
+    pub grid_voltage_low_pure: u16,      // Grid voltage low pure limit
+    pub grid_freq_high_pure: u16,        // Grid frequency high pure limit
+    pub grid_freq_low_pure: u16,         // Grid frequency low pure limit
+    pub grid_volt_high_delay: u16,       // Grid voltage high delay
+    pub grid_volt_low_delay: u16,        // Grid voltage low delay
+    pub grid_freq_high_delay: u16,       // Grid frequency high delay
+    pub grid_freq_low_delay: u16,        // Grid frequency low delay
+    pub grid_volt_recover_high: u16,     // Grid voltage recover high threshold
+    pub grid_volt_recover_low: u16,      // Grid voltage recover low threshold
+    pub grid_freq_recover_high: u16,     // Grid frequency recover high threshold
+    pub grid_freq_recover_low: u16,      // Grid frequency recover low threshold
+    pub grid_volt_recover_delay: u16,    // Grid voltage recover delay
+    pub grid_freq_recover_delay: u16,    // Grid frequency recover delay
+    pub island_detect_time: u16,         // Island detection time
+    pub pf_cmd_memory_en: u16,           // Power factor command memory enable
+    pub pf_cmd_memory_pf: u16,           // Power factor command memory value
+    pub pf_cmd_memory_p_ref: u16,        // Power factor command memory reference power
+    pub pf_cmd_memory_v_ref: u16,        // Power factor command memory reference voltage
+    pub pf_cmd_memory_q_ref: u16,        // Power factor command memory reference reactive power
+}
+
+impl ReadInputAll {
+    /// Returns `(field_name, unit, description)` for every decoded field, so
+    /// callers can attach units/descriptions without hard-coding their own map.
+    pub fn field_units() -> &'static [(&'static str, Unit, &'static str)] {
+        READ_INPUT_ALL_FIELD_UNITS
+    }
+
+    /// Same as `calculate_derived_values`, but judges `cell_imbalance_alarm`
+    /// against a caller-supplied voltage threshold (volts) instead of
+    /// [`CELL_IMBALANCE_VOLTAGE_THRESHOLD_V`].
+    pub fn calculate_derived_values_with_imbalance_threshold(
+        &mut self,
+        imbalance_threshold_v: f64,
+    ) -> Result<()> {
+        self.calculate_derived_values_impl(imbalance_threshold_v)
+    }
+
+    pub fn calculate_derived_values(&mut self) -> Result<()> {
+        self.calculate_derived_values_impl(CELL_IMBALANCE_VOLTAGE_THRESHOLD_V)
+    }
+
+    fn calculate_derived_values_impl(&mut self, imbalance_threshold_v: f64) -> Result<()> {
+        debug!("Calculating derived values for ReadInputAll");
+
+        // Safe conversion and addition of power values using u16
+        self.p_pv = self.p_pv_1
+            .checked_add(self.p_pv_2)
+            .and_then(|sum| sum.checked_add(self.p_pv_3))
+            .ok_or_else(|| anyhow!("Power value overflow in p_pv calculation"))?;
+
+        // Safe conversion and subtraction for battery power
+        self.p_battery = i32::from(self.p_charge)
+            .checked_sub(i32::from(self.p_discharge))
+            .ok_or_else(|| anyhow!("Power value overflow in p_battery calculation"))?;
+
+        // Safe conversion and subtraction for grid power
+        self.p_grid = i32::from(self.p_to_user)
+            .checked_sub(i32::from(self.p_to_grid))
+            .ok_or_else(|| anyhow!("Power value overflow in p_grid calculation"))?;
+
+        // Safe addition for total PV energy - using f64 arithmetic
+        self.e_pv_day = Utils::round(self.e_pv_day_1 + self.e_pv_day_2 + self.e_pv_day_3, 1);
+        self.e_pv_all = Utils::round(self.e_pv_all_1 + self.e_pv_all_2 + self.e_pv_all_3, 1);
+
+        self.soc_ocv = self.calculate_soc_from_ocv();
+
+        self.cell_voltage_delta = Utils::round(self.max_cell_voltage - self.min_cell_voltage, 3);
+        self.cell_temp_delta = Utils::round(self.max_cell_temp - self.min_cell_temp, 1);
+        self.cell_imbalance_alarm = self.cell_voltage_delta > imbalance_threshold_v;
+
+        self.battery_modules = battery_modules(
+            [self.v_bat_1, self.v_bat_2, self.v_bat_3, self.v_bat_4],
+            [self.i_bat_1, self.i_bat_2, self.i_bat_3, self.i_bat_4],
+            [self.t_bat_1, self.t_bat_2, self.t_bat_3, self.t_bat_4],
+            [self.soc_1, self.soc_2, self.soc_3, self.soc_4],
+            [self.soh_1, self.soh_2, self.soh_3, self.soh_4],
+        );
+        let module_stats = battery_module_stats(&self.battery_modules);
+        self.worst_module_soh = module_stats.worst_module_soh;
+        self.max_module_soc_spread = module_stats.max_module_soc_spread;
+        self.max_module_temp_spread = module_stats.max_module_temp_spread;
+        self.outlier_module_index = module_stats.outlier_module_index;
+
+        debug!("Derived values calculated successfully");
+        Ok(())
+    }
+
+    /// Estimates SOC from `v_bat`/`bat_current` via the on-device OCV/SOC
+    /// curve, for cross-checking against the BMS-reported `soc`. First
+    /// compensates the measured terminal voltage for IR drop (discharge
+    /// current is negative, so this raises `v_ocv` under load), then does
+    /// piecewise-linear interpolation between the two curve points
+    /// `(soc_volt1, soc_pct1)` and `(soc_volt2, soc_pct2)`.
+    pub fn calculate_soc_from_ocv(&self) -> Option<f64> {
+        let v_bat = self.v_bat?;
+        if self.soc_volt1 == self.soc_volt2 {
+            return None;
+        }
+
+        let v_ocv = v_bat - self.bat_current * (self.soc_inner_resistance as f64 / 1000.0);
+
+        let pct1 = self.soc_pct1 as f64;
+        let pct2 = self.soc_pct2 as f64;
+        let soc = pct1 + (v_ocv - self.soc_volt1) * (pct2 - pct1) / (self.soc_volt2 - self.soc_volt1);
+
+        Some(soc.clamp(0.0, 100.0))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        // Validate SOC and SOH
+        if self.soc < 0 || self.soc > 100 {
+            return Err(anyhow!("Invalid SOC value: {}", self.soc));
+        }
+        if self.soh < 0 || self.soh > 100 {
+            return Err(anyhow!("Invalid SOH value: {}", self.soh));
+        }
+
+        // Validate power values are within reasonable ranges
+        if self.p_pv_1 > 10000 || self.p_pv_2 > 10000 || self.p_pv_3 > 10000 {
+            return Err(anyhow!("Invalid PV power values"));
+        }
+
+        // Validate frequencies
+        if self.f_ac < 45.0 || self.f_ac > 65.0 {
+            return Err(anyhow!("Invalid AC frequency: {}", self.f_ac));
+        }
+        if self.f_eps < 45.0 || self.f_eps > 65.0 {
+            return Err(anyhow!("Invalid EPS frequency: {}", self.f_eps));
+        }
+
+        // Validate generator measurements
+        if self.v_gen > 0.0 && (self.v_gen < 180.0 || self.v_gen > 270.0) {
+            return Err(anyhow!("Invalid generator voltage: {}", self.v_gen));
+        }
+        if self.f_gen > 0.0 && (self.f_gen < 45.0 || self.f_gen > 65.0) {
+            return Err(anyhow!("Invalid generator frequency: {}", self.f_gen));
+        }
+        if self.p_gen > 10000 {
+            return Err(anyhow!("Invalid generator power: {}", self.p_gen));
+        }
+        if self.gen_power_factor > 1000 {
+            return Err(anyhow!("Invalid generator power factor: {}", self.gen_power_factor));
+        }
+        if self.gen_current > 100 {
+            return Err(anyhow!("Invalid generator current: {}", self.gen_current));
+        }
+
+        // Validate bus voltage
+        if self.v_bus_half > 1000.0 {
+            return Err(anyhow!("Invalid half bus voltage: {}", self.v_bus_half));
+        }
+
+        Ok(())
+    }
+
+    /// Decodes `fault_code`/`warning_code` into named flags alongside the
+    /// raw values.
+    pub fn decode_flags(&self) -> StatusReport {
+        StatusReport {
+            fault_code: self.fault_code,
+            fault_flags: decode_status_bits(self.fault_code, FAULT_BITS),
+            warning_code: self.warning_code,
+            warning_flags: decode_status_bits(self.warning_code, WARNING_BITS),
+        }
+    }
+
+    /// Decodes `bms_event_1`/`bms_event_2` (the BMS's own fault/warning
+    /// words) into named flags alongside the raw values.
+    pub fn decode_bms_flags(&self) -> BmsStatusReport {
+        BmsStatusReport {
+            bms_event_1: self.bms_event_1,
+            bms_fault_flags: decode_status_bits(self.bms_event_1, BMS_FAULT_BITS),
+            bms_event_2: self.bms_event_2,
+            bms_warning_flags: decode_status_bits(self.bms_event_2, BMS_WARNING_BITS),
+        }
+    }
+
+    /// Classifies battery condition using `BatteryHealthThresholds::default()`.
+    pub fn health(&self) -> BatteryHealth {
+        self.health_with_thresholds(&BatteryHealthThresholds::default())
+    }
+
+    /// Same as `health`, but against caller-supplied thresholds instead of
+    /// the defaults.
+    pub fn health_with_thresholds(&self, thresholds: &BatteryHealthThresholds) -> BatteryHealth {
+        classify_battery_health(
+            self.bat_count,
+            self.bat_capacity,
+            self.max_cell_temp,
+            self.min_cell_temp,
+            self.max_cell_voltage,
+            self.min_cell_voltage,
+            self.charge_volt_ref,
+            self.dischg_cut_volt,
+            self.cycle_count,
+            self.bms_event_2,
+            thresholds,
+        )
+    }
+
+    /// Like `health`, but also decodes `fault_code`/`warning_code`/
+    /// `bms_event_1`/`bms_event_2` into a list of active reasons and folds
+    /// in a cell-imbalance check, using `BatteryHealthThresholds::default()`
+    /// and `CELL_IMBALANCE_VOLTAGE_THRESHOLD_V`.
+    pub fn health_report(&self) -> BatteryHealthReport {
+        self.health_report_with_thresholds(
+            &BatteryHealthThresholds::default(),
+            CELL_IMBALANCE_VOLTAGE_THRESHOLD_V,
+        )
+    }
+
+    /// Same as `health_report`, but against caller-supplied thresholds.
+    pub fn health_report_with_thresholds(
+        &self,
+        thresholds: &BatteryHealthThresholds,
+        imbalance_threshold_v: f64,
+    ) -> BatteryHealthReport {
+        classify_battery_health_report(
+            self.bat_count,
+            self.bat_capacity,
+            self.max_cell_temp,
+            self.min_cell_temp,
+            self.max_cell_voltage,
+            self.min_cell_voltage,
+            self.charge_volt_ref,
+            self.dischg_cut_volt,
+            self.cycle_count,
+            self.fault_code,
+            self.warning_code,
+            self.bms_event_1,
+            self.bms_event_2,
+            imbalance_threshold_v,
+            thresholds,
+        )
+    }
+}
+// }}}
+
+// {{{ typed engineering quantities (uom)
+//
+// `ReadInputAll`'s fields are bare integers/floats - the unit and scale
+// (often x0.1, see `READ_INPUT_ALL_FIELD_UNITS`) only live in the field name
+// and doc comment, so every consumer has to remember the convention by
+// hand. Behind the `uom` feature, these accessors wrap a handful of the
+// most commonly consumed fields in `uom`'s dimensioned quantity types so
+// callers get dimension-checked arithmetic and unit-correct formatting for
+// free, without changing how the field itself is decoded off the wire.
+#[cfg(feature = "uom")]
+mod quantities {
+    use super::ReadInputAll;
+    use uom::si::electric_current::ampere;
+    use uom::si::electric_potential::volt;
+    use uom::si::energy::kilowatt_hour;
+    use uom::si::f64::{ElectricCurrent, ElectricPotential, Energy, Power, ThermodynamicTemperature};
+    use uom::si::power::watt;
+    use uom::si::thermodynamic_temperature::degree_celsius;
+
+    impl ReadInputAll {
+        pub fn v_pv_1(&self) -> Option<ElectricPotential> {
+            self.v_pv_1.map(ElectricPotential::new::<volt>)
+        }
+
+        pub fn v_bat(&self) -> Option<ElectricPotential> {
+            self.v_bat.map(ElectricPotential::new::<volt>)
+        }
+
+        pub fn bat_current(&self) -> ElectricCurrent {
+            ElectricCurrent::new::<ampere>(self.bat_current)
+        }
+
+        pub fn p_grid(&self) -> Power {
+            Power::new::<watt>(self.p_grid as f64)
+        }
+
+        pub fn p_battery(&self) -> Power {
+            Power::new::<watt>(self.p_battery as f64)
+        }
+
+        pub fn e_pv_day(&self) -> Energy {
+            Energy::new::<kilowatt_hour>(self.e_pv_day)
+        }
+
+        pub fn t_inner(&self) -> ThermodynamicTemperature {
+            ThermodynamicTemperature::new::<degree_celsius>(self.t_inner as f64)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn quantity_accessors_wrap_the_raw_fields_with_units() {
+            // ReadInputAll::read_input() only treats a 254-byte payload as
+            // the "all registers" frame (see `TranslatedData::read_input`),
+            // so an all-zero buffer of that length parses cleanly.
+            let (_, mut r) = ReadInputAll::parse(&[0u8; 254]).unwrap();
+            r.calculate_derived_values().unwrap();
+
+            assert_eq!(r.v_pv_1().unwrap().get::<volt>(), 0.0);
+            assert_eq!(r.v_bat().unwrap().get::<volt>(), 0.0);
+            assert_eq!(r.bat_current().get::<ampere>(), 0.0);
+            assert_eq!(r.p_grid().get::<watt>(), 0.0);
+            assert_eq!(r.p_battery().get::<watt>(), 0.0);
+            assert_eq!(r.e_pv_day().get::<kilowatt_hour>(), 0.0);
+            assert_eq!(r.t_inner().get::<degree_celsius>(), 0.0);
+        }
+    }
+}
+// }}}
+
+// {{{ rolling stats aggregation
+//
+// UPS/battery tooling conventionally exposes min/max/average alongside the
+// instantaneous reading (`battery.voltage.minimum/maximum/average` in
+// UPScode II, the Samsung fuel-gauge driver, etc). `ReadInputStats` below
+// gives this crate the same trend envelope, keyed per-inverter so a
+// multi-inverter setup doesn't mix readings together.
+
+/// Metrics tracked by [`ReadInputStats`]. Extend this list (and the
+/// `samples` array in [`ReadInputStats::ingest`]) to aggregate more fields.
+pub const TRACKED_STATS_METRICS: &[&str] = &[
+    "v_bat",
+    "bat_current",
+    "soc",
+    "p_pv",
+    "p_grid",
+    "t_bat",
+    "max_cell_temp",
+    "min_cell_temp",
+];
+
+/// Rolling min/max and time-weighted moving average for a single metric.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricWindow {
+    pub min: f64,
+    pub max: f64,
+    pub average: f64,
+    #[serde(skip)]
+    weighted_sum: f64,
+    #[serde(skip)]
+    weighted_seconds: f64,
+    #[serde(skip)]
+    last_sample_at: Option<std::time::Instant>,
+}
+
+impl Default for MetricWindow {
+    fn default() -> Self {
+        Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            average: 0.0,
+            weighted_sum: 0.0,
+            weighted_seconds: 0.0,
+            last_sample_at: None,
+        }
+    }
+}
+
+impl MetricWindow {
+    fn observe(&mut self, value: f64, now: std::time::Instant) {
+        if value < self.min {
+            self.min = value;
+        }
+        if value > self.max {
+            self.max = value;
+        }
+
+        if let Some(last_sample_at) = self.last_sample_at {
+            let elapsed = now.duration_since(last_sample_at).as_secs_f64();
+            self.weighted_sum += value * elapsed;
+            self.weighted_seconds += elapsed;
+        }
+        self.last_sample_at = Some(now);
+
+        self.average = if self.weighted_seconds > 0.0 {
+            self.weighted_sum / self.weighted_seconds
+        } else {
+            value
+        };
+    }
+}
+
+/// Per-inverter rolling min/max/average for [`TRACKED_STATS_METRICS`],
+/// reset whenever that inverter's own `e_*_day` counters roll over (the
+/// same daily boundary the inverter itself uses), so the window always
+/// covers "today" rather than growing without bound.
+#[derive(Debug, Default)]
+pub struct ReadInputStats {
+    windows: std::collections::HashMap<Serial, std::collections::HashMap<&'static str, MetricWindow>>,
+    last_day_counter: std::collections::HashMap<Serial, f64>,
+}
+
+impl ReadInputStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingest one reading, updating (or, on daily rollover, resetting)
+    /// that inverter's windows.
+    pub fn ingest(&mut self, input: &ReadInputAll) {
+        let now = std::time::Instant::now();
+
+        let rolled_over = match self.last_day_counter.get(&input.datalog) {
+            // e_pv_day only ever grows through the day, so a lower value
+            // than last time means the inverter rolled over at midnight.
+            Some(&previous) => input.e_pv_day + 0.01 < previous,
+            None => false,
+        };
+        self.last_day_counter.insert(input.datalog, input.e_pv_day);
+
+        let windows = self.windows.entry(input.datalog).or_default();
+        if rolled_over {
+            windows.clear();
+        }
+
+        let samples: [(&'static str, Option<f64>); 8] = [
+            ("v_bat", input.v_bat),
+            ("bat_current", Some(input.bat_current)),
+            ("soc", Some(input.soc as f64)),
+            ("p_pv", Some(input.p_pv as f64)),
+            ("p_grid", Some(input.p_grid as f64)),
+            ("t_bat", Some(input.t_bat as f64)),
+            ("max_cell_temp", Some(input.max_cell_temp)),
+            ("min_cell_temp", Some(input.min_cell_temp)),
+        ];
+
+        for (name, value) in samples {
+            if let Some(value) = value {
+                windows.entry(name).or_default().observe(value, now);
+            }
+        }
+    }
+
+    /// Current windows for one inverter, if any readings have been
+    /// ingested for it yet.
+    pub fn windows_for(
+        &self,
+        datalog: &Serial,
+    ) -> Option<&std::collections::HashMap<&'static str, MetricWindow>> {
+        self.windows.get(datalog)
+    }
+}
+// }}}
+
+// {{{ ReadInput1
+#[derive(Clone, Debug, Serialize, Nom)]
+#[nom(LittleEndian)]
+pub struct ReadInput1 {
+    pub status: u16,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_1: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_2: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_pv_3: Option<f64>,
+    #[nom(Parse = "Utils::le_u16_checked_div10")]
+    pub v_bat: Option<f64>,
+
+    pub soc: i8,
+    pub soh: i8,
+
+    pub internal_fault: u16,
+
+    #[nom(Ignore)]
+    pub p_pv: u16,
+    pub p_pv_1: u16,
+    pub p_pv_2: u16,
+    pub p_pv_3: u16,
+    #[nom(Ignore)]
+    pub p_battery: i32,
+    pub p_charge: u16,
+    pub p_discharge: u16,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_r: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_s: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_ac_t: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_ac: f64,
+
+    pub p_inv: u16,
+    pub p_rec: u16,
+
+    #[nom(SkipBefore(2))] // IinvRMS
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_r: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_s: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_t: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps: f64,
+    pub p_eps: u16,
+    pub s_eps: u16,
+    #[nom(Ignore)]
+    pub p_grid: i32,
+    pub p_to_grid: u16,
+    pub p_to_user: u16,
+
+    #[nom(Ignore)]
+    pub e_pv_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_2: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_pv_day_3: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_inv_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_rec_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_chg_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_dischg_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_to_grid_day: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_to_user_day: f64,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bus_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bus_2: f64,
+
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+
+impl ReadInput1 {
+    pub fn calculate_derived_values(&mut self) -> Result<()> {
+        // Safe conversion and addition of power values using u16
+        self.p_pv = self.p_pv_1
+            .checked_add(self.p_pv_2)
+            .and_then(|sum| sum.checked_add(self.p_pv_3))
+            .ok_or_else(|| anyhow!("Power value overflow in p_pv calculation"))?;
+
+        // Safe conversion and subtraction for battery power
+        self.p_battery = i32::from(self.p_charge)
+            .checked_sub(i32::from(self.p_discharge))
+            .ok_or_else(|| anyhow!("Power value overflow in p_battery calculation"))?;
+
+        // Safe conversion and subtraction for grid power
+        self.p_grid = i32::from(self.p_to_user)
+            .checked_sub(i32::from(self.p_to_grid))
+            .ok_or_else(|| anyhow!("Power value overflow in p_grid calculation"))?;
+
+        // Safe addition for total PV energy - using f64 arithmetic
+        self.e_pv_day = Utils::round(self.e_pv_day_1 + self.e_pv_day_2 + self.e_pv_day_3, 1);
+
+        Ok(())
+    }
+}
+// }}}
+
+// {{{ ReadInput2
+#[derive(Clone, Debug, Serialize, Nom)]
+#[nom(Debug, LittleEndian)]
+pub struct ReadInput2 {
+    // Total PV energy (derived from sum of e_pv_all_1/2/3)
+    #[nom(Ignore)]
+    pub e_pv_all: f64,
+    // Total PV energy from string 1 (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_1: f64,
+    // Total PV energy from string 2 (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_2: f64,
+    // Total PV energy from string 3 (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_pv_all_3: f64,
+
+    // Total energy fed into grid through inverter (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_inv_all: f64,
+    // Total energy received from grid (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_rec_all: f64,
+    // Total energy used to charge battery (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_chg_all: f64,
+    // Total energy discharged from battery (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_dischg_all: f64,
+    // Total energy supplied to EPS loads (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_all: f64,
+    // Total energy exported to grid (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_to_grid_all: f64,
+    // Total energy consumed from grid (kWh)
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_to_user_all: f64,
+
+    // System fault code (bitmap)
+    pub fault_code: u32,
+    // System warning code (bitmap)
+    pub warning_code: u32,
+
+    // Internal temperature (°C)
+    pub t_inner: u16,
+    // Radiator temperature 1 (°C)
+    pub t_rad_1: u16,
+    // Radiator temperature 2 (°C)
+    pub t_rad_2: u16,
+    // Battery temperature (°C)
+    pub t_bat: u16,
+
+    // Radiator temperature 3 (°C)
+    pub t_rad_3: u16,
+
+    // Total runtime in seconds
+    pub runtime: u32,
+
+    // Auto-test data (18 bytes total)
+    // Current status of auto-test (0=not running, 1=in progress)
+    pub auto_test_status: u16,
+    // Current stage of testing sequence
+    pub auto_test_stage: u16,
+    // Time remaining or timeout value for current test
+    pub auto_test_timeout: u16,
+    // Upper frequency limit being tested (Hz)
+    pub auto_test_frequency_upper: u16,
+    // Lower frequency limit being tested (Hz)
+    pub auto_test_frequency_lower: u16,
+    // Upper voltage limit being tested (V)
+    pub auto_test_voltage_upper: u16,
+    // Lower voltage limit being tested (V)
+    pub auto_test_voltage_lower: u16,
+    // Power reading during test (W)
+    pub auto_test_power: u16,
+    // Result code from auto-test
+    pub auto_test_result: u16,
+
+    // Battery information
+    // Battery manufacturer/brand identifier
+    pub bat_brand: u8,
+    // Battery communication protocol type
+    pub bat_com_type: u8,
+
+    // Timestamp of reading
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    // Datalog serial number
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+
+impl ReadInput2 {
+    pub fn calculate_derived_values(&mut self) -> Result<()> {
+        // Safe addition for total PV energy - using f64 arithmetic
+        self.e_pv_all = Utils::round(self.e_pv_all_1 + self.e_pv_all_2 + self.e_pv_all_3, 1);
+        Ok(())
+    }
+
+    /// Decodes `fault_code`/`warning_code` into named flags alongside the
+    /// raw values. ReadInput2 doesn't carry the BMS event words (those live
+    /// on `ReadInput3`), so unlike `ReadInputAll` there's no
+    /// `decode_bms_flags` here.
+    pub fn decode_flags(&self) -> StatusReport {
+        StatusReport {
+            fault_code: self.fault_code,
+            fault_flags: decode_status_bits(self.fault_code, FAULT_BITS),
+            warning_code: self.warning_code,
+            warning_flags: decode_status_bits(self.warning_code, WARNING_BITS),
+        }
+    }
+}
+// }}}
+
+// {{{ ReadInput3
+#[derive(Clone, Debug, Serialize, Nom)]
+#[nom(LittleEndian)]
+pub struct ReadInput3 {
+    #[nom(SkipBefore(2))] // bat_brand, bat_com_type
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub max_chg_curr: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub max_dischg_curr: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub charge_volt_ref: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub dischg_cut_volt: f64,
+
+    pub bat_status_0: u16,
+    pub bat_status_1: u16,
+    pub bat_status_2: u16,
+    pub bat_status_3: u16,
+    pub bat_status_4: u16,
+    pub bat_status_5: u16,
+    pub bat_status_6: u16,
+    pub bat_status_7: u16,
+    pub bat_status_8: u16,
+    pub bat_status_9: u16,
+    pub bat_status_inv: u16,
+
+    pub bat_count: u16,
+    pub bat_capacity: u16,
+
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub bat_current: f64,
+
+    pub bms_event_1: u16,
+    pub bms_event_2: u16,
+
+    // TODO: probably floats but need non-zero sample data to check. just guessing at the div100.
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub max_cell_voltage: f64,
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub min_cell_voltage: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub max_cell_temp: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub min_cell_temp: f64,
+
+    pub bms_fw_update_state: u16,
+
+    pub cycle_count: u16,
+
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub vbat_inv: f64,
+
+    // Battery module information
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub t_bat_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub t_bat_2: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub t_bat_3: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub t_bat_4: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bat_1: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bat_2: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bat_3: f64,
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_bat_4: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_bat_1: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_bat_2: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_bat_3: f64,
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_bat_4: f64,
+    pub soc_1: u8,
+    pub soc_2: u8,
+    pub soc_3: u8,
+    pub soc_4: u8,
+    pub soh_1: u8,
+    pub soh_2: u8,
+    pub soh_3: u8,
+    pub soh_4: u8,
+
+    // following are for influx capability only
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+
+impl ReadInput3 {
+    /// Classifies battery condition using `BatteryHealthThresholds::default()`.
+    pub fn health(&self) -> BatteryHealth {
+        self.health_with_thresholds(&BatteryHealthThresholds::default())
+    }
+
+    /// Same as `health`, but against caller-supplied thresholds instead of
+    /// the defaults.
+    pub fn health_with_thresholds(&self, thresholds: &BatteryHealthThresholds) -> BatteryHealth {
+        classify_battery_health(
+            self.bat_count,
+            self.bat_capacity,
+            self.max_cell_temp,
+            self.min_cell_temp,
+            self.max_cell_voltage,
+            self.min_cell_voltage,
+            self.charge_volt_ref,
+            self.dischg_cut_volt,
+            self.cycle_count,
+            self.bms_event_2,
+            thresholds,
+        )
+    }
+}
+// }}}
+
+#[derive(Clone, Debug, Serialize, Nom)]
+#[nom(LittleEndian)]
+pub struct ReadInput4 {
+    // Half bus voltage (V) - Expected range: 0-1000V
+    #[nom(SkipBefore(2))]
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_gen: f64,
+    // Generator frequency (Hz) - Expected range: 45-65Hz when running
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_gen: f64,
+    // Generator power output (W) - Expected range: 0-10000W
+    pub p_gen: u16,
+    // Generator daily energy production (kWh) - Expected range: 0-1000kWh
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_gen_day: f64,
+    // Generator total energy production (kWh) - Expected range: 0-999999kWh
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_gen_all: f64,
+    // EPS voltage L1 (V) - Expected range: 180-270V
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_l1: f64,
+    // EPS voltage L2 (V) - Expected range: 180-270V
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub v_eps_l2: f64,
+    // EPS power L1 (W) - Expected range: 0-10000W
+    pub p_eps_l1: u16,
+    // EPS power L2 (W) - Expected range: 0-10000W
+    pub p_eps_l2: u16,
+    // EPS apparent power L1 (VA) - Expected range: 0-10000VA
+    pub s_eps_l1: u16,
+    // EPS apparent power L2 (VA) - Expected range: 0-10000VA
+    pub s_eps_l2: u16,
+    // EPS daily energy L1 (kWh) - Expected range: 0-1000kWh
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_l1_day: f64,
+    // EPS daily energy L2 (kWh) - Expected range: 0-1000kWh
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub e_eps_l2_day: f64,
+    // EPS total energy L1 (kWh) - Expected range: 0-999999kWh
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_l1_all: f64,
+    // EPS total energy L2 (kWh) - Expected range: 0-999999kWh
+    #[nom(Parse = "Utils::le_u32_div10")]
+    pub e_eps_l2_all: f64,
+
+    // Additional EPS values
+    // EPS current L1 (A) - Expected range: 0-50A
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_eps_l1: f64,
+    // EPS current L2 (A) - Expected range: 0-50A
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub i_eps_l2: f64,
+    // EPS power factor L1 - Expected range: 0-1.0
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf_eps_l1: f64,
+    // EPS power factor L2 - Expected range: 0-1.0
+    #[nom(Parse = "Utils::le_u16_div1000")]
+    pub pf_eps_l2: f64,
+    // EPS frequency L1 (Hz) - Expected range: 45-65Hz
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps_l1: f64,
+    // EPS frequency L2 (Hz) - Expected range: 45-65Hz
+    #[nom(Parse = "Utils::le_u16_div100")]
+    pub f_eps_l2: f64,
+
+    // EPS data; unsure what this is
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+
+// {{{ ReadInput5
+#[derive(Clone, Debug, Serialize, Nom)]
+#[nom(LittleEndian)]
+pub struct ReadInput5 {
+    // Battery configuration
+    pub bat_cell_count: u16,          // Number of battery cells in series
+    pub bat_parallel_count: u16,      // Number of battery cells in parallel
+    pub under_freq_start: u16,        // Underfrequency load reduction starting point
+    pub under_freq_end: u16,          // Underfrequency derating end point
+    pub under_freq_slope: u16,        // Underfrequency load shedding slope
+    pub max_compensation: u16,        // Maximum compensation amount for specific load
+    pub chg_power_pct: u16,          // Charging power percentage setting
+    pub dischg_power_pct: u16,       // Discharge power percentage setting
+    pub ac_charge_pct: u16,          // AC charge percentage setting
+    pub chg_priority_pct: u16,       // Charging priority percentage setting
+    pub forced_dischg_pct: u16,      // Forced discharge percentage setting
+    pub inv_power_pct: u16,          // Inverter active power percentage setting
+    
+    // AC charging parameters
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ac_chg_start_v: f64,         // AC charging starting battery voltage
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ac_chg_end_v: f64,           // AC charging cut off battery voltage
+    pub ac_chg_start_soc: u16,       // AC charging starting SOC
+    pub ac_chg_end_soc: u16,         // AC charging stops SOC
+    
+    // Battery voltage parameters
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_v: f64,              // Battery undervoltage alarm point
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_back_v: f64,         // Battery undervoltage alarm recovery point
+    pub bat_low_soc: u16,            // Battery undervoltage alarm point (SOC)
+    pub bat_low_back_soc: u16,       // Battery undervoltage alarm recovery point (SOC)
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub bat_low_utility_v: f64,      // Battery undervoltage to mains voltage point
+    pub bat_low_utility_soc: u16,    // Battery undervoltage to mains SOC
+    
+    // Additional parameters
+    pub ac_chg_curr: u16,            // AC charge current
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub ongrid_eod_v: f64,           // On-grid EOD voltage
+    
+    // SOC curve data
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub soc_volt1: f64,              // SOC curve voltage point 1
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub soc_volt2: f64,              // SOC curve voltage point 2
+    pub soc_pct1: u16,               // SOC percentage point 1
+    pub soc_pct2: u16,               // SOC percentage point 2
+    pub soc_inner_resistance: u16,    // SOC curve inner resistance
+    
+    // Power settings
+    pub max_grid_input_power: u16,    // Maximum grid input power
+    pub gen_rated_power: u16,         // Generator rated power
+    
+    // Function enable flags
+    pub function_bit_flags: u16,      // Combined function enable flags
+    
+    // Additional settings
+    pub afci_threshold: u16,          // AFCI arc threshold
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub volt_watt_v1: f64,           // Volt-Watt V1 setting
+    #[nom(Parse = "Utils::le_u16_div10")]
+    pub volt_watt_v2: f64,           // Volt-Watt V2 setting
+    pub volt_watt_delay: u16,        // Volt-Watt delay time
+    pub volt_watt_p2: u16,           // Volt-Watt P2 setting
+    
+    // Timestamp and datalog
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+// }}}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadInput6 {
+    // Extended system parameters (registers 200-239)
+    pub grid_voltage_high_pure: u16,     // Grid voltage high pure limit
+    pub grid_voltage_low_pure: u16,      // Grid voltage low pure limit
+    pub grid_freq_high_pure: u16,        // Grid frequency high pure limit
+    pub grid_freq_low_pure: u16,         // Grid frequency low pure limit
+    pub grid_volt_high_delay: u16,       // Grid voltage high delay
+    pub grid_volt_low_delay: u16,        // Grid voltage low delay
+    pub grid_freq_high_delay: u16,       // Grid frequency high delay
+    pub grid_freq_low_delay: u16,        // Grid frequency low delay
+    pub grid_volt_recover_high: u16,     // Grid voltage recover high threshold
+    pub grid_volt_recover_low: u16,      // Grid voltage recover low threshold
+    pub grid_freq_recover_high: u16,     // Grid frequency recover high threshold
+    pub grid_freq_recover_low: u16,      // Grid frequency recover low threshold
+    pub grid_volt_recover_delay: u16,    // Grid voltage recover delay
+    pub grid_freq_recover_delay: u16,    // Grid frequency recover delay
+    pub island_detect_time: u16,         // Island detection time
+    pub pf_cmd_memory_en: u16,           // Power factor command memory enable
+    pub pf_cmd_memory_pf: u16,           // Power factor command memory value
+    pub pf_cmd_memory_p_ref: u16,        // Power factor command memory reference power
+    pub pf_cmd_memory_v_ref: u16,        // Power factor command memory reference voltage
+    pub pf_cmd_memory_q_ref: u16,        // Power factor command memory reference reactive power
+
+    // following are for influx capability only
+    #[nom(Parse = "Utils::current_time_for_nom")]
+    pub time: UnixTime,
+    #[nom(Ignore)]
+    pub datalog: Serial,
+}
+
+// {{{ coulomb counter
+
+/// `CoulombCounter::update`'s result: the estimate plus its drift from the
+/// BMS-reported SOC that fed it.
+#[derive(Debug, Clone, Copy)]
+pub struct CoulombCounterReading {
+    pub soc_estimated: f64,
+    pub soc_drift: f64,
+}
+
+/// Coulomb-counting SOC estimator, the same accumulator approach hardware
+/// fuel gauges use, run alongside (not instead of) the BMS-reported SOC.
+/// Integrates `bat_current` over time into a running `charge_ah`, and
+/// recalibrates to the reported SOC whenever the pack looks full (at
+/// `charge_volt_ref`, current near zero) or empty (at `dischg_cut_volt`,
+/// current near zero) so drift can't accumulate indefinitely between
+/// recalibrations. Owned by `ReadInputs`, updated on each new `ReadInput3`
+/// (the only struct that carries current, capacity, and both voltage
+/// thresholds together).
+#[derive(Debug, Clone, Default)]
+pub struct CoulombCounter {
+    charge_ah: f64,
+    last_sample_at: Option<std::time::Instant>,
+    seeded: bool,
+}
+
+impl CoulombCounter {
+    /// Voltage band (volts) around `charge_volt_ref`/`dischg_cut_volt`
+    /// within which the pack is treated as full/empty for recalibration.
+    const RECALIBRATION_VOLTAGE_BAND_V: f64 = 0.2;
+    /// Current (amps) below which the pack counts as "at rest" for
+    /// recalibration purposes.
+    const RECALIBRATION_CURRENT_BAND_A: f64 = 0.5;
+
+    pub fn update(&mut self, input: &ReadInput3) -> CoulombCounterReading {
+        let now = std::time::Instant::now();
+        let capacity_ah = input.bat_capacity as f64;
+        let soc_reported = input.soc_1 as f64;
+        let at_rest = input.bat_current.abs() < Self::RECALIBRATION_CURRENT_BAND_A;
+        let near_full = at_rest
+            && input.vbat_inv >= input.charge_volt_ref - Self::RECALIBRATION_VOLTAGE_BAND_V;
+        let near_empty = at_rest
+            && input.vbat_inv <= input.dischg_cut_volt + Self::RECALIBRATION_VOLTAGE_BAND_V;
+
+        if !self.seeded || near_full || near_empty {
+            self.charge_ah = capacity_ah * (soc_reported / 100.0);
+            self.seeded = true;
+        } else if capacity_ah > 0.0 {
+            if let Some(last_sample_at) = self.last_sample_at {
+                let dt_seconds = now.duration_since(last_sample_at).as_secs_f64();
+                // bat_current is negative on discharge, so this already
+                // decreases charge_ah on discharge without flipping sign.
+                let delta_ah = input.bat_current * dt_seconds / 3600.0;
+                self.charge_ah = (self.charge_ah + delta_ah).clamp(0.0, capacity_ah);
+            }
+        }
+        self.last_sample_at = Some(now);
+
+        let soc_estimated = if capacity_ah > 0.0 {
+            (100.0 * self.charge_ah / capacity_ah).clamp(0.0, 100.0)
+        } else {
+            soc_reported
+        };
+
+        CoulombCounterReading {
+            soc_estimated: Utils::round(soc_estimated, 1),
+            soc_drift: Utils::round(soc_estimated - soc_reported, 1),
+        }
+    }
+}
+// }}}
+
+// {{{ rolling average smoothing
+//
+// Battery gauges conventionally average over more than one ADC sample
+// before reporting a value, so a single noisy reading doesn't spike a
+// chart or trip an alert. `RollingAverage` is a fixed-size ring buffer
+// doing exactly that for one quantity; `ReadInputSmoothing` holds one per
+// noisy field this crate tracks.
+
+/// Samples kept per quantity by default, absent a caller-supplied window.
+pub const DEFAULT_ROLLING_AVERAGE_WINDOW: usize = 20;
+
+/// Fixed-size ring-buffer moving average over the last `capacity` samples
+/// of one quantity.
+#[derive(Debug, Clone)]
+pub struct RollingAverage {
+    window: std::collections::VecDeque<f64>,
+    capacity: usize,
+    sum: f64,
+}
+
+impl RollingAverage {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            window: std::collections::VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+            sum: 0.0,
+        }
+    }
+
+    /// Pushes one sample and returns the updated average.
+    pub fn push(&mut self, value: f64) -> f64 {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.capacity {
+            if let Some(oldest) = self.window.pop_front() {
+                self.sum -= oldest;
+            }
+        }
+        self.sum / self.window.len() as f64
+    }
+}
+
+impl Default for RollingAverage {
+    fn default() -> Self {
+        Self::new(DEFAULT_ROLLING_AVERAGE_WINDOW)
+    }
+}
+
+/// Latest moving averages produced by `ReadInputSmoothing`, cached on
+/// `ReadInputs` so `to_input_all` can populate `ReadInputAll`'s `*_avg`
+/// fields without re-reading the ring buffers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadInputAverages {
+    pub v_bat_avg: Option<f64>,
+    pub bat_current_avg: Option<f64>,
+    pub t_bat_avg: Option<f64>,
+    pub soc_avg: Option<f64>,
+}
+
+/// One `RollingAverage` per noisy quantity this crate smooths:
+/// `v_bat`/`soc` (from `ReadInput1`), `t_bat` (from `ReadInput2`), and
+/// `bat_current` (from `ReadInput3`). Owned by `ReadInputs`.
+#[derive(Debug, Clone)]
+pub struct ReadInputSmoothing {
+    v_bat: RollingAverage,
+    bat_current: RollingAverage,
+    t_bat: RollingAverage,
+    soc: RollingAverage,
+}
+
+impl ReadInputSmoothing {
+    pub fn new(window: usize) -> Self {
+        Self {
+            v_bat: RollingAverage::new(window),
+            bat_current: RollingAverage::new(window),
+            t_bat: RollingAverage::new(window),
+            soc: RollingAverage::new(window),
+        }
+    }
+}
+
+impl Default for ReadInputSmoothing {
+    fn default() -> Self {
+        Self::new(DEFAULT_ROLLING_AVERAGE_WINDOW)
+    }
+}
+// }}}
+
+// {{{ ReadInputs
+#[derive(Default, Clone, Debug)]
+pub struct ReadInputs {
+    read_input_1: Option<ReadInput1>,
+    read_input_2: Option<ReadInput2>,
+    read_input_3: Option<ReadInput3>,
+    read_input_4: Option<ReadInput4>,
+    read_input_5: Option<ReadInput5>,
+    coulomb_counter: CoulombCounter,
+    last_coulomb_reading: Option<CoulombCounterReading>,
+    smoothing: ReadInputSmoothing,
+    last_averages: ReadInputAverages,
+}
+
+impl ReadInputs {
+    pub fn set_read_input_1(&mut self, i: ReadInput1) {
+        if let Some(v_bat) = i.v_bat {
+            self.last_averages.v_bat_avg = Some(self.smoothing.v_bat.push(v_bat));
+        }
+        self.last_averages.soc_avg = Some(self.smoothing.soc.push(i.soc as f64));
+        self.read_input_1 = Some(i);
+    }
+    pub fn set_read_input_2(&mut self, i: ReadInput2) {
+        self.last_averages.t_bat_avg = Some(self.smoothing.t_bat.push(i.t_bat as f64));
+        self.read_input_2 = Some(i);
+    }
+    pub fn set_read_input_3(&mut self, i: ReadInput3) {
+        self.last_coulomb_reading = Some(self.coulomb_counter.update(&i));
+        self.last_averages.bat_current_avg = Some(self.smoothing.bat_current.push(i.bat_current));
+        self.read_input_3 = Some(i);
+    }
+    pub fn set_read_input_4(&mut self, i: ReadInput4) {
+        self.read_input_4 = Some(i);
+    }
+    pub fn set_read_input_5(&mut self, i: ReadInput5) {
+        self.read_input_5 = Some(i);
+    }
+
+    pub fn to_input_all(&self) -> Option<ReadInputAll> {
+        if let (Some(r1), Some(r2), Some(r3), Some(r4), Some(r5), Some(r6)) = (
+            &self.read_input_1,
+            &self.read_input_2,
+            &self.read_input_3,
+            &self.read_input_4,
+            &self.read_input_5,
+            &self.read_input_6,
+        ) {
+            let mut all = ReadInputAll {
+                status: r1.status,
+                v_pv_1: r1.v_pv_1,
+                v_pv_2: r1.v_pv_2,
+                v_pv_3: r1.v_pv_3,
+                v_bat: r1.v_bat,
+                soc: r1.soc,
+                soh: r1.soh,
+                internal_fault: r1.internal_fault,
+                p_pv: r1.p_pv,
+                p_pv_1: r1.p_pv_1,
+                p_pv_2: r1.p_pv_2,
+                p_pv_3: r1.p_pv_3,
+                p_battery: r1.p_battery,
+                p_charge: r1.p_charge,
+                p_discharge: r1.p_discharge,
+                v_ac_r: r1.v_ac_r,
+                v_ac_s: r1.v_ac_s,
+                v_ac_t: r1.v_ac_t,
+                f_ac: r1.f_ac,
+                p_inv: r1.p_inv,
+                p_rec: r1.p_rec,
+                pf: r1.pf,
+                v_eps_r: r1.v_eps_r,
+                v_eps_s: r1.v_eps_s,
+                v_eps_t: r1.v_eps_t,
+                f_eps: r1.f_eps,
+                p_eps: r1.p_eps,
+                s_eps: r1.s_eps,
+                p_grid: r1.p_grid,
+                p_to_grid: r1.p_to_grid,
+                p_to_user: r1.p_to_user,
+                e_pv_day: r1.e_pv_day,
+                e_pv_day_1: r1.e_pv_day_1,
+                e_pv_day_2: r1.e_pv_day_2,
+                e_pv_day_3: r1.e_pv_day_3,
+                e_inv_day: r1.e_inv_day,
+                e_rec_day: r1.e_rec_day,
+                e_chg_day: r1.e_chg_day,
+                e_dischg_day: r1.e_dischg_day,
+                e_eps_day: r1.e_eps_day,
+                e_to_grid_day: r1.e_to_grid_day,
+                e_to_user_day: r1.e_to_user_day,
+                v_bus_1: r1.v_bus_1,
+                v_bus_2: r1.v_bus_2,
+                e_pv_all: r2.e_pv_all,
+                e_pv_all_1: r2.e_pv_all_1,
+                e_pv_all_2: r2.e_pv_all_2,
+                e_pv_all_3: r2.e_pv_all_3,
+                e_inv_all: r2.e_inv_all,
+                e_rec_all: r2.e_rec_all,
+                e_chg_all: r2.e_chg_all,
+                e_dischg_all: r2.e_dischg_all,
+                e_eps_all: r2.e_eps_all,
+                e_to_grid_all: r2.e_to_grid_all,
+                e_to_user_all: r2.e_to_user_all,
+                fault_code: r2.fault_code,
+                warning_code: r2.warning_code,
+                t_inner: r2.t_inner,
+                t_rad_1: r2.t_rad_1,
+                t_rad_2: r2.t_rad_2,
+                t_bat: r2.t_bat,
+                runtime: r2.runtime,
+                max_chg_curr: r3.max_chg_curr,
+                max_dischg_curr: r3.max_dischg_curr,
+                charge_volt_ref: r3.charge_volt_ref,
+                dischg_cut_volt: r3.dischg_cut_volt,
+                bat_status_0: r3.bat_status_0,
+                bat_status_1: r3.bat_status_1,
+                bat_status_2: r3.bat_status_2,
+                bat_status_3: r3.bat_status_3,
+                bat_status_4: r3.bat_status_4,
+                bat_status_5: r3.bat_status_5,
+                bat_status_6: r3.bat_status_6,
+                bat_status_7: r3.bat_status_7,
+                bat_status_8: r3.bat_status_8,
+                bat_status_9: r3.bat_status_9,
+                bat_status_inv: r3.bat_status_inv,
+                bat_count: r3.bat_count,
+                bat_capacity: r3.bat_capacity,
+                bat_current: r3.bat_current,
+                bms_event_1: r3.bms_event_1,
+                bms_event_2: r3.bms_event_2,
+                max_cell_voltage: r3.max_cell_voltage,
+                min_cell_voltage: r3.min_cell_voltage,
+                max_cell_temp: r3.max_cell_temp,
+                min_cell_temp: r3.min_cell_temp,
+                cell_voltage_delta: 0.0,
+                cell_temp_delta: 0.0,
+                cell_imbalance_alarm: false,
+                t_bat_1: r3.t_bat_1,
+                t_bat_2: r3.t_bat_2,
+                t_bat_3: r3.t_bat_3,
+                t_bat_4: r3.t_bat_4,
+                v_bat_1: r3.v_bat_1,
+                v_bat_2: r3.v_bat_2,
+                v_bat_3: r3.v_bat_3,
+                v_bat_4: r3.v_bat_4,
+                i_bat_1: r3.i_bat_1,
+                i_bat_2: r3.i_bat_2,
+                i_bat_3: r3.i_bat_3,
+                i_bat_4: r3.i_bat_4,
+                soc_1: r3.soc_1,
+                soc_2: r3.soc_2,
+                soc_3: r3.soc_3,
+                soc_4: r3.soc_4,
+                soh_1: r3.soh_1,
+                soh_2: r3.soh_2,
+                soh_3: r3.soh_3,
+                soh_4: r3.soh_4,
+                battery_modules: Vec::new(),
+                worst_module_soh: None,
+                max_module_soc_spread: None,
+                max_module_temp_spread: None,
+                outlier_module_index: None,
+                bms_fw_update_state: r3.bms_fw_update_state,
+                cycle_count: r3.cycle_count,
+                vbat_inv: r3.vbat_inv,
+                gen_status: 0,          // Default value since not available in input
+                gen_power_factor: 0,    // Default value since not available in input
+                gen_current: 0,         // Default value since not available in input
+                gen_power_limit: 0,     // Default value since not available in input
+                gen_connect_status: 0,  // Default value since not available in input
+                gen_control_mode: 0,    // Default value since not available in input
+                gen_dispatch_mode: 0,   // Default value since not available in input
+                v_bus_half: 0.0,       // Default value since not available in input
+                v_gen: r4.v_gen,
+                f_gen: r4.f_gen,
+                p_gen: r4.p_gen,
+                e_gen_day: r4.e_gen_day,
+                e_gen_all: r4.e_gen_all,
+                v_eps_l1: r4.v_eps_l1,
+                v_eps_l2: r4.v_eps_l2,
+                p_eps_l1: r4.p_eps_l1,
+                p_eps_l2: r4.p_eps_l2,
+                s_eps_l1: r4.s_eps_l1,
+                s_eps_l2: r4.s_eps_l2,
+                e_eps_l1_day: r4.e_eps_l1_day,
+                e_eps_l2_day: r4.e_eps_l2_day,
+                e_eps_l1_all: r4.e_eps_l1_all,
+                e_eps_l2_all: r4.e_eps_l2_all,
+                i_eps_l1: r4.i_eps_l1,
+                i_eps_l2: r4.i_eps_l2,
+                pf_eps_l1: r4.pf_eps_l1,
+                pf_eps_l2: r4.pf_eps_l2,
+                f_eps_l1: r4.f_eps_l1,
+                f_eps_l2: r4.f_eps_l2,
+                datalog: r1.datalog,
+                time: r1.time.clone(),
+                bat_cell_count: r5.bat_cell_count,
+                bat_parallel_count: r5.bat_parallel_count,
+                under_freq_start: r5.under_freq_start,
+                under_freq_end: r5.under_freq_end,
+                under_freq_slope: r5.under_freq_slope,
+                max_compensation: r5.max_compensation,
+                chg_power_pct: r5.chg_power_pct,
+                dischg_power_pct: r5.dischg_power_pct,
+                ac_charge_pct: r5.ac_charge_pct,
+                chg_priority_pct: r5.chg_priority_pct,
+                forced_dischg_pct: r5.forced_dischg_pct,
+                inv_power_pct: r5.inv_power_pct,
+                ac_chg_start_v: r5.ac_chg_start_v,
+                ac_chg_end_v: r5.ac_chg_end_v,
+                ac_chg_start_soc: r5.ac_chg_start_soc,
+                ac_chg_end_soc: r5.ac_chg_end_soc,
+                bat_low_v: r5.bat_low_v,
+                bat_low_back_v: r5.bat_low_back_v,
+                bat_low_soc: r5.bat_low_soc,
+                bat_low_back_soc: r5.bat_low_back_soc,
+                bat_low_utility_v: r5.bat_low_utility_v,
+                bat_low_utility_soc: r5.bat_low_utility_soc,
+                ac_chg_curr: r5.ac_chg_curr,
+                ongrid_eod_v: r5.ongrid_eod_v,
+                soc_volt1: r5.soc_volt1,
+                soc_volt2: r5.soc_volt2,
+                soc_pct1: r5.soc_pct1,
+                soc_pct2: r5.soc_pct2,
+                soc_inner_resistance: r5.soc_inner_resistance,
+                soc_ocv: None,
+                soc_estimated: self.last_coulomb_reading.map(|r| r.soc_estimated),
+                soc_drift: self.last_coulomb_reading.map(|r| r.soc_drift),
+                v_bat_avg: self.last_averages.v_bat_avg,
+                bat_current_avg: self.last_averages.bat_current_avg,
+                t_bat_avg: self.last_averages.t_bat_avg,
+                soc_avg: self.last_averages.soc_avg,
+                max_grid_input_power: r5.max_grid_input_power,
+                gen_rated_power: r5.gen_rated_power,
+                function_bit_flags: r5.function_bit_flags,
+                afci_threshold: r5.afci_threshold,
+                volt_watt_v1: r5.volt_watt_v1,
+                volt_watt_v2: r5.volt_watt_v2,
+                volt_watt_delay: r5.volt_watt_delay,
+                volt_watt_p2: r5.volt_watt_p2,
+                grid_voltage_high_pure: r6.grid_voltage_high_pure,
+                grid_voltage_low_pure: r6.grid_voltage_low_pure,
+                grid_freq_high_pure: r6.grid_freq_high_pure,
+                grid_freq_low_pure: r6.grid_freq_low_pure,
+                grid_volt_high_delay: r6.grid_volt_high_delay,
+                grid_volt_low_delay: r6.grid_volt_low_delay,
+                grid_freq_high_delay: r6.grid_freq_high_delay,
+                grid_freq_low_delay: r6.grid_freq_low_delay,
+                grid_volt_recover_high: r6.grid_volt_recover_high,
+                grid_volt_recover_low: r6.grid_volt_recover_low,
+                grid_freq_recover_high: r6.grid_freq_recover_high,
+                grid_freq_recover_low: r6.grid_freq_recover_low,
+                grid_volt_recover_delay: r6.grid_volt_recover_delay,
+                grid_freq_recover_delay: r6.grid_freq_recover_delay,
+                island_detect_time: r6.island_detect_time,
+                pf_cmd_memory_en: r6.pf_cmd_memory_en,
+                pf_cmd_memory_pf: r6.pf_cmd_memory_pf,
+                pf_cmd_memory_p_ref: r6.pf_cmd_memory_p_ref,
+                pf_cmd_memory_v_ref: r6.pf_cmd_memory_v_ref,
+                pf_cmd_memory_q_ref: r6.pf_cmd_memory_q_ref,
+            };
+
+            // Calculate derived values
+            if let Err(e) = all.calculate_derived_values() {
+                error!("Failed to calculate derived values: {}", e);
+                return None;
+            }
+
+            // Validate the result
+            if let Err(e) = all.validate() {
+                error!("Validation failed for ReadInputAll: {}", e);
+                return None;
+            }
+
+            Some(all)
+        } else {
+            None
+        }
+    }
+}
+// }}}
+
+// {{{ TcpFunction
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum TcpFunction {
+    Heartbeat = 193,
+    TranslatedData = 194,
+    ReadParam = 195,
+    WriteParam = 196,
+}
+// }}}
+
+// {{{ DeviceFunction
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum DeviceFunction {
+    ReadHold = 3,
+    ReadInput = 4,
+    WriteSingle = 6,
+    WriteMulti = 16,
+    // UpdatePrepare = 33
+    // UpdateSendData = 34
+    // UpdateReset = 35
+    // ReadHoldError = 131
+    // ReadInputError = 132
+    // WriteSingleError = 134
+    // WriteMultiError = 144
+}
+// }}}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u16)]
+pub enum Register {
+    Register21 = 21,             // not sure of a better name for this one..
+    ChargePowerPercentCmd = 64,  // System Charge Rate (%)
+    DischgPowerPercentCmd = 65,  // System Discharge Rate (%)
+    AcChargePowerCmd = 66,       // Grid Charge Power Rate (%)
+    AcChargeSocLimit = 67,       // AC Charge SOC Limit (%)
+    ChargePriorityPowerCmd = 74, // Charge Priority Charge Rate (%)
+    ChargePrioritySocLimit = 75, // Charge Priority SOC Limit (%)
+    ForcedDischgSocLimit = 83,   // Forced Discarge SOC Limit (%)
+    DischgCutOffSocEod = 105,    // Discharge cut-off SOC (%)
+    EpsDischgCutoffSocEod = 125, // EPS Discharge cut-off SOC (%)
+    AcChargeStartSocLimit = 160, // SOC at which AC charging will begin (%)
+    AcChargeEndSocLimit = 161,   // SOC at which AC charging will end (%)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u16)]
+pub enum RegisterBit {
+    // Register 21
+    AcChargeEnable = 1 << 7,
+    ForcedDischargeEnable = 1 << 10,
+    ChargePriorityEnable = 1 << 11,
+}
+
+// Register21Bits {{{
+#[derive(Clone, Debug, Serialize)]
+pub struct Register21Bits {
+    pub eps_en: String,
+    pub ovf_load_derate_en: String,
+    pub drms_en: String,
+    pub lvrt_en: String,
+    pub anti_island_en: String,
+    pub neutral_detect_en: String,
+    pub grid_on_power_ss_en: String,
+    pub ac_charge_en: String,
+    pub sw_seamless_en: String,
+    pub set_to_standby: String,
+    pub forced_discharge_en: String,
+    pub charge_priority_en: String,
+    pub iso_en: String,
+    pub gfci_en: String,
+    pub dci_en: String,
+    pub feed_in_grid_en: String,
+}
+
+impl Register21Bits {
+    fn is_bit_set(data: u16, bit: u16) -> String {
+        if (data & bit) == bit {
+            "ON".to_string()
+        } else {
+            "OFF".to_string()
+        }
+    }
+
+    pub fn new(data: u16) -> Self {
+        Self {
+            eps_en: Self::is_bit_set(data, 1 << 0),
+            ovf_load_derate_en: Self::is_bit_set(data, 1 << 1),
+            drms_en: Self::is_bit_set(data, 1 << 2),
+            lvrt_en: Self::is_bit_set(data, 1 << 3),
+            anti_island_en: Self::is_bit_set(data, 1 << 4),
+            neutral_detect_en: Self::is_bit_set(data, 1 << 5),
+            grid_on_power_ss_en: Self::is_bit_set(data, 1 << 6),
+            ac_charge_en: Self::is_bit_set(data, 1 << 7),
+            sw_seamless_en: Self::is_bit_set(data, 1 << 8),
+            set_to_standby: Self::is_bit_set(data, 1 << 9),
+            forced_discharge_en: Self::is_bit_set(data, 1 << 10),
+            charge_priority_en: Self::is_bit_set(data, 1 << 11),
+            iso_en: Self::is_bit_set(data, 1 << 12),
+            gfci_en: Self::is_bit_set(data, 1 << 13),
+            dci_en: Self::is_bit_set(data, 1 << 14),
+            feed_in_grid_en: Self::is_bit_set(data, 1 << 15),
+        }
+    }
+}
+// }}}
+
+// Register110Bits {{{
+#[derive(Clone, Debug, Serialize)]
+pub struct Register110Bits {
+    pub ub_pv_grid_off_en: String,
+    pub ub_run_without_grid: String,
+    pub ub_micro_grid_en: String,
+}
+impl Register110Bits {
+    fn is_bit_set(data: u16, bit: u16) -> String {
+        if (data & bit) == bit {
+            "ON".to_string()
+        } else {
+            "OFF".to_string()
+        }
+    }
+
+    pub fn new(data: u16) -> Self {
+        Self {
+            ub_pv_grid_off_en: Self::is_bit_set(data, 1 << 0),
+            ub_run_without_grid: Self::is_bit_set(data, 1 << 1),
+            ub_micro_grid_en: Self::is_bit_set(data, 1 << 2),
+        }
+    }
+}
+// }}}
+
+#[enum_dispatch]
+pub trait PacketCommon {
+    fn datalog(&self) -> Serial;
+    fn set_datalog(&mut self, datalog: Serial);
+    fn inverter(&self) -> Option<Serial>;
+    fn set_inverter(&mut self, serial: Serial);
+    fn protocol(&self) -> u16;
+    fn tcp_function(&self) -> TcpFunction;
+    fn bytes(&self) -> Vec<u8>;
+
+    fn register(&self) -> u16 {
+        unimplemented!("register() not implemented");
+    }
+    fn value(&self) -> u16 {
+        unimplemented!("value() not implemented");
+    }
+}
+
+pub struct TcpFrameFactory;
+impl TcpFrameFactory {
+    pub fn build(data: &Packet) -> Vec<u8> {
+        let data_bytes = data.bytes();
+        let data_length = data_bytes.len() as u8;
+        let frame_length = (18 + data_length) as u16;
+
+        // debug!("data_length={}, frame_length={}", data_length, frame_length);
+
+        let mut r = vec![0; frame_length as usize];
+
+        r[0] = 161;
+        r[1] = 26;
+        r[2..4].copy_from_slice(&data.protocol().to_le_bytes());
+        r[4..6].copy_from_slice(&(frame_length - 6).to_le_bytes());
+        r[6] = 1; // unsure what this is, always seems to be 1
+        r[7] = data.tcp_function() as u8;
+
+        r[8..18].copy_from_slice(&data.datalog().data());
+        // WIP - trying to work out how to learn the inverter sn
+        //r[8..18].copy_from_slice(&[0; 10]);
+
+        r[18..].copy_from_slice(&data_bytes);
+
+        r
+    }
+}
+
+/// Bare Modbus-RTU framing for inverters wired directly over RS485 rather
+/// than via a TCP datalogger. There's no 18-byte datalogger envelope and no
+/// inverter/datalog serial on the wire - just the standard
+/// `[unit_addr, function, register_hi, register_lo, ...payload, crc_lo, crc_hi]`
+/// shape, addressed by a single Modbus unit id instead of a 10-byte serial.
+pub struct SerialFrameFactory;
+impl SerialFrameFactory {
+    pub fn build(data: &TranslatedData, unit_addr: u8) -> Vec<u8> {
+        let mut r = vec![unit_addr, data.device_function as u8];
+        r.extend_from_slice(&data.register.to_be_bytes());
+        r.extend_from_slice(&data.values);
+        r.extend_from_slice(&TranslatedData::checksum(&r));
+
+        r
+    }
+}
+
+/// Decodes frames built by [`SerialFrameFactory`]. Since the RS485 wire
+/// format carries neither a datalog nor an inverter serial, the caller has
+/// to supply both - it already knows which inverter it's talking to from
+/// the serial port/unit address it addressed the request to.
+pub struct SerialParser;
+impl SerialParser {
+    pub fn decode(input: &[u8], datalog: Serial, inverter: Serial) -> Result<TranslatedData> {
+        let len = input.len();
+        if len < 6 {
+            bail!("SerialParser::decode frame too short");
+        }
+
+        let frame = &input[..len - 2];
+        let checksum = &input[len - 2..];
+        if TranslatedData::checksum(frame) != checksum {
+            bail!(
+                "SerialParser::decode checksum mismatch - got {:?}, expected {:?}",
+                checksum,
+                TranslatedData::checksum(frame)
+            );
+        }
+
+        let device_function = DeviceFunction::try_from(frame[1])?;
+        let register = u16::from_be_bytes([frame[2], frame[3]]);
+        let values = frame[4..].to_vec();
+
+        Ok(TranslatedData {
+            datalog,
+            device_function,
+            inverter,
+            register,
+            values,
+            checksum_valid: true,
+        })
+    }
+}
+
+#[enum_dispatch(PacketCommon)]
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Packet {
+    Heartbeat(Heartbeat),
+    TranslatedData(TranslatedData),
+    ReadParam(ReadParam),
+    WriteParam(WriteParam),
+}
+
+#[derive(PartialEq)]
+enum PacketSource {
+    Inverter,
+    Client,
+}
+
+/////////////
+//
+// HEARTBEATS
+//
+/////////////
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Heartbeat {
+    pub datalog: Serial,
+}
+impl Heartbeat {
+    fn decode(input: &[u8]) -> Result<Self> {
+        let len = input.len();
+        if len < 19 {
+            bail!("heartbeat packet too short");
+        }
+
+        // assert that the final byte is 0, meaning 0 data bytes follow it
+        if input[18] != 0 {
+            bail!("heartbeat with non-zero ({}) length byte?", input[18]);
+        }
+
+        let datalog = Serial::new(&input[8..18])?;
+
+        Ok(Self { datalog })
+    }
+}
+
+impl PacketCommon for Heartbeat {
+    fn protocol(&self) -> u16 {
+        2
+    }
+
+    fn datalog(&self) -> Serial {
+        self.datalog
+    }
+    fn set_datalog(&mut self, datalog: Serial) {
+        self.datalog = datalog;
+    }
+    fn inverter(&self) -> Option<Serial> {
+        None
+    }
+    fn set_inverter(&mut self, _datalog: Serial) {}
+
+    fn tcp_function(&self) -> TcpFunction {
+        TcpFunction::Heartbeat
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        vec![0]
+    }
+}
+
+/////////////
+//
+// TRANSLATED DATA
+//
+/////////////
+
+/// Controls how `TranslatedData::decode_with_mode`/`Parser::parse_with_mode`
+/// treat a CRC16/MODBUS mismatch on an incoming frame. Mirrors how
+/// register-transfer drivers let the caller choose whether checksum
+/// validation is enforced - useful for reverse-engineering firmware variants
+/// that checksum over a different byte range, or for replaying
+/// partially-corrupted pcap/log captures that `Verify` would otherwise
+/// discard outright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumMode {
+    /// Bail on a checksum mismatch. The default, and the only mode used by
+    /// the plain `decode`/`parse`.
+    #[default]
+    Verify,
+    /// Decode the payload regardless of whether the checksum matches, and
+    /// record the outcome in `checksum_valid`.
+    Ignore,
+    /// Like `Ignore`, but also logs the computed/received CRC bytes for
+    /// diagnosing capture/firmware mismatches.
+    Report,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct TranslatedData {
+    pub datalog: Serial,
+    pub device_function: DeviceFunction, // ReadHold or ReadInput etc..
+    pub inverter: Serial,                // inverter serial
+    pub register: u16,                   // first register of values
+    pub values: Vec<u8>,                 // undecoded, since can be u16 or u32s?
+    pub checksum_valid: bool,            // false only when decoded via ChecksumMode::Ignore/Report with a CRC mismatch
+}
+impl TranslatedData {
+    pub fn pairs(&self) -> Vec<(u16, u16)> {
+        self.values
+            .chunks(2)
+            .enumerate()
+            .map(|(pos, value)| (self.register + pos as u16, Utils::u16ify(value, 0)))
+            .collect()
+    }
+
+    /// Decodes this packet's raw `pairs()` against `parser`'s register
+    /// definitions, coalescing 32-bit registers with their partner word and
+    /// applying signedness/scale - the same decode path `RegisterParser`
+    /// already uses for modbus-polled raw data, applied here to packets
+    /// coming off the wire instead. Unknown registers are skipped.
+    pub fn decoded_registers(&self, parser: &crate::register::RegisterParser) -> Vec<crate::register::DecodedRegister> {
+        let pairs = self.pairs();
+        let mut out = Vec::new();
+        let mut i = 0;
+
+        while i < pairs.len() {
+            let (reg_num, raw) = pairs[i];
+            let Some(register) = parser.get_register(reg_num) else {
+                i += 1;
+                continue;
+            };
+
+            let hex_value = format!("{:04x}", raw);
+
+            let value = if register.is_32bit() {
+                match pairs.get(i + 1) {
+                    Some((partner_num, partner_raw)) if *partner_num == reg_num + 1 => {
+                        i += 1;
+                        register.decode_pair(&hex_value, &format!("{:04x}", partner_raw))
+                    }
+                    // partner word isn't in this packet; skip rather than
+                    // publish a half-built 32-bit value
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                }
+            } else {
+                register.decode_value(&hex_value)
+            };
+
+            let name = if !register.shortname.is_empty() {
+                register.shortname.clone()
+            } else {
+                register.name.clone()
+            };
+
+            out.push(crate::register::DecodedRegister {
+                name,
+                value,
+                unit: register.unit.clone(),
+            });
+
+            i += 1;
+        }
+
+        out
+    }
+
+    /// Builds a read request for the register named `name` in `parser`,
+    /// reading `count` consecutive u16 registers - same `values` shape as a
+    /// hand-built `read_input`/`read_hold` packet (`[count as u8, 0]`) - so
+    /// callers can request registers by name instead of a magic number.
+    pub fn read_register(
+        datalog: Serial,
+        inverter: Serial,
+        device_function: DeviceFunction,
+        parser: &crate::register::RegisterParser,
+        name: &str,
+        count: u16,
+    ) -> Result<Self> {
+        let register = parser
+            .find_by_name(name)
+            .ok_or_else(|| anyhow!("unknown register name: {}", name))?
+            .register_number;
+
+        Ok(Self {
+            datalog,
+            device_function,
+            inverter,
+            register,
+            values: vec![count as u8, 0],
+            checksum_valid: true,
+        })
+    }
+
+    /// Builds a single-register write request for the register named `name`
+    /// in `parser`, rather than a magic number.
+    pub fn write_register(
+        datalog: Serial,
+        inverter: Serial,
+        parser: &crate::register::RegisterParser,
+        name: &str,
+        value: u16,
+    ) -> Result<Self> {
+        let register = parser
+            .find_by_name(name)
+            .ok_or_else(|| anyhow!("unknown register name: {}", name))?
+            .register_number;
+
+        Ok(Self {
+            datalog,
+            device_function: DeviceFunction::WriteSingle,
+            inverter,
+            register,
+            values: value.to_le_bytes().to_vec(),
+            checksum_valid: true,
+        })
+    }
+
+    pub fn read_input(&self) -> Result<ReadInput> {
+        match (self.register, self.values.len()) {
+            (0, 254) => Ok(ReadInput::ReadInputAll(Box::new(self.read_input_all()?))),
+            (0, 80) => Ok(ReadInput::ReadInput1(self.read_input1()?)),
+            (40, 80) => Ok(ReadInput::ReadInput2(self.read_input2()?)),
+            (80, 80) => Ok(ReadInput::ReadInput3(self.read_input3()?)),
+            (120, 80) => Ok(ReadInput::ReadInput4(self.read_input4()?)),
+            (160, 80) => Ok(ReadInput::ReadInput5(self.read_input5()?)),
+            (200, 80) => Ok(ReadInput::ReadInput6(self.read_input6()?)),
+            (r1, r2) => bail!("unhandled ReadInput register={} len={}", r1, r2),
+        }
+    }
+
+    fn read_input_all(&self) -> Result<ReadInputAll> {
+        match ReadInputAll::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.p_pv = u16::from(r.p_pv_1)
+                    .checked_add(u16::from(r.p_pv_2))
+                    .and_then(|sum| sum.checked_add(r.p_pv_3))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_pv calculation"))?;
+                r.p_grid = i32::from(r.p_to_user)
+                    .checked_sub(i32::from(r.p_to_grid))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_grid calculation"))?;
+                r.p_battery = i32::from(r.p_charge)
+                    .checked_sub(i32::from(r.p_discharge))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_battery calculation"))?;
+                r.e_pv_day = Utils::round(r.e_pv_day_1 + r.e_pv_day_2 + r.e_pv_day_3, 1);
+                r.e_pv_all = Utils::round(r.e_pv_all_1 + r.e_pv_all_2 + r.e_pv_all_3, 1);
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("meh")),
+        }
+    }
+
+    fn read_input1(&self) -> Result<ReadInput1> {
+        match ReadInput1::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.p_pv = u16::from(r.p_pv_1)
+                    .checked_add(u16::from(r.p_pv_2))
+                    .and_then(|sum| sum.checked_add(r.p_pv_3))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_pv calculation"))?;
+                r.p_grid = i32::from(r.p_to_user)
+                    .checked_sub(i32::from(r.p_to_grid))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_grid calculation"))?;
+                r.p_battery = i32::from(r.p_charge)
+                    .checked_sub(i32::from(r.p_discharge))
+                    .ok_or_else(|| anyhow!("Power value overflow in p_battery calculation"))?;
+                r.e_pv_day = Utils::round(r.e_pv_day_1 + r.e_pv_day_2 + r.e_pv_day_3, 1);
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("meh")),
+        }
+    }
+
+    fn read_input2(&self) -> Result<ReadInput2> {
+        match ReadInput2::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.e_pv_all = Utils::round(r.e_pv_all_1 + r.e_pv_all_2 + r.e_pv_all_3, 1);
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("meh")),
+        }
+    }
+
+    fn read_input3(&self) -> Result<ReadInput3> {
+        match ReadInput3::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("meh")),
+        }
+    }
+
+    fn read_input4(&self) -> Result<ReadInput4> {
+        match ReadInput4::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("meh")),
+        }
+    }
+
+    fn read_input5(&self) -> Result<ReadInput5> {
+        match ReadInput5::parse(&self.values) {
+            Ok((_, mut r)) => {
+                r.datalog = self.datalog;
+                Ok(r)
+            }
+            Err(_) => Err(anyhow!("Failed to parse ReadInput5")),
+        }
+    }
+
+    fn read_input6(&self) -> Result<ReadInput6> {
+        let mut input = ReadInput6 {
+            grid_voltage_high_pure: 0,
+            grid_voltage_low_pure: 0,
+            grid_freq_high_pure: 0,
+            grid_freq_low_pure: 0,
+            grid_volt_high_delay: 0,
+            grid_volt_low_delay: 0,
+            grid_freq_high_delay: 0,
+            grid_freq_low_delay: 0,
+            grid_volt_recover_high: 0,
+            grid_volt_recover_low: 0,
+            grid_freq_recover_high: 0,
+            grid_freq_recover_low: 0,
+            grid_volt_recover_delay: 0,
+            grid_freq_recover_delay: 0,
+            island_detect_time: 0,
+            pf_cmd_memory_en: 0,
+            pf_cmd_memory_pf: 0,
+            pf_cmd_memory_p_ref: 0,
+            pf_cmd_memory_v_ref: 0,
+            pf_cmd_memory_q_ref: 0,
+            time: UnixTime::now(),
+            datalog: self.datalog,
+        };
+
+        let pairs = self.pairs();
+        for (i, (_, value)) in pairs.iter().enumerate() {
+            match i {
+                0 => input.grid_voltage_high_pure = *value,
+                1 => input.grid_voltage_low_pure = *value,
+                2 => input.grid_freq_high_pure = *value,
+                3 => input.grid_freq_low_pure = *value,
+                4 => input.grid_volt_high_delay = *value,
+                5 => input.grid_volt_low_delay = *value,
+                6 => input.grid_freq_high_delay = *value,
+                7 => input.grid_freq_low_delay = *value,
+                8 => input.grid_volt_recover_high = *value,
+                9 => input.grid_volt_recover_low = *value,
+                10 => input.grid_freq_recover_high = *value,
+                11 => input.grid_freq_recover_low = *value,
+                12 => input.grid_volt_recover_delay = *value,
+                13 => input.grid_freq_recover_delay = *value,
+                14 => input.island_detect_time = *value,
+                15 => input.pf_cmd_memory_en = *value,
+                16 => input.pf_cmd_memory_pf = *value,
+                17 => input.pf_cmd_memory_p_ref = *value,
+                18 => input.pf_cmd_memory_v_ref = *value,
+                19 => input.pf_cmd_memory_q_ref = *value,
+                _ => {}
+            }
+        }
+
+        Ok(input)
+    }
+
+    fn decode(input: &[u8]) -> Result<Self> {
+        Self::decode_with_mode(input, ChecksumMode::Verify)
+    }
+
+    /// Like `decode`, but `mode` controls whether a CRC16/MODBUS mismatch is
+    /// fatal. See `ChecksumMode`.
+    pub fn decode_with_mode(input: &[u8], mode: ChecksumMode) -> Result<Self> {
+        let len = input.len();
+        if len < 38 {
+            bail!("TranslatedData::decode packet too short");
+        }
+
+        let protocol = Utils::u16ify(input, 2);
+        let datalog = Serial::new(&input[8..18])?;
+
+        let data = &input[20..len - 2];
+
+        let checksum = &input[len - 2..];
+        let computed_checksum = Self::checksum(data);
+        let checksum_valid = computed_checksum == checksum;
+
+        if !checksum_valid {
+            match mode {
+                ChecksumMode::Verify => bail!(
+                    "TranslatedData::decode checksum mismatch - got {:?}, expected {:?}",
+                    checksum,
+                    computed_checksum
+                ),
+                ChecksumMode::Ignore => {}
+                ChecksumMode::Report => warn!(
+                    "TranslatedData::decode checksum mismatch (ignored) - got {:?}, expected {:?}",
+                    checksum, computed_checksum
+                ),
+            }
+        }
+
+        //let address = data[0]; // 0=client, 1=inverter?
+        let device_function = DeviceFunction::try_from(data[1])?;
+        let inverter = Serial::new(&data[2..12])?;
+        let register = Utils::u16ify(data, 12);
+
+        let mut value_len = 2;
+        let mut value_offset = 14;
+
+        if Self::has_value_length_byte(PacketSource::Inverter, protocol, device_function) {
+            value_len = data[value_offset] as usize;
+            value_offset += 1;
+        }
+
+        let values = data[value_offset..].to_vec();
+
+        if values.len() != value_len {
+            bail!(
+                "TranslatedData::decode mismatch: values.len()={}, value_length_byte={}",
+                values.len(),
+                value_len
+            );
+        }
+
+        Ok(Self {
+            datalog,
+            device_function,
+            inverter,
+            register,
+            values,
+            checksum_valid,
+        })
+    }
+
+    fn has_value_length_byte(
+        source: PacketSource,
+        protocol: u16,
+        device_function: DeviceFunction,
+    ) -> bool {
+        use DeviceFunction::*;
+
+        let p1 = protocol == 1;
+        let psi = source == PacketSource::Inverter;
+        match device_function {
+            ReadHold | ReadInput => !p1 && psi,
+            WriteSingle => false,
+            WriteMulti => !p1 && !psi,
+        }
+    }
+
+    fn checksum(data: &[u8]) -> [u8; 2] {
+        crc16::State::<crc16::MODBUS>::calculate(data).to_le_bytes()
+    }
+}
+
+impl PacketCommon for TranslatedData {
+    fn protocol(&self) -> u16 {
+        if self.device_function == DeviceFunction::WriteMulti {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn datalog(&self) -> Serial {
+        self.datalog
+    }
+    fn set_datalog(&mut self, datalog: Serial) {
+        self.datalog = datalog;
+    }
+
+    fn inverter(&self) -> Option<Serial> {
+        Some(self.inverter)
+    }
+    fn set_inverter(&mut self, serial: Serial) {
+        self.inverter = serial;
+    }
+
+    fn tcp_function(&self) -> TcpFunction {
+        TcpFunction::TranslatedData
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        let mut data = vec![0; 16];
+
+        // data[2] (address) is 0 when writing to inverter, 1 when reading from it
+        data[3] = self.device_function as u8;
+
+        // experimental: looks like maybe you don't need to fill this in..
+        data[4..14].copy_from_slice(&self.inverter.data());
+        //data[4..14].copy_from_slice(&[0; 10]);
+
+        data[14..16].copy_from_slice(&self.register.to_le_bytes());
+
+        if self.device_function == DeviceFunction::WriteMulti {
+            let register_count = self.pairs().len() as u16;
+            data.extend_from_slice(&register_count.to_le_bytes());
+        }
+
+        if Self::has_value_length_byte(PacketSource::Client, self.protocol(), self.device_function)
+        {
+            let len = self.values.len() as u8;
+            data.extend_from_slice(&[len]);
+        }
+
+        let mut m = Vec::new();
+        for i in &self.values {
+            m.extend_from_slice(&i.to_le_bytes());
+        }
+        data.append(&mut m);
+
+        // the first two bytes are the data length, excluding checksum which we'll add next
+        let data_length = data.len() as u16;
+        data[0..2].copy_from_slice(&data_length.to_le_bytes());
+
+        // checksum does not include the first two bytes (data length)
+        data.extend_from_slice(&Self::checksum(&data[2..]));
+
+        data
+    }
+
+    fn register(&self) -> u16 {
+        self.register
+    }
+
+    fn value(&self) -> u16 {
+        Utils::u16ify(&self.values, 0)
+    }
+}
+
+/////////////
+//
+// READ PARAM
+//
+/////////////
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ReadParam {
+    pub datalog: Serial,
+    pub register: u16,   // first register of values
+    pub values: Vec<u8>, // undecoded, since can be u16 or i32s?
+}
+impl ReadParam {
+    pub fn pairs(&self) -> Vec<(u16, u16)> {
+        self.values
+            .chunks(2)
+            .enumerate()
+            .map(|(pos, value)| (self.register + pos as u16, Utils::u16ify(value, 0)))
+            .collect()
+    }
+
+    fn decode(input: &[u8]) -> Result<Self> {
+        let len = input.len();
+        if len < 24 {
+            bail!("ReadParam::decode packet too short");
+        }
+
+        let protocol = Utils::u16ify(input, 2);
+        let datalog = Serial::new(&input[8..18])?;
+
+        let data = &input[18..];
+        let register = Utils::u16ify(data, 0);
+
+        let mut value_len = 2;
+        let mut value_offset = 2;
+
+        if Self::has_value_length_bytes(protocol) {
+            value_len = Utils::u16ify(data, value_offset) as usize;
+            value_offset += 2;
+        }
+
+        let values = data[value_offset..].to_vec();
+
+        if values.len() != value_len {
+            bail!(
+                "ReadParam::decode mismatch: values.len()={}, value_length_byte={}",
+                values.len(),
+                value_len
+            );
+        }
+
+        Ok(Self {
+            datalog,
+            register,
+            values,
+        })
+    }
+
+    fn has_value_length_bytes(protocol: u16) -> bool {
+        protocol == 2
+    }
+}
+
+impl PacketCommon for ReadParam {
+    fn protocol(&self) -> u16 {
+        2
+    }
+
+    fn datalog(&self) -> Serial {
+        self.datalog
+    }
+    fn set_datalog(&mut self, datalog: Serial) {
+        self.datalog = datalog;
+    }
+    fn inverter(&self) -> Option<Serial> {
+        None
+    }
+    fn set_inverter(&mut self, _datalog: Serial) {}
+
+    fn tcp_function(&self) -> TcpFunction {
+        TcpFunction::ReadParam
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        vec![self.register() as u8, 0]
+    }
+
+    fn register(&self) -> u16 {
+        self.register
+    }
+
+    fn value(&self) -> u16 {
+        Utils::u16ify(&self.values, 0)
+    }
+}
+
+/////////////
+//
+// WRITE PARAM
+//
+/////////////
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct WriteParam {
+    pub datalog: Serial,
+    pub register: u16,   // first register of values
+    pub values: Vec<u8>, // undecoded, since can be u16 or i32s?
+}
+impl WriteParam {
+    pub fn pairs(&self) -> Vec<(u16, u16)> {
+        self.values
+            .chunks(2)
+            .enumerate()
+            .map(|(pos, value)| (self.register + pos as u16, Utils::u16ify(value, 0)))
+            .collect()
+    }
+
+    fn decode(input: &[u8]) -> Result<Self> {
+        let len = input.len();
+        if len < 21 {
+            bail!("WriteParam::decode packet too short");
+        }
+
+        let protocol = Utils::u16ify(input, 2);
+        let datalog = Serial::new(&input[8..18])?;
+
+        let data = &input[18..];
+        let register = u16::from(data[0]);
+
+        let mut value_len = 2;
+        let mut value_offset = 1;
+
+        if Self::has_value_length_bytes(protocol) {
+            value_len = Utils::u16ify(data, value_offset) as usize;
+            value_offset += 2;
+        }
+
+        let values = data[value_offset..].to_vec();
+
+        if values.len() != value_len {
+            bail!(
+                "WriteParam::decode mismatch: values.len()={}, value_length_byte={}",
+                values.len(),
+                value_len
+            );
+        }
+
+        Ok(Self {
+            datalog,
+            register,
+            values,
+        })
+    }
+
+    fn has_value_length_bytes(_protocol: u16) -> bool {
+        false
+    }
+}
+
+impl PacketCommon for WriteParam {
+    fn protocol(&self) -> u16 {
+        2
+    }
+
+    fn datalog(&self) -> Serial {
+        self.datalog
+    }
+    fn set_datalog(&mut self, datalog: Serial) {
+        self.datalog = datalog;
+    }
+    fn inverter(&self) -> Option<Serial> {
+        None
+    }
+    fn set_inverter(&mut self, _datalog: Serial) {}
+
+    fn tcp_function(&self) -> TcpFunction {
+        TcpFunction::WriteParam
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        let mut data = vec![0; 2];
+
+        data[0..2].copy_from_slice(&self.register.to_le_bytes());
+
+        let len = self.values.len() as u16;
+        data.extend_from_slice(&len.to_le_bytes());
+
+        let mut m = Vec::new();
+        for i in &self.values {
+            m.extend_from_slice(&i.to_le_bytes());
+        }
+        data.append(&mut m);
+
+        data
+    }
+
+    fn register(&self) -> u16 {
+        self.register
+    }
+
+    fn value(&self) -> u16 {
+        Utils::u16ify(&self.values, 0)
+    }
+}
+
+pub struct Parser;
+impl Parser {
+    pub fn parse(input: &[u8]) -> Result<Packet> {
+        Self::parse_with_mode(input, ChecksumMode::Verify)
+    }
+
+    /// Like `parse`, but `mode` controls whether a `TranslatedData` checksum
+    /// mismatch is fatal. See `ChecksumMode`.
+    pub fn parse_with_mode(input: &[u8], mode: ChecksumMode) -> Result<Packet> {
+        let input_len = input.len() as u8;
+        if input_len < 18 {
+            bail!("packet less than 18 bytes?");
+        }
+
+        if input[0..2] != [161, 26] {
+            bail!("invalid packet prefix");
+        }
+
+        if input_len < input[4] - 6 {
+            bail!(
+                "Parser::parse mismatch: input.len()={},  frame_length={}",
+                input_len,
+                input[4] - 6
+            );
+        }
+
+        let r = match TcpFunction::try_from(input[7])? {
+            TcpFunction::Heartbeat => Packet::Heartbeat(Heartbeat::decode(input)?),
+            TcpFunction::TranslatedData => {
+                Packet::TranslatedData(TranslatedData::decode_with_mode(input, mode)?)
+            }
+            TcpFunction::ReadParam => Packet::ReadParam(ReadParam::decode(input)?),
+            TcpFunction::WriteParam => Packet::WriteParam(WriteParam::decode(input)?),
+            //_ => bail!("unhandled: tcp_function={} input={:?}", input[7], input),
+        };
+
+        Ok(r)
+    }
+}
+
+/// Standard Modbus exception codes, decoded from the single-byte exception
+/// code a slave returns in place of the expected reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ModbusError {
+    IllegalFunction,
+    IllegalDataAddress,
+    IllegalDataValue,
+    SlaveDeviceFailure,
+    Acknowledge,
+    SlaveDeviceBusy,
+    MemoryParityError,
+    GatewayPathUnavailable,
+    GatewayTargetFailedToRespond,
+    Unknown(u8),
+}
+
+impl ModbusError {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x01 => Self::IllegalFunction,
+            0x02 => Self::IllegalDataAddress,
+            0x03 => Self::IllegalDataValue,
+            0x04 => Self::SlaveDeviceFailure,
+            0x05 => Self::Acknowledge,
+            0x06 => Self::SlaveDeviceBusy,
+            0x08 => Self::MemoryParityError,
+            0x0A => Self::GatewayPathUnavailable,
+            0x0B => Self::GatewayTargetFailedToRespond,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+pub struct StatusString;
+impl StatusString {
+    pub fn from_value(status: u16) -> &'static str {
+        match status {
+            0x00 => "Standby",
+            0x02 => "FW Updating",
+            0x04 => "PV On-grid",
+            0x08 => "PV Charge",
+            0x0C => "PV Charge On-grid",
+            0x10 => "Battery On-grid",
+            0x11 => "Bypass",
+            0x14 => "PV & Battery On-grid",
+            0x19 => "PV Charge + Bypass",
+            0x20 => "AC Charge",
+            0x28 => "PV & AC Charge",
+            0x40 => "Battery Off-grid",
+            0x80 => "PV Off-grid",
+            0xC0 => "PV & Battery Off-grid",
+            0x88 => "PV Charge Off-grid",
+
+            _ => "Unknown",
+        }
+    }
+}
+
+/// Returns the bit indices (0-31) that are set in `value`, low to high.
+fn set_bits(value: u32) -> impl Iterator<Item = usize> {
+    (0..=31).filter(move |i| value & (1 << i) > 0)
+}
+
+pub struct WarningCodeString;
+impl WarningCodeString {
+    pub fn from_value(value: u32) -> &'static str {
+        if value == 0 {
+            return "OK";
+        }
+
+        set_bits(value).next().map(Self::from_bit).unwrap()
+    }
+
+    /// Returns every concurrently-set warning code, empty if `value` is 0.
+    pub fn all(value: u32) -> Vec<&'static str> {
+        set_bits(value).map(Self::from_bit).collect()
+    }
+
+    fn from_bit(bit: usize) -> &'static str {
+        match bit {
+            0 => "W000: Battery communication failure",
+            1 => "W001: AFCI communication failure",
+            2 => "W002: AFCI high",
+            3 => "W003: Meter communication failure",
+            4 => "W004: Both charge and discharge forbidden by battery",
+            5 => "W005: Auto test failed",
+            6 => "W006: Reserved",
+            7 => "W007: LCD communication failure",
+            8 => "W008: FW version mismatch",
+            9 => "W009: Fan stuck",
+            10 => "W010: Reserved",
+            11 => "W011: Parallel number out of range",
+            12 => "W012: Bat On Mos",
+            13 => "W013: Overtemperature (NTC reading is too high)",
+            14 => "W014: Reserved",
+            15 => "W015: Battery reverse connection",
+            16 => "W016: Grid power outage",
+            17 => "W017: Grid voltage out of range",
+            18 => "W018: Grid frequency out of range",
+            19 => "W019: Reserved",
+            20 => "W020: PV insulation low",
+            21 => "W021: Leakage current high",
+            22 => "W022: DCI high",
+            23 => "W023: PV short",
+            24 => "W024: Reserved",
+            25 => "W025: Battery voltage high",
+            26 => "W026: Battery voltage low",
+            27 => "W027: Battery open circuit",
+            28 => "W028: EPS overload",
+            29 => "W029: EPS voltage high",
+            30 => "W030: Meter reverse connection",
+            31 => "W031: DCV high",
+
+            _ => todo!("Unknown Warning"),
+        }
+    }
+}
+
+pub struct FaultCodeString;
+impl FaultCodeString {
+    pub fn from_value(value: u32) -> &'static str {
+        if value == 0 {
+            return "OK";
+        }
+
+        set_bits(value).next().map(Self::from_bit).unwrap()
+    }
+
+    /// Returns every concurrently-set fault code, empty if `value` is 0.
+    pub fn all(value: u32) -> Vec<&'static str> {
+        set_bits(value).map(Self::from_bit).collect()
+    }
+
+    fn from_bit(bit: usize) -> &'static str {
+        match bit {
+            0 => "E000: Internal communication fault 1",
+            1 => "E001: Model fault",
+            2 => "E002: BatOnMosFail",
+            3 => "E003: CT Fail",
+            4 => "E004: Reserved",
+            5 => "E005: Reserved",
+            6 => "E006: Reserved",
+            7 => "E007: Reserved",
+            8 => "E008: CAN communication error in parallel system",
+            9 => "E009: master lost in parallel system",
+            10 => "E010: multiple master units in parallel system",
+            11 => "E011: AC input inconsistent in parallel system",
+            12 => "E012: UPS short",
+            13 => "E013: Reverse current on UPS output",
+            14 => "E014: Bus short",
+            15 => "E015: Phase error in three phase system",
+            16 => "E016: Relay check fault",
+            17 => "E017: Internal communication fault 2",
+            18 => "E018: Internal communication fault 3",
+            19 => "E019: Bus voltage high",
+            20 => "E020: EPS connection fault",
+            21 => "E021: PV voltage high",
+            22 => "E022: Over current protection",
+            23 => "E023: Neutral fault",
+            24 => "E024: PV short",
+            25 => "E025: Radiator temperature over range",
+            26 => "E026: Internal fault",
+            27 => "E027: Sample inconsistent between Main CPU and redundant CPU",
+            28 => "E028: Reserved",
+            29 => "E029: Reserved",
+            30 => "E030: Reserved",
+            31 => "E031: Internal communication fault 4",
+            _ => todo!("Unknown Fault"),
+        }
+    }
+}
+
+/// Decodes `ReadInput3::bat_status_9`/`bat_status_inv`, the battery's own
+/// status words (distinct from the inverter-level `fault_code`/
+/// `warning_code` `WarningCodeString`/`FaultCodeString` above).
+pub struct BatteryStatusString;
+impl BatteryStatusString {
+    /// Returns every concurrently-set flag in battery status word 9, empty if `value` is 0.
+    pub fn decode_status_9(value: u16) -> Vec<&'static str> {
+        set_bits(value as u32).map(Self::from_status_9_bit).collect()
+    }
+
+    /// Returns every concurrently-set flag in the battery's inverter status word, empty if `value` is 0.
+    pub fn decode_status_inv(value: u16) -> Vec<&'static str> {
+        set_bits(value as u32).map(Self::from_status_inv_bit).collect()
+    }
+
+    fn from_status_9_bit(bit: usize) -> &'static str {
+        match bit {
+            0 => "S9-00: charge immediately (level 1)",
+            1 => "S9-01: charge immediately (level 2)",
+            2 => "S9-02: charge request",
+            3 => "S9-03: discharge request",
+            4 => "S9-04: charge forbidden",
+            5 => "S9-05: discharge forbidden",
+            6 => "S9-06: battery full",
+            7 => "S9-07: battery empty",
+            8 => "S9-08: cell balancing active",
+            9 => "S9-09: reserved",
+            10 => "S9-10: reserved",
+            11 => "S9-11: reserved",
+            12 => "S9-12: reserved",
+            13 => "S9-13: reserved",
+            14 => "S9-14: reserved",
+            15 => "S9-15: reserved",
+            _ => "S9-??: unknown",
+        }
+    }
+
+    fn from_status_inv_bit(bit: usize) -> &'static str {
+        match bit {
+            0 => "SINV-00: grid connected",
+            1 => "SINV-01: inverting",
+            2 => "SINV-02: charging",
+            3 => "SINV-03: eps active",
+            4 => "SINV-04: fault locked",
+            5 => "SINV-05: derating",
+            6 => "SINV-06: standby",
+            7 => "SINV-07: bypass active",
+            8 => "SINV-08: reserved",
+            9 => "SINV-09: reserved",
+            10 => "SINV-10: reserved",
+            11 => "SINV-11: reserved",
+            12 => "SINV-12: reserved",
+            13 => "SINV-13: reserved",
+            14 => "SINV-14: reserved",
+            15 => "SINV-15: reserved",
+            _ => "SINV-??: unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn serial_frame_factory_and_parser_round_trip() {
+        let datalog = Serial::from_str("0000000001").unwrap();
+        let inverter = Serial::from_str("0000000002").unwrap();
+
+        let original = TranslatedData {
+            datalog,
+            device_function: DeviceFunction::WriteMulti,
+            inverter,
+            register: 96,
+            values: vec![6, 0, 7, 0],
+            checksum_valid: true,
+        };
+
+        let frame = SerialFrameFactory::build(&original, 1);
+        let decoded = SerialParser::decode(&frame, datalog, inverter).unwrap();
+
+        assert_eq!(decoded.device_function, original.device_function);
+        assert_eq!(decoded.register, original.register);
+        assert_eq!(decoded.values, original.values);
+    }
+
+    #[test]
+    fn serial_parser_rejects_checksum_mismatch() {
+        let datalog = Serial::from_str("0000000001").unwrap();
+        let inverter = Serial::from_str("0000000002").unwrap();
+
+        let original = TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter,
+            register: 0,
+            values: vec![1, 0],
+            checksum_valid: true,
+        };
+
+        let mut frame = SerialFrameFactory::build(&original, 1);
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+
+        assert!(SerialParser::decode(&frame, datalog, inverter).is_err());
+    }
+
+    #[test]
+    fn serial_parser_rejects_short_frames() {
+        let datalog = Serial::from_str("0000000001").unwrap();
+        let inverter = Serial::from_str("0000000002").unwrap();
+
+        assert!(SerialParser::decode(&[1, 2, 3], datalog, inverter).is_err());
+    }
+
+    /// A `ReadInputAll` fixture for tests that only care about a handful of
+    /// fields: parses an all-zero 254-byte payload (the same size
+    /// `TranslatedData::read_input` requires for the "all registers" frame)
+    /// so every field starts at its zero value, ready for the caller to
+    /// override just the ones under test.
+    fn sample_read_input_all() -> ReadInputAll {
+        ReadInputAll::parse(&[0u8; 254]).unwrap().1
+    }
+
+    #[test]
+    fn calculate_soc_from_ocv_interpolates_between_curve_points() {
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(51.0);
+        r.bat_current = 0.0;
+        r.soc_inner_resistance = 0;
+        r.soc_volt1 = 48.0;
+        r.soc_pct1 = 0;
+        r.soc_volt2 = 54.0;
+        r.soc_pct2 = 100;
+
+        // halfway between the two curve points -> 50%
+        assert_eq!(r.calculate_soc_from_ocv(), Some(50.0));
+    }
+
+    #[test]
+    fn calculate_soc_from_ocv_clamps_to_0_100() {
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(60.0);
+        r.bat_current = 0.0;
+        r.soc_inner_resistance = 0;
+        r.soc_volt1 = 48.0;
+        r.soc_pct1 = 0;
+        r.soc_volt2 = 54.0;
+        r.soc_pct2 = 100;
+
+        assert_eq!(r.calculate_soc_from_ocv(), Some(100.0));
+    }
+
+    #[test]
+    fn calculate_soc_from_ocv_is_none_without_v_bat_or_a_degenerate_curve() {
+        let mut r = sample_read_input_all();
+        r.soc_volt1 = 48.0;
+        r.soc_volt2 = 54.0;
+        // v_bat defaults to None from the all-zero fixture
+        assert_eq!(r.calculate_soc_from_ocv(), None);
+
+        r.v_bat = Some(51.0);
+        r.soc_volt2 = r.soc_volt1; // degenerate curve
+        assert_eq!(r.calculate_soc_from_ocv(), None);
+    }
+
+    #[test]
+    fn decode_status_bits_names_known_bits_and_flags_unknown_ones() {
+        // bit 0 (bat_voltage_high) and bit 30, which FAULT_BITS doesn't name
+        let code = 1u32 | (1 << 30);
+        let flags = decode_status_bits(code, FAULT_BITS);
+
+        assert_eq!(flags, vec!["bat_voltage_high".to_string(), "unknown_bit_30".to_string()]);
+    }
+
+    #[test]
+    fn decode_flags_reports_fault_and_warning_bits_together() {
+        let mut r = sample_read_input_all();
+        r.fault_code = 1 << 4; // over_current
+        r.warning_code = 1 << 7; // over_load
+
+        let report = r.decode_flags();
+        assert_eq!(report.fault_flags, vec!["over_current".to_string()]);
+        assert_eq!(report.warning_flags, vec!["over_load".to_string()]);
+    }
+
+    #[test]
+    fn decode_bms_flags_reports_bms_event_bits() {
+        let mut r = sample_read_input_all();
+        r.bms_event_1 = 1 << 8; // cell_imbalance
+        r.bms_event_2 = 1 << 0; // cell_voltage_high
+
+        let report = r.decode_bms_flags();
+        assert_eq!(report.bms_fault_flags, vec!["cell_imbalance".to_string()]);
+        assert_eq!(report.bms_warning_flags, vec!["cell_voltage_high".to_string()]);
+    }
+
+    #[test]
+    fn health_reports_no_battery_when_count_or_capacity_is_zero() {
+        let r = sample_read_input_all();
+        assert_eq!(r.bat_count, 0);
+        assert_eq!(r.health(), BatteryHealth::NoBattery);
+    }
+
+    #[test]
+    fn health_prioritizes_watchdog_timer_expire_over_other_conditions() {
+        let mut r = sample_read_input_all();
+        r.bat_count = 1;
+        r.bat_capacity = 100;
+        r.bms_event_2 = BMS_WATCHDOG_TIMER_EXPIRE_BIT;
+        // also push cell voltage out of range, to confirm watchdog wins
+        r.max_cell_voltage = 1000.0;
+        r.charge_volt_ref = 1.0;
+
+        assert_eq!(r.health(), BatteryHealth::WatchdogTimerExpire);
+    }
+
+    #[test]
+    fn health_report_adds_cell_imbalance_reason_and_health() {
+        let mut r = sample_read_input_all();
+        r.bat_count = 1;
+        r.bat_capacity = 100;
+        r.charge_volt_ref = 60.0;
+        r.dischg_cut_volt = 40.0;
+        r.max_cell_voltage = 3.5;
+        r.min_cell_voltage = 3.0; // 0.5V spread, over the 0.05V default threshold
+
+        let report = r.health_report();
+        assert_eq!(report.health, BatteryHealth::CellImbalance);
+        assert!(report.reasons.iter().any(|r| r.starts_with("cell_imbalance:")));
+    }
+
+    #[test]
+    fn field_units_names_each_field_exactly_once() {
+        let units = ReadInputAll::field_units();
+        assert!(!units.is_empty());
+
+        let mut names: Vec<&str> = units.iter().map(|(name, _, _)| *name).collect();
+        let before = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), before, "field_units has a duplicate field name");
+    }
+
+    #[test]
+    fn field_units_reports_the_expected_unit_for_a_few_sample_fields() {
+        let units = ReadInputAll::field_units();
+        let unit_of = |field: &str| units.iter().find(|(name, _, _)| *name == field).map(|(_, unit, _)| *unit);
+
+        assert_eq!(unit_of("v_bat"), Some(Unit::Volt));
+        assert_eq!(unit_of("p_battery"), Some(Unit::Watt));
+        assert_eq!(unit_of("soc"), Some(Unit::Percent));
+        assert_eq!(unit_of("f_ac"), Some(Unit::Hertz));
+        assert_eq!(unit_of("e_pv_day"), Some(Unit::KilowattHour));
+    }
+
+    #[test]
+    fn read_input_stats_tracks_min_max_per_inverter() {
+        let mut stats = ReadInputStats::new();
+
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(50.0);
+        stats.ingest(&r);
+
+        r.v_bat = Some(52.0);
+        stats.ingest(&r);
+
+        r.v_bat = Some(48.0);
+        stats.ingest(&r);
+
+        let window = &stats.windows_for(&r.datalog).unwrap()["v_bat"];
+        assert_eq!(window.min, 48.0);
+        assert_eq!(window.max, 52.0);
+    }
+
+    #[test]
+    fn read_input_stats_first_sample_sets_average_to_the_sampled_value() {
+        let mut stats = ReadInputStats::new();
+
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(51.5);
+        stats.ingest(&r);
+
+        let window = &stats.windows_for(&r.datalog).unwrap()["v_bat"];
+        assert_eq!(window.average, 51.5);
+    }
+
+    #[test]
+    fn read_input_stats_resets_on_daily_rollover() {
+        let mut stats = ReadInputStats::new();
+
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(50.0);
+        r.e_pv_day = 12.0;
+        stats.ingest(&r);
+        assert!(stats.windows_for(&r.datalog).unwrap().contains_key("v_bat"));
+
+        // e_pv_day only grows through the day, so a drop means midnight rollover
+        r.e_pv_day = 0.0;
+        r.v_bat = None;
+        stats.ingest(&r);
+        assert!(stats.windows_for(&r.datalog).unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_input_stats_keeps_separate_windows_per_inverter() {
+        let mut stats = ReadInputStats::new();
+
+        let mut r1 = sample_read_input_all();
+        r1.datalog = Serial::from_str("0000000001").unwrap();
+        r1.v_bat = Some(10.0);
+        stats.ingest(&r1);
+
+        let mut r2 = sample_read_input_all();
+        r2.datalog = Serial::from_str("0000000002").unwrap();
+        r2.v_bat = Some(99.0);
+        stats.ingest(&r2);
+
+        assert_eq!(stats.windows_for(&r1.datalog).unwrap()["v_bat"].max, 10.0);
+        assert_eq!(stats.windows_for(&r2.datalog).unwrap()["v_bat"].max, 99.0);
+    }
+
+    #[test]
+    fn calculate_derived_values_sets_cell_imbalance_alarm_past_the_default_threshold() {
+        let mut r = sample_read_input_all();
+        r.max_cell_voltage = 3.40;
+        r.min_cell_voltage = 3.30; // 0.1V spread, over CELL_IMBALANCE_VOLTAGE_THRESHOLD_V (0.05V)
+
+        r.calculate_derived_values().unwrap();
+
+        assert_eq!(r.cell_voltage_delta, 0.1);
+        assert!(r.cell_imbalance_alarm);
+    }
+
+    #[test]
+    fn calculate_derived_values_leaves_alarm_unset_within_the_default_threshold() {
+        let mut r = sample_read_input_all();
+        r.max_cell_voltage = 3.32;
+        r.min_cell_voltage = 3.30; // 0.02V spread, under the 0.05V default
+
+        r.calculate_derived_values().unwrap();
+
+        assert!(!r.cell_imbalance_alarm);
+    }
+
+    #[test]
+    fn calculate_derived_values_with_imbalance_threshold_overrides_the_default() {
+        let mut r = sample_read_input_all();
+        r.max_cell_voltage = 3.32;
+        r.min_cell_voltage = 3.30; // 0.02V spread
+
+        // tighter than the default 0.05V threshold, so this should now trip
+        r.calculate_derived_values_with_imbalance_threshold(0.01).unwrap();
+
+        assert!(r.cell_imbalance_alarm);
+    }
+
+    /// A `ReadInput3` fixture for `CoulombCounter` tests: parses an all-zero
+    /// 80-byte payload (the size `TranslatedData::read_input` requires for
+    /// register 80, see `read_input`), so every field starts at zero, ready
+    /// for the caller to override just the ones under test.
+    fn sample_read_input3() -> ReadInput3 {
+        ReadInput3::parse(&[0u8; 80]).unwrap().1
+    }
+
+    #[test]
+    fn coulomb_counter_seeds_from_the_reported_soc_on_first_update() {
+        let mut counter = CoulombCounter::default();
+        let mut r3 = sample_read_input3();
+        r3.bat_capacity = 100;
+        r3.soc_1 = 60;
+
+        let reading = counter.update(&r3);
+
+        assert_eq!(reading.soc_estimated, 60.0);
+        assert_eq!(reading.soc_drift, 0.0);
+    }
+
+    #[test]
+    fn coulomb_counter_recalibrates_to_reported_soc_near_full() {
+        let mut counter = CoulombCounter::default();
+        let mut r3 = sample_read_input3();
+        r3.bat_capacity = 100;
+        r3.soc_1 = 40;
+        counter.update(&r3);
+
+        // pack now reads as resting near charge_volt_ref -> recalibrates to
+        // whatever SOC is reported now, even though it drifted from 40%
+        r3.bat_current = 0.0;
+        r3.charge_volt_ref = 56.0;
+        r3.vbat_inv = 56.0;
+        r3.soc_1 = 99;
+
+        let reading = counter.update(&r3);
+
+        assert_eq!(reading.soc_estimated, 99.0);
+        assert_eq!(reading.soc_drift, 0.0);
+    }
+
+    #[test]
+    fn coulomb_counter_falls_back_to_reported_soc_with_no_capacity() {
+        let mut counter = CoulombCounter::default();
+        let mut r3 = sample_read_input3();
+        r3.bat_capacity = 0;
+        r3.soc_1 = 42;
+
+        let reading = counter.update(&r3);
+
+        assert_eq!(reading.soc_estimated, 42.0);
+    }
+
+    #[test]
+    fn field_units_names_the_ocv_estimate_soc_ocv_not_soc_calc() {
+        let units = ReadInputAll::field_units();
+        assert!(units.iter().any(|(name, _, _)| *name == "soc_ocv"));
+        assert!(!units.iter().any(|(name, _, _)| *name == "soc_calc"));
+    }
+
+    #[test]
+    fn calculate_derived_values_populates_soc_ocv() {
+        let mut r = sample_read_input_all();
+        r.v_bat = Some(51.0);
+        r.bat_current = 0.0;
+        r.soc_inner_resistance = 0;
+        r.soc_volt1 = 48.0;
+        r.soc_pct1 = 0;
+        r.soc_volt2 = 54.0;
+        r.soc_pct2 = 100;
+
+        r.calculate_derived_values().unwrap();
+
+        assert_eq!(r.soc_ocv, Some(50.0));
+    }
+
+    #[test]
+    fn health_report_decodes_fault_warning_and_bms_reasons() {
+        let mut r = sample_read_input_all();
+        r.bat_count = 1;
+        r.bat_capacity = 100;
+        r.charge_volt_ref = 60.0;
+        r.dischg_cut_volt = 40.0;
+        r.fault_code = 1 << 4; // over_current
+        r.warning_code = 1 << 7; // over_load
+        r.bms_event_1 = 1 << 8; // cell_imbalance (BMS-reported)
+
+        let report = r.health_report();
+
+        assert!(report.reasons.contains(&"over_current".to_string()));
+        assert!(report.reasons.contains(&"over_load".to_string()));
+        assert!(report.reasons.contains(&"cell_imbalance".to_string()));
+    }
+
+    #[test]
+    fn health_report_with_thresholds_honors_a_custom_max_cell_temp() {
+        let mut r = sample_read_input_all();
+        r.bat_count = 1;
+        r.bat_capacity = 100;
+        r.charge_volt_ref = 60.0;
+        r.dischg_cut_volt = 40.0;
+        r.max_cell_temp = 40.0;
+
+        // default threshold (55C) doesn't trip at 40C
+        assert_eq!(r.health_report().health, BatteryHealth::Good);
+
+        // a stricter caller-supplied threshold does
+        let thresholds = BatteryHealthThresholds { max_cell_temp_c: 35.0, ..Default::default() };
+        assert_eq!(r.health_report_with_thresholds(&thresholds, CELL_IMBALANCE_VOLTAGE_THRESHOLD_V).health, BatteryHealth::Overheat);
+    }
+
+    #[test]
+    fn rolling_average_evicts_the_oldest_sample_past_capacity() {
+        let mut avg = RollingAverage::new(3);
+        assert_eq!(avg.push(10.0), 10.0);
+        assert_eq!(avg.push(20.0), 15.0);
+        assert_eq!(avg.push(30.0), 20.0);
+        // capacity 3 reached - pushing a 4th evicts the first (10.0)
+        assert_eq!(avg.push(40.0), 30.0);
+    }
+
+    #[test]
+    fn rolling_average_capacity_floors_at_one() {
+        let mut avg = RollingAverage::new(0);
+        assert_eq!(avg.push(5.0), 5.0);
+        // capacity floored to 1 - the old sample is evicted immediately
+        assert_eq!(avg.push(7.0), 7.0);
+    }
+
+    #[test]
+    fn read_input_smoothing_tracks_a_separate_average_per_quantity() {
+        let mut smoothing = ReadInputSmoothing::new(2);
+
+        assert_eq!(smoothing.v_bat.push(50.0), 50.0);
+        assert_eq!(smoothing.v_bat.push(52.0), 51.0);
+
+        // bat_current has its own independent window
+        assert_eq!(smoothing.bat_current.push(1.0), 1.0);
+    }
+
+    #[test]
+    fn battery_modules_skips_all_zero_strings() {
+        let modules = battery_modules(
+            [50.0, 0.0, 51.0, 0.0],
+            [1.0, 0.0, -1.0, 0.0],
+            [25.0, 0.0, 26.0, 0.0],
+            [80, 0, 82, 0],
+            [99, 0, 98, 0],
+        );
+
+        let indices: Vec<usize> = modules.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn battery_module_stats_reports_none_with_fewer_than_two_modules() {
+        let modules = battery_modules([50.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0], [25.0, 0.0, 0.0, 0.0], [80, 0, 0, 0], [99, 0, 0, 0]);
+        let stats = battery_module_stats(&modules);
+
+        assert_eq!(stats.worst_module_soh, Some(99));
+        assert_eq!(stats.max_module_soc_spread, None);
+        assert_eq!(stats.outlier_module_index, None);
+    }
+
+    #[test]
+    fn battery_module_stats_finds_spreads_and_the_outlier_module() {
+        let modules = battery_modules(
+            [50.0, 51.0, 52.0, 0.0],
+            [1.0, 1.0, 1.0, 0.0],
+            [20.0, 25.0, 30.0, 0.0],
+            [80, 82, 60, 0], // module 2 is the outlier, furthest from the mean
+            [99, 95, 90, 0],
+        );
+        let stats = battery_module_stats(&modules);
+
+        assert_eq!(stats.worst_module_soh, Some(90));
+        assert_eq!(stats.max_module_soc_spread, Some(22)); // 82 - 60
+        assert_eq!(stats.max_module_temp_spread, Some(10.0)); // 30.0 - 20.0
+        assert_eq!(stats.outlier_module_index, Some(2));
+    }
+
+    fn write_register_map(json: &str) -> crate::register::RegisterParser {
+        let mut path = std::env::temp_dir();
+        path.push(format!("eg4-packet-test-registers-{:?}.json", std::thread::current().id()));
+        std::fs::write(&path, json).unwrap();
+        let parser = crate::register::RegisterParser::new(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        parser
+    }
+
+    #[test]
+    fn decoded_registers_applies_scaling_and_skips_unknown_registers() {
+        let parser = write_register_map(
+            r#"{
+                "registers": [
+                    {
+                        "register_type": "hold",
+                        "register_map": [
+                            {
+                                "register_number": 0,
+                                "name": "soc",
+                                "description": "state of charge",
+                                "datatype": "u16",
+                                "scaling": 0.1
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        );
+
+        let datalog = Serial::from_str("0000000001").unwrap();
+        let inverter = Serial::from_str("0000000002").unwrap();
+
+        let packet = TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter,
+            register: 0,
+            // register 0's word is [5, 5] (0x0505 = 1285 either byte order,
+            // so this assertion doesn't depend on Utils::u16ify's
+            // endianness) -> decodes to 128.5; register 1 is undefined in
+            // the map above and should be skipped rather than reported.
+            values: vec![5, 5, 9, 9],
+            checksum_valid: true,
+        };
+
+        let decoded = packet.decoded_registers(&parser);
+
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].name, "soc");
+        assert_eq!(decoded[0].value, 128.5);
+    }
+
+    #[test]
+    fn decoded_registers_coalesces_32bit_partner_word() {
+        let parser = write_register_map(
+            r#"{
+                "registers": [
+                    {
+                        "register_type": "hold",
+                        "register_map": [
+                            {
+                                "register_number": 10,
+                                "name": "total_energy",
+                                "description": "cumulative energy",
+                                "datatype": "u32",
+                                "length": 2
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        );
+
+        let datalog = Serial::from_str("0000000001").unwrap();
+        let inverter = Serial::from_str("0000000002").unwrap();
+
+        let packet = TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter,
+            register: 10,
+            // high word's bytes are [2, 2] (0x0202 = 514 either byte order)
+            // and the low word is [0, 0] -> combined raw = 514 << 16 =
+            // 33685504, independent of Utils::u16ify's endianness.
+            values: vec![2, 2, 0, 0],
+            checksum_valid: true,
+        };
+
+        let decoded = packet.decoded_registers(&parser);
+
+        assert_eq!(
+            decoded,
+            vec![crate::register::DecodedRegister {
+                name: "total_energy".to_string(),
+                value: 33685504.0,
+                unit: String::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn warning_code_string_all_reports_every_concurrently_set_bit() {
+        // bits 3 and 16 both set
+        let value = (1 << 3) | (1 << 16);
+
+        assert_eq!(
+            WarningCodeString::all(value),
+            vec!["W003: Meter communication failure", "W016: Grid power outage"]
+        );
+        // from_value only ever reports the lowest set bit
+        assert_eq!(WarningCodeString::from_value(value), "W003: Meter communication failure");
+    }
+
+    #[test]
+    fn warning_code_string_all_is_empty_when_no_bits_set() {
+        assert!(WarningCodeString::all(0).is_empty());
+    }
+
+    #[test]
+    fn fault_code_string_all_reports_every_concurrently_set_bit() {
+        // bits 1 and 9 both set
+        let value = (1 << 1) | (1 << 9);
+
+        assert_eq!(
+            FaultCodeString::all(value),
+            vec!["E001: Model fault", "E009: master lost in parallel system"]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::prelude::*;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::eg4::packet::{ChecksumMode, Packet, Parser};
+
+const PREFIX: [u8; 2] = [161, 26];
+const LENGTH_FIELD_OFFSET: usize = 4;
+const HEADER_LEN: usize = 18;
+
+/// Incrementally decodes the `[161, 26, ...]`-framed packets documented on
+/// `TcpFrameFactory`/`Parser::parse` out of a raw byte stream. A TCP socket
+/// can split a frame across reads or coalesce several into one, so this
+/// owns the leftover bytes between calls instead of assuming `decode` is
+/// ever handed exactly one frame - mirrors how a ring-buffered UART reader
+/// drains whatever has arrived and holds onto the rest.
+#[derive(Default)]
+pub struct PacketDecoder {
+    checksum_mode: ChecksumMode,
+}
+
+impl PacketDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a decoder that parses frames with `mode` instead of the
+    /// default `ChecksumMode::Verify`, for callers that need to tolerate a
+    /// CRC mismatch (e.g. replaying a partially-corrupted capture). See
+    /// `ChecksumMode`.
+    pub fn with_checksum_mode(mode: ChecksumMode) -> Self {
+        Self { checksum_mode: mode }
+    }
+
+    // Drops bytes ahead of the next prefix so garbage (or a resync after a
+    // dropped frame) can't wedge the decoder forever. Keeps a one-byte tail
+    // when no prefix is found, in case it's the first half of one split
+    // across this read and the next.
+    fn resync(buf: &mut BytesMut) {
+        if buf.len() < PREFIX.len() {
+            return;
+        }
+
+        match buf.windows(PREFIX.len()).position(|w| w == PREFIX) {
+            Some(pos) => {
+                if pos > 0 {
+                    buf.advance(pos);
+                }
+            }
+            None => {
+                let keep = PREFIX.len() - 1;
+                buf.advance(buf.len() - keep);
+            }
+        }
+    }
+}
+
+impl Decoder for PacketDecoder {
+    type Item = Packet;
+    type Error = anyhow::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Packet>> {
+        loop {
+            Self::resync(buf);
+
+            if buf.len() < HEADER_LEN {
+                return Ok(None);
+            }
+
+            // little-endian, excludes the first 6 bytes of the envelope -
+            // see `TcpFrameFactory::build`
+            let len_field = u16::from_le_bytes([buf[LENGTH_FIELD_OFFSET], buf[LENGTH_FIELD_OFFSET + 1]]);
+            let frame_len = len_field as usize + 6;
+
+            if buf.len() < frame_len {
+                return Ok(None);
+            }
+
+            let frame = buf.split_to(frame_len);
+            match Parser::parse_with_mode(&frame, self.checksum_mode) {
+                Ok(packet) => return Ok(Some(packet)),
+                Err(e) => {
+                    warn!("PacketDecoder: dropping unparseable frame: {}", e);
+                    continue;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eg4::packet::{DeviceFunction, TcpFrameFactory, TranslatedData};
+    use std::str::FromStr;
+
+    fn sample_frame() -> Vec<u8> {
+        let datalog = crate::eg4::inverter::Serial::from_str("0000000001").unwrap();
+        let inverter = crate::eg4::inverter::Serial::from_str("0000000002").unwrap();
+
+        TcpFrameFactory::build(&Packet::TranslatedData(TranslatedData {
+            datalog,
+            device_function: DeviceFunction::ReadHold,
+            inverter,
+            register: 0,
+            values: vec![1, 0],
+            checksum_valid: true,
+        }))
+    }
+
+    #[test]
+    fn decodes_one_frame_delivered_whole() {
+        let mut buf = BytesMut::from(&sample_frame()[..]);
+        let mut decoder = PacketDecoder::new();
+
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decodes_a_frame_split_across_two_reads() {
+        let frame = sample_frame();
+        let (first_half, second_half) = frame.split_at(frame.len() / 2);
+
+        let mut buf = BytesMut::from(first_half);
+        let mut decoder = PacketDecoder::new();
+
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(second_half);
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+    }
+
+    #[test]
+    fn decodes_two_coalesced_frames_from_one_read() {
+        let frame = sample_frame();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&frame);
+        buf.extend_from_slice(&frame);
+
+        let mut decoder = PacketDecoder::new();
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn resyncs_past_garbage_bytes_before_the_prefix() {
+        let frame = sample_frame();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&[0xFF, 0xFF, 0xFF]);
+        buf.extend_from_slice(&frame);
+
+        let mut decoder = PacketDecoder::new();
+        assert!(decoder.decode(&mut buf).unwrap().is_some());
+    }
+
+    #[test]
+    fn default_mode_drops_a_frame_with_a_bad_checksum() {
+        let mut frame = sample_frame();
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+
+        let mut buf = BytesMut::from(&frame[..]);
+        let mut decoder = PacketDecoder::new();
+
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn ignore_mode_decodes_a_bad_checksum_and_flags_it() {
+        let mut frame = sample_frame();
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+
+        let mut buf = BytesMut::from(&frame[..]);
+        let mut decoder = PacketDecoder::with_checksum_mode(ChecksumMode::Ignore);
+
+        let Packet::TranslatedData(packet) = decoder.decode(&mut buf).unwrap().unwrap() else {
+            panic!("expected a TranslatedData packet");
+        };
+        assert!(!packet.checksum_valid);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::prelude::*;
+use crate::mqtt::Message;
+use crate::register::{Register, RegisterParser};
+use std::sync::Arc;
+
+/// Builds Home Assistant MQTT-discovery messages for one inverter: a
+/// retained `sensor` config per register the loaded register map knows
+/// about (state topic matching what `Message::for_hold`/`for_input`/
+/// `for_param` already publish), plus `number`/`switch` entities for the
+/// handful of writable controls `process_command` understands, whose
+/// command topics feed straight back into `Mqtt::handle_message` ->
+/// `Coordinator::process_message`. Every entity is grouped under one HA
+/// `device` keyed by the inverter's datalog serial.
+///
+/// A no-op (`all()` returns an empty list) when no `register_file` is
+/// configured, since there's no field metadata (name/unit) to build
+/// sensors from in that case.
+pub struct Config {
+    datalog: String,
+    namespace: String,
+    prefix: String,
+    register_parser: Option<Arc<RegisterParser>>,
+}
+
+/// One writable control exposed by `process_command`, described well enough
+/// to build its `number`/`switch` discovery config.
+struct Control {
+    field: &'static str,
+    name: &'static str,
+    command_suffix: &'static str,
+    kind: ControlKind,
+}
+
+enum ControlKind {
+    /// A `set/<suffix>` topic taking a plain integer payload (see
+    /// `Message::payload_int`), exposed as an HA `number` with `min`/`max`.
+    Percent,
+    /// A `set/<suffix>` topic taking `payload_bool`'s truthy/falsy strings,
+    /// exposed as an HA `switch`.
+    Bool,
+}
+
+/// The writable controls `Coordinator::process_command` handles that take a
+/// single scalar value - the atomic `batch`/`schedule`/time-window commands
+/// don't map onto a single HA entity and are left to manual MQTT use.
+const CONTROLS: &[Control] = &[
+    Control { field: "charge_rate_pct", name: "Charge Rate", command_suffix: "charge_rate_pct", kind: ControlKind::Percent },
+    Control { field: "discharge_rate_pct", name: "Discharge Rate", command_suffix: "discharge_rate_pct", kind: ControlKind::Percent },
+    Control { field: "ac_charge_rate_pct", name: "AC Charge Rate", command_suffix: "ac_charge_rate_pct", kind: ControlKind::Percent },
+    Control { field: "ac_charge_soc_limit_pct", name: "AC Charge SOC Limit", command_suffix: "ac_charge_soc_limit_pct", kind: ControlKind::Percent },
+    Control { field: "discharge_cutoff_soc_limit_pct", name: "Discharge Cutoff SOC Limit", command_suffix: "discharge_cutoff_soc_limit_pct", kind: ControlKind::Percent },
+    Control { field: "ac_charge", name: "AC Charge", command_suffix: "ac_charge", kind: ControlKind::Bool },
+    Control { field: "charge_priority", name: "Charge Priority", command_suffix: "charge_priority", kind: ControlKind::Bool },
+    Control { field: "forced_discharge", name: "Forced Discharge", command_suffix: "forced_discharge", kind: ControlKind::Bool },
+];
+
+impl Config {
+    pub fn new(inverter: &config::Inverter, mqtt: &config::Mqtt, config: &ConfigWrapper) -> Self {
+        Self {
+            datalog: inverter.datalog().map(|s| s.to_string()).unwrap_or_default(),
+            namespace: mqtt.namespace().to_string(),
+            prefix: mqtt.homeassistant().prefix().to_string(),
+            register_parser: config
+                .register_file()
+                .as_ref()
+                .and_then(|file| RegisterParser::new(file).ok())
+                .map(Arc::new),
+        }
+    }
+
+    /// Every discovery message for this inverter.
+    pub fn all(&self) -> Result<Vec<Message>> {
+        let mut r = Vec::new();
+
+        if let Some(parser) = self.register_parser.clone() {
+            for register in parser.all() {
+                r.push(self.sensor_config(register)?);
+            }
+        }
+
+        for control in CONTROLS {
+            r.push(self.control_config(control)?);
+        }
+
+        Ok(r)
+    }
+
+    /// The `device` block every entity for this inverter is grouped under,
+    /// so Home Assistant shows one "EG4 Inverter <datalog>" device instead
+    /// of a flat list of unrelated entities.
+    fn device(&self) -> serde_json::Value {
+        serde_json::json!({
+            "identifiers": [format!("eg4_{}", self.datalog)],
+            "name": format!("EG4 Inverter {}", self.datalog),
+            "manufacturer": "EG4",
+            "model": "Inverter",
+        })
+    }
+
+    /// The retained topic a register's decoded value is actually published
+    /// on, matching `Message::for_hold`/`for_param`/`for_input`'s topic
+    /// naming for this register's type.
+    fn state_topic(&self, register: &Register) -> String {
+        let kind = match register.register_type.as_str() {
+            "hold" => "hold",
+            "param" => "param",
+            _ => "input",
+        };
+        format!("{}/{}/{}/{}", self.namespace, self.datalog, kind, register.register_number)
+    }
+
+    fn sensor_config(&self, register: &Register) -> Result<Message> {
+        let field = register.field_name();
+        let mut payload = serde_json::json!({
+            "name": register.name,
+            "unique_id": format!("eg4_{}_{}", self.datalog, field),
+            "state_topic": self.state_topic(register),
+            "device": self.device(),
+        });
+
+        if !register.unit.is_empty() {
+            payload["unit_of_measurement"] = serde_json::json!(register.unit);
+        }
+        if let Some(device_class) = device_class_for_unit(&register.unit) {
+            payload["device_class"] = serde_json::json!(device_class);
+            payload["state_class"] = serde_json::json!("measurement");
+        }
+
+        Ok(Message {
+            topic: format!("{}/sensor/{}_{}/config", self.prefix, self.datalog, field),
+            retain: true,
+            payload: serde_json::to_string(&payload)?,
+            ..Default::default()
+        })
+    }
+
+    fn control_config(&self, control: &Control) -> Result<Message> {
+        let command_topic = format!("{}/cmd/{}/set/{}", self.namespace, self.datalog, control.command_suffix);
+
+        let (component, payload) = match control.kind {
+            ControlKind::Percent => (
+                "number",
+                serde_json::json!({
+                    "name": control.name,
+                    "unique_id": format!("eg4_{}_{}", self.datalog, control.field),
+                    "command_topic": command_topic,
+                    "min": 0,
+                    "max": 100,
+                    "step": 1,
+                    "unit_of_measurement": "%",
+                    "device": self.device(),
+                }),
+            ),
+            ControlKind::Bool => (
+                "switch",
+                serde_json::json!({
+                    "name": control.name,
+                    "unique_id": format!("eg4_{}_{}", self.datalog, control.field),
+                    "command_topic": command_topic,
+                    "payload_on": "true",
+                    "payload_off": "false",
+                    "device": self.device(),
+                }),
+            ),
+        };
+
+        Ok(Message {
+            topic: format!("{}/{}/{}_{}/config", self.prefix, component, self.datalog, control.field),
+            retain: true,
+            payload: serde_json::to_string(&payload)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Best-effort HA `device_class` for a register's `unit` string, so values
+/// with a recognized unit render with the right icon/rounding in HA instead
+/// of as a bare number. Conservative: anything not recognized is left
+/// without a `device_class` rather than guessed.
+fn device_class_for_unit(unit: &str) -> Option<&'static str> {
+    match unit {
+        "V" => Some("voltage"),
+        "A" => Some("current"),
+        "W" => Some("power"),
+        "kW" => Some("power"),
+        "kWh" => Some("energy"),
+        "Wh" => Some("energy"),
+        "Hz" => Some("frequency"),
+        "%" => Some("battery"),
+        "C" | "\u{b0}C" => Some("temperature"),
+        _ => None,
+    }
+}
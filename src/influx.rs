@@ -1,7 +1,9 @@
 use crate::prelude::*;
-use crate::register::RegisterParser;
+use crate::register::{RegisterParser, RegisterValue};
+use crate::sink::OutputSink;
 use std::collections::HashMap;
 
+use async_trait::async_trait;
 use chrono::TimeZone;
 use rinfluxdb::line_protocol::{r#async::Client, LineBuilder};
 
@@ -10,9 +12,31 @@ static MEASUREMENT: &str = "eg4_inverter";
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum ChannelData {
     InputData(serde_json::Value),
+    /// A bridge-level `PacketStats` telemetry snapshot (see
+    /// `Coordinator`'s periodic telemetry task). Shaped differently from
+    /// `InputData` - no `serial`/`raw_data` register map - so it's built
+    /// into points by `build_stats_points` instead of `build_points`.
+    Stats(serde_json::Value),
     Shutdown,
 }
 
+/// The two wire protocols we can speak to an InfluxDB server with.
+///
+/// `V1` uses the existing `rinfluxdb` line-protocol client against a named
+/// database; `V2` talks the `/api/v2/write` HTTP API directly with token
+/// auth against an org + bucket.
+#[derive(Clone)]
+enum InfluxClient {
+    V1(Client),
+    V2 {
+        http: reqwest::Client,
+        url: reqwest::Url,
+        token: String,
+        org: String,
+        bucket: String,
+    },
+}
+
 #[derive(Clone)]
 pub struct Influx {
     config: ConfigWrapper,
@@ -39,17 +63,45 @@ impl Influx {
             return Ok(());
         }
 
-        info!("initializing influx at {}", self.config.influx().url());
+        info!(
+            "initializing influx v{} at {}",
+            self.config.influx().version(),
+            self.config.influx().url()
+        );
 
         let client = {
             let config = self.config.influx();
             let url = reqwest::Url::parse(config.url())?;
-            let credentials = match (config.username(), config.password()) {
-                (Some(u), Some(p)) => Some((u, p)),
-                _ => None,
-            };
 
-            Client::new(url, credentials)?
+            if config.version() == 2 {
+                let token = config
+                    .token()
+                    .clone()
+                    .ok_or_else(|| anyhow!("influx.token is required for InfluxDB 2.x"))?;
+                let org = config
+                    .org()
+                    .clone()
+                    .ok_or_else(|| anyhow!("influx.org is required for InfluxDB 2.x"))?;
+                let bucket = config
+                    .bucket()
+                    .clone()
+                    .ok_or_else(|| anyhow!("influx.bucket is required for InfluxDB 2.x"))?;
+
+                InfluxClient::V2 {
+                    http: reqwest::Client::new(),
+                    url,
+                    token,
+                    org,
+                    bucket,
+                }
+            } else {
+                let credentials = match (config.username(), config.password()) {
+                    (Some(u), Some(p)) => Some((u, p)),
+                    _ => None,
+                };
+
+                InfluxClient::V1(Client::new(url, credentials)?)
+            }
         };
 
         // Spawn the sender task instead of awaiting it
@@ -69,96 +121,298 @@ impl Influx {
         let _ = self.channels.to_influx.send(ChannelData::Shutdown);
     }
 
-    async fn sender(&self, client: Client) -> Result<()> {
+    async fn sender(&self, client: InfluxClient) -> Result<()> {
         use ChannelData::*;
 
+        let config = self.config.influx();
+        let buffer_size = config.buffer_size();
+        let flush_interval = std::time::Duration::from_millis(config.flush_interval_ms());
+
         let mut receiver = self.channels.to_influx.subscribe();
+        let mut buffer: Vec<rinfluxdb::line_protocol::Line> = Vec::with_capacity(buffer_size);
+        let mut interval = tokio::time::interval(flush_interval);
+        let mut backoff_secs = 1u64;
+
         info!("InfluxDB sender started");
 
         loop {
-            match receiver.recv().await {
-                Ok(Shutdown) => {
-                    info!("InfluxDB sender received shutdown signal");
-                    break;
-                }
-                Ok(InputData(data)) => {
-                    let mut points = Vec::new();
-                    
-                    // Extract common fields
-                    let serial = data.get("serial")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| anyhow!("Missing serial in data"))?;
-                    let datalog = data.get("datalog")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| anyhow!("Missing datalog in data"))?;
-                    let timestamp = data.get("time")
-                        .and_then(|v| v.as_i64())
-                        .ok_or_else(|| anyhow!("Missing time in data"))?;
-
-                    // Get raw register data
-                    let raw_data = data.get("raw_data")
-                        .and_then(|v| v.as_object())
-                        .ok_or_else(|| anyhow!("Missing raw_data in data"))?;
-
-                    // Convert raw_data to HashMap<String, String>
-                    let mut register_data = HashMap::new();
-                    for (key, value) in raw_data {
-                        if let Some(hex_value) = value.as_str() {
-                            register_data.insert(key.clone(), hex_value.to_string());
+            tokio::select! {
+                biased;
+
+                message = crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToInflux) => {
+                    match message {
+                        Ok(Shutdown) => {
+                            info!("InfluxDB sender received shutdown signal");
+                            break;
+                        }
+                        Ok(InputData(data)) => {
+                            match self.build_points(&data) {
+                                Ok(mut points) => buffer.append(&mut points),
+                                Err(err) => error!("Failed to build InfluxDB points: {}", err),
+                            }
+
+                            if buffer.len() >= buffer_size {
+                                self.flush(&client, &mut buffer, &mut backoff_secs).await;
+                            }
+                        }
+                        Ok(Stats(data)) => {
+                            match self.build_stats_points(&data) {
+                                Ok(mut points) => buffer.append(&mut points),
+                                Err(err) => error!("Failed to build InfluxDB telemetry points: {}", err),
+                            }
+
+                            if buffer.len() >= buffer_size {
+                                self.flush(&client, &mut buffer, &mut backoff_secs).await;
+                            }
+                        }
+                        Err(e) => {
+                            error!("Error receiving from InfluxDB channel: {}", e);
                         }
                     }
+                }
 
-                    // Decode register values if we have a register parser
-                    let decoded_values = if let Some(parser) = &self.register_parser {
-                        parser.decode_registers(&register_data, self.config.show_unknown(), datalog)
-                    } else {
-                        // If no register parser, just use raw values
-                        register_data.iter()
-                            .map(|(k, v)| (k.clone(), u16::from_str_radix(v, 16).unwrap_or(0) as f64))
-                            .collect()
-                    };
-
-                    // Create points for each decoded value
-                    for (name, value) in decoded_values {
-                        let mut line = LineBuilder::new(MEASUREMENT)
-                            .insert_tag("serial", serial)
-                            .insert_tag("datalog", datalog)
-                            .set_timestamp(chrono::Utc.timestamp_opt(timestamp, 0)
-                                .single()
-                                .ok_or_else(|| anyhow!("Invalid timestamp: {}", timestamp))?);
-
-                        // Add the field value
-                        line = line.insert_field(name.as_str(), value);
-                        points.push(line.build());
+                _ = interval.tick() => {
+                    if !buffer.is_empty() {
+                        self.flush(&client, &mut buffer, &mut backoff_secs).await;
                     }
+                }
+            }
+        }
 
-                    trace!("Sending to InfluxDB: {:?}", points);
+        if !buffer.is_empty() {
+            self.flush(&client, &mut buffer, &mut backoff_secs).await;
+        }
 
-                    let mut retry_count = 0;
-                    while retry_count < 3 {
-                        match client.send(&self.database(), &points).await {
-                            Ok(_) => {
-                                info!("Successfully sent {} points to InfluxDB", points.len());
-                                break;
-                            }
-                            Err(err) => {
-                                error!("InfluxDB push failed: {:?} - retrying in 10s (attempt {}/3)", err, retry_count + 1);
-                                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                                retry_count += 1;
-                            }
+        info!("InfluxDB sender loop exiting");
+
+        Ok(())
+    }
+
+    /// Builds the line-protocol points for a single `InputData` message.
+    fn build_points(&self, data: &serde_json::Value) -> Result<Vec<rinfluxdb::line_protocol::Line>> {
+        let serial = data.get("serial")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing serial in data"))?;
+        let datalog = data.get("datalog")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing datalog in data"))?;
+        let timestamp = data.get("time")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| anyhow!("Missing time in data"))?;
+
+        // Get raw register data
+        let raw_data = data.get("raw_data")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| anyhow!("Missing raw_data in data"))?;
+
+        // Convert raw_data to HashMap<String, String>
+        let mut register_data = HashMap::new();
+        for (key, value) in raw_data {
+            if let Some(hex_value) = value.as_str() {
+                register_data.insert(key.clone(), hex_value.to_string());
+            }
+        }
+
+        // Decode register values if we have a register parser
+        let decoded_values = if let Some(parser) = &self.register_parser {
+            parser.decode_registers(&register_data, self.config.show_unknown(), datalog)
+        } else {
+            // If no register parser, just use raw values
+            register_data.iter()
+                .map(|(k, v)| (k.clone(), RegisterValue::Number(u16::from_str_radix(v, 16).unwrap_or(0) as f64)))
+                .collect()
+        };
+
+        let config = self.config.influx();
+        let measurement = format!("{}{}", config.measurement_prefix(), MEASUREMENT);
+        let static_tags = config.tags();
+
+        // Create points for each decoded value
+        let mut points = Vec::new();
+        for (name, value) in decoded_values {
+            let mut line = LineBuilder::new(&measurement)
+                .insert_tag("serial", serial)
+                .insert_tag("datalog", datalog);
+
+            for (tag, tag_value) in &static_tags {
+                line = line.insert_tag(tag.as_str(), tag_value.as_str());
+            }
+
+            line = line.set_timestamp(chrono::Utc.timestamp_opt(timestamp, 0)
+                .single()
+                .ok_or_else(|| anyhow!("Invalid timestamp: {}", timestamp))?);
+
+            // Add the field value, typed according to what it decoded to
+            line = match value {
+                RegisterValue::Number(n) => line.insert_field(name.as_str(), n),
+                RegisterValue::Text(s) => line.insert_field(name.as_str(), s),
+                RegisterValue::Flag(b) => line.insert_field(name.as_str(), b),
+            };
+            points.push(line.build());
+        }
+
+        Ok(points)
+    }
+
+    /// Builds the line-protocol points for one `Stats` (bridge telemetry)
+    /// message: a single untagged point carrying every scalar counter plus
+    /// the `rates.*` fields, and one tagged point per inverter for the
+    /// `inverters` breakout of `inverter_disconnections`/`last_messages`.
+    fn build_stats_points(&self, data: &serde_json::Value) -> Result<Vec<rinfluxdb::line_protocol::Line>> {
+        let timestamp = data.get("time")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| anyhow!("Missing time in telemetry data"))?;
+        let timestamp = chrono::Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .ok_or_else(|| anyhow!("Invalid timestamp: {}", timestamp))?;
+
+        let config = self.config.influx();
+        let static_tags = config.tags();
+        let measurement = format!("{}eg4_bridge_stats", config.measurement_prefix());
+        let inverter_measurement = format!("{}eg4_bridge_inverter_stats", config.measurement_prefix());
+
+        let mut line = LineBuilder::new(&measurement);
+        for (tag, tag_value) in &static_tags {
+            line = line.insert_tag(tag.as_str(), tag_value.as_str());
+        }
+        line = line.set_timestamp(timestamp);
+
+        let object = data.as_object().ok_or_else(|| anyhow!("telemetry data is not a JSON object"))?;
+        for (key, value) in object {
+            match key.as_str() {
+                "time" | "inverters" | "task_restarts" => {} // handled separately or not graphable
+                "rates" => {
+                    for (rate_name, rate_value) in value.as_object().into_iter().flatten() {
+                        if let Some(n) = rate_value.as_f64() {
+                            line = line.insert_field(format!("rate_{}", rate_name).as_str(), n);
                         }
                     }
-                    if retry_count == 3 {
-                        error!("Failed to send data to InfluxDB after 3 attempts");
+                }
+                _ => {
+                    if let Some(n) = value.as_u64() {
+                        line = line.insert_field(key.as_str(), n as f64);
+                    } else if let Some(n) = value.as_f64() {
+                        line = line.insert_field(key.as_str(), n);
                     }
                 }
-                Err(e) => {
-                    error!("Error receiving from InfluxDB channel: {}", e);
+            }
+        }
+
+        let mut points = vec![line.build()];
+
+        if let Some(inverters) = data.get("inverters").and_then(|v| v.as_object()) {
+            for (serial, stats) in inverters {
+                let mut inverter_line = LineBuilder::new(&inverter_measurement)
+                    .insert_tag("serial", serial.as_str());
+                for (tag, tag_value) in &static_tags {
+                    inverter_line = inverter_line.insert_tag(tag.as_str(), tag_value.as_str());
+                }
+                inverter_line = inverter_line.set_timestamp(timestamp);
+
+                if let Some(n) = stats.get("disconnections").and_then(|v| v.as_u64()) {
+                    inverter_line = inverter_line.insert_field("disconnections", n as f64);
+                }
+                if let Some(msg) = stats.get("last_message").and_then(|v| v.as_str()) {
+                    inverter_line = inverter_line.insert_field("last_message", msg);
                 }
+                points.push(inverter_line.build());
             }
         }
 
-        info!("InfluxDB sender loop exiting");
+        Ok(points)
+    }
+
+    /// Flushes any spilled batches (oldest-first), then the current buffer.
+    /// On failure the un-flushed buffer is appended to the spill file and
+    /// `backoff_secs` is doubled (capped at 60s); on success it's reset to 1s.
+    async fn flush(
+        &self,
+        client: &InfluxClient,
+        buffer: &mut Vec<rinfluxdb::line_protocol::Line>,
+        backoff_secs: &mut u64,
+    ) {
+        if let Err(err) = self.replay_spill(client).await {
+            trace!("InfluxDB spill replay did not complete: {}", err);
+        }
+
+        trace!("Sending {} points to InfluxDB", buffer.len());
+
+        match self.send_points(client, buffer).await {
+            Ok(_) => {
+                info!("Successfully sent {} points to InfluxDB", buffer.len());
+                *backoff_secs = 1;
+            }
+            Err(err) => {
+                error!(
+                    "InfluxDB push failed: {:?} - spilling {} points to disk, retrying in {}s",
+                    err,
+                    buffer.len(),
+                    backoff_secs
+                );
+                if let Err(spill_err) = self.spill_to_disk(buffer) {
+                    error!("Failed to spill InfluxDB batch to disk: {}", spill_err);
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(*backoff_secs)).await;
+                *backoff_secs = (*backoff_secs * 2).min(60);
+            }
+        }
+
+        buffer.clear();
+    }
+
+    /// Appends a batch as line-protocol text to the spill file, trimming the
+    /// oldest spilled lines first if the file would exceed `max_spill_bytes`.
+    fn spill_to_disk(&self, batch: &[rinfluxdb::line_protocol::Line]) -> Result<()> {
+        use std::io::Write;
+
+        let path = self.config.influx().spill_file();
+        let max_bytes = self.config.influx().max_spill_bytes();
+
+        let body = batch
+            .iter()
+            .map(|point| point.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let addition = body.len() as u64 + 1;
+
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        let mut kept_lines: Vec<&str> = existing.lines().collect();
+
+        let mut total_bytes: u64 = kept_lines.iter().map(|l| l.len() as u64 + 1).sum::<u64>() + addition;
+        while total_bytes > max_bytes && !kept_lines.is_empty() {
+            let dropped = kept_lines.remove(0);
+            total_bytes -= dropped.len() as u64 + 1;
+        }
+
+        let mut file = std::fs::File::create(&path)?;
+        for line in &kept_lines {
+            writeln!(file, "{}", line)?;
+        }
+        writeln!(file, "{}", body)?;
+
+        Ok(())
+    }
+
+    /// Re-attempts everything currently in the spill file, oldest-first,
+    /// removing it from disk only once the write succeeds.
+    async fn replay_spill(&self, client: &InfluxClient) -> Result<()> {
+        let path = self.config.influx().spill_file();
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) if !content.is_empty() => content,
+            _ => return Ok(()),
+        };
+
+        let points: Vec<rinfluxdb::line_protocol::Line> = content
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.parse())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| anyhow!("Failed to parse spilled line-protocol data"))?;
+
+        self.send_points(client, &points).await?;
+        std::fs::remove_file(&path)?;
+        info!("Replayed {} spilled points to InfluxDB", points.len());
 
         Ok(())
     }
@@ -166,4 +420,73 @@ impl Influx {
     fn database(&self) -> String {
         self.config.influx().database().to_string()
     }
+
+    /// Writes a batch of line-protocol points using whichever client mode
+    /// `start()` selected.
+    async fn send_points(
+        &self,
+        client: &InfluxClient,
+        points: &[rinfluxdb::line_protocol::Line],
+    ) -> Result<()> {
+        match client {
+            InfluxClient::V1(client) => {
+                client.send(&self.database(), points).await?;
+            }
+            InfluxClient::V2 {
+                http,
+                url,
+                token,
+                org,
+                bucket,
+            } => {
+                let mut write_url = url.join("/api/v2/write")?;
+                write_url
+                    .query_pairs_mut()
+                    .append_pair("org", org)
+                    .append_pair("bucket", bucket)
+                    .append_pair("precision", "s");
+
+                let body = points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                let response = http
+                    .post(write_url)
+                    .header("Authorization", format!("Token {}", token))
+                    .body(body)
+                    .send()
+                    .await?;
+
+                if !response.status().is_success() {
+                    bail!(
+                        "InfluxDB 2.x write failed with status {}: {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutputSink for Influx {
+    async fn start(&self) -> Result<()> {
+        Influx::start(self).await
+    }
+
+    async fn write(&self, data: &serde_json::Value) -> Result<()> {
+        self.channels
+            .to_influx
+            .send(ChannelData::InputData(data.clone()))?;
+        Ok(())
+    }
+
+    fn stop(&self) {
+        Influx::stop(self)
+    }
 }
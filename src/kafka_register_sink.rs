@@ -0,0 +1,88 @@
+use crate::config;
+use crate::prelude::*;
+
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use serde::Deserialize;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// How a register update's Kafka partition is chosen, so high-frequency registers
+/// spread across partitions instead of serializing behind one log.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionKeyStrategy {
+    /// `register % partition_count`. Cheap, and keeps one register's updates in
+    /// order, which matters if a consumer applies them as a running mirror.
+    RegisterModulo,
+    /// Hash of the configured inverter's datalog serial together with the
+    /// register, for multi-inverter deployments where register numbers alone
+    /// would collide across inverters sharing one topic.
+    InverterSerialHash,
+}
+
+/// Publishes register deltas (`ChannelData::RegisterData`) to a Kafka topic so
+/// downstream analytics can consume raw changes without polling MQTT. Owned by a
+/// `RegisterCache` and run as a third task alongside `cache_getter`/`cache_setter`.
+pub struct KafkaRegisterSink {
+    producer: FutureProducer,
+    topic: String,
+    partition_count: i32,
+    partition_key: PartitionKeyStrategy,
+    datalog: Serial,
+}
+
+impl KafkaRegisterSink {
+    pub fn new(config: &config::Kafka, datalog: Serial) -> Result<Self> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", config.brokers())
+            .set("client.id", config.client_id())
+            .create()
+            .map_err(|e| anyhow!("kafka_register_sink: failed to build producer: {}", e))?;
+
+        Ok(Self {
+            producer,
+            topic: config.topic(),
+            partition_count: config.partition_count() as i32,
+            partition_key: config.partition_key(),
+            datalog,
+        })
+    }
+
+    /// Forwards one register update. Errors are logged rather than propagated - a
+    /// dropped delta shouldn't take the register cache down with it.
+    pub async fn publish(&self, register: u16, value: u16) {
+        let key = register.to_string();
+        let payload = serde_json::json!({
+            "datalog": self.datalog.to_string(),
+            "register": register,
+            "value": value,
+        })
+        .to_string();
+
+        let record = FutureRecord::to(&self.topic)
+            .key(&key)
+            .payload(&payload)
+            .partition(self.partition(register));
+
+        if let Err((e, _)) = self.producer.send(record, Duration::from_secs(0)).await {
+            warn!("kafka_register_sink: failed to publish register {}: {}", register, e);
+        }
+    }
+
+    fn partition(&self, register: u16) -> i32 {
+        if self.partition_count <= 0 {
+            return 0;
+        }
+
+        match self.partition_key {
+            PartitionKeyStrategy::RegisterModulo => register as i32 % self.partition_count,
+            PartitionKeyStrategy::InverterSerialHash => {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                self.datalog.hash(&mut hasher);
+                register.hash(&mut hasher);
+                (hasher.finish() % self.partition_count as u64) as i32
+            }
+        }
+    }
+}
@@ -2,21 +2,28 @@
 pub mod channels;      // Inter-component communication channels
 pub mod command;       // Command processing and handling
 pub mod config;        // Configuration management
+pub mod config_path;   // Dotted-path get/set into the Config tree (runtime MQTT config)
+pub mod config_cli;    // `config validate`/`print-default`/`wizard` CLI subcommands
 pub mod coordinator;   // Main application coordinator
 pub mod database;      // Database operations and storage
 pub mod datalog_writer; // Data logging functionality
 pub mod home_assistant; // Home Assistant integration
 pub mod influx;        // InfluxDB integration
+pub mod kafka_register_sink; // Optional Kafka publisher for RegisterCache deltas
+pub mod logging;       // Reloadable log level + in-memory ring buffer
 pub mod mqtt;          // MQTT client and messaging
 pub mod options;       // Command line options parsing
 pub mod prelude;       // Common imports and types
 pub mod register_cache; // Register value caching
+pub mod register_poll_queue; // Priority-queue scheduler for per-register poll periods
+pub mod register_store; // Pluggable RegisterCache backends (in-process, Redis, ...)
 pub mod scheduler;     // Task scheduling
 pub mod unixtime;      // Unix timestamp handling
 pub mod utils;         // Utility functions
 pub mod eg4;           // EG4 inverter protocol implementation
 pub mod error;         // Error handling and types
 pub mod register;      // Register definitions and parsing
+pub mod sink;           // Pluggable OutputSink trait for telemetry destinations
 
 use crate::prelude::*;
 use std::sync::Arc;
@@ -114,6 +121,38 @@ impl Components {
     }
 }
 
+/// Watches `config.file()` for changes via inotify and calls `reload()` on
+/// each event, debounced slightly so an editor's write-then-rename doesn't
+/// trigger two reloads back to back. Runs until its channel closes (which
+/// only happens if the underlying watcher itself dies).
+async fn watch_config_file(config: Arc<ConfigWrapper>) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let _ = tx.blocking_send(res);
+    })?;
+    watcher.watch(std::path::Path::new(config.file()), RecursiveMode::NonRecursive)?;
+
+    info!("Watching {} for config changes", config.file());
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            Ok(event) if event.kind.is_modify() || event.kind.is_create() => {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                info!("Config file changed on disk, reloading");
+                if let Err(e) = config.reload() {
+                    error!("config reload failed, keeping running config: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!("config file watcher error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
 /// Application entry point
 /// 
 /// This function is the main entry point for the application.
@@ -135,6 +174,36 @@ pub async fn run(config: Config) -> Result<()> {
         let _ = shutdown_tx.send(());
     });
 
+    // SIGHUP triggers a config reload instead of a restart - a failed parse
+    // or validation just logs and leaves the running config untouched.
+    {
+        let config = config.clone();
+        tokio::spawn(async move {
+            let Ok(mut hangup) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) else {
+                error!("Failed to install SIGHUP handler");
+                return;
+            };
+            loop {
+                hangup.recv().await;
+                info!("SIGHUP received, reloading config from disk");
+                if let Err(e) = config.reload() {
+                    error!("config reload failed, keeping running config: {}", e);
+                }
+            }
+        });
+    }
+
+    // Optional inotify-based watch, for deployments that can't easily send
+    // a signal (e.g. a ConfigMap-mounted file in Kubernetes).
+    if config.watch_config() {
+        let config = config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = watch_config_file(config).await {
+                error!("config file watcher stopped: {}", e);
+            }
+        });
+    }
+
     // Run the main application
     info!("Starting main application loop");
     trace!("Calling Coordinator::app with shutdown receiver and config");
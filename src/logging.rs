@@ -0,0 +1,107 @@
+//! A reloadable `log` backend: the filter level can be changed at runtime
+//! (e.g. from an MQTT `cmd/loglevel` message, see `mqtt.rs`) without
+//! restarting the process, and the most recent formatted lines are kept in
+//! memory so they can be pulled on demand (`cmd/logs`).
+use crate::prelude::*;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// How many formatted log lines are kept for `cmd/logs` to drain; older
+/// lines are dropped once the buffer is full.
+const MAX_BUFFERED_LINES: usize = 1000;
+
+static LOGGER: OnceLock<&'static ReloadableLogger> = OnceLock::new();
+
+pub struct ReloadableLogger {
+    level: AtomicU8,
+    buffer: Mutex<VecDeque<String>>,
+}
+
+impl ReloadableLogger {
+    fn level_filter(&self) -> LevelFilter {
+        level_filter_from_u8(self.level.load(Ordering::Relaxed))
+    }
+
+    /// Parses `level` (e.g. "debug") and makes it the new effective filter
+    /// for every subsequent log call, process-wide, immediately.
+    pub fn set_level(&self, level: &str) -> Result<()> {
+        let filter: LevelFilter = level
+            .parse()
+            .map_err(|_| anyhow!("invalid log level {:?}", level))?;
+        self.level.store(filter as u8, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Removes and returns every currently buffered line, oldest first.
+    pub fn drain_buffer(&self) -> Vec<String> {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.drain(..).collect()
+    }
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_filter()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "[{} {} {}] {}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+            record.level(),
+            record.module_path().unwrap_or(""),
+            record.args()
+        );
+
+        eprintln!("{}", line);
+
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() >= MAX_BUFFERED_LINES {
+            buffer.pop_front();
+        }
+        buffer.push_back(line);
+    }
+
+    fn flush(&self) {}
+}
+
+fn level_filter_from_u8(v: u8) -> LevelFilter {
+    match v {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Installs the reloadable logger as the global `log` backend, starting at
+/// `initial_level` (falling back to `info` if unparseable). Must be called
+/// at most once per process, from `main`.
+pub fn init(initial_level: &str) -> &'static ReloadableLogger {
+    let filter = initial_level.parse().unwrap_or(LevelFilter::Info);
+    let logger: &'static ReloadableLogger = Box::leak(Box::new(ReloadableLogger {
+        level: AtomicU8::new(filter as u8),
+        buffer: Mutex::new(VecDeque::with_capacity(MAX_BUFFERED_LINES)),
+    }));
+
+    log::set_logger(logger).expect("logger already initialized");
+    // Let every record reach our own `enabled()` check, which does the real
+    // filtering against the reloadable level instead of a fixed one.
+    log::set_max_level(LevelFilter::Trace);
+
+    let _ = LOGGER.set(logger);
+    logger
+}
+
+/// Returns the global logger handle installed by `init`, if any.
+pub fn handle() -> Option<&'static ReloadableLogger> {
+    LOGGER.get().copied()
+}
@@ -4,8 +4,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 use std::error::Error;
 use std::time::Duration;
-use clap::Parser;
-use std::io::Write;
+use clap::{Parser, Subcommand};
 
 use eg4_bridge::prelude::*;
 
@@ -23,6 +22,58 @@ struct Args {
     /// Optional runtime limit in seconds
     #[arg(short, long)]
     time: Option<u64>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate, validate, and inspect config files without running the bridge
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Parse and validate a config file, exiting non-zero with the precise error if it's invalid
+    Validate {
+        /// Config file to validate
+        file: String,
+    },
+    /// Print a fully-commented reference config with every field and its default
+    PrintDefault,
+    /// Interactively build a new config file by answering a few prompts
+    Wizard {
+        /// Path to write the generated config to
+        #[arg(short, long, default_value = "config.yaml")]
+        output: String,
+    },
+}
+
+fn run_config_command(action: ConfigAction) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match action {
+        ConfigAction::Validate { file } => match eg4_bridge::config_cli::validate(&file) {
+            Ok(()) => {
+                println!("{} is valid", file);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{} is invalid: {}", file, e);
+                std::process::exit(1);
+            }
+        },
+        ConfigAction::PrintDefault => {
+            eg4_bridge::config_cli::print_default();
+            Ok(())
+        }
+        ConfigAction::Wizard { output } => {
+            eg4_bridge::config_cli::run_wizard(&output)?;
+            Ok(())
+        }
+    }
 }
 
 #[tokio::main]
@@ -30,24 +81,18 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     // Parse command line arguments
     let args = Args::parse();
 
+    if let Some(Command::Config { action }) = args.command {
+        return run_config_command(action);
+    }
+
     // Load configuration from the specified file
     let config = Config::new(args.config)?;
     let config = Arc::new(ConfigWrapper::from_config(config));
 
-    // Initialize logging once with the configured level
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(config.loglevel()))
-        .format(|buf, record| {
-            writeln!(
-                buf,
-                "[{} {} {}] {}",
-                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
-                record.level(),
-                record.module_path().unwrap_or(""),
-                record.args()
-            )
-        })
-        .write_style(env_logger::WriteStyle::Never)
-        .init();
+    // Install a reloadable logger (instead of a one-shot `env_logger` init)
+    // so the level can be changed at runtime via MQTT `cmd/loglevel`, and so
+    // recent log lines are buffered in memory for `cmd/logs` to drain.
+    eg4_bridge::logging::init(config.loglevel());
 
     info!("Starting eg4-bridge {}", CARGO_PKG_VERSION);
 
@@ -1,16 +1,54 @@
 use crate::prelude::*;
 use crate::eg4::packet::BatteryStatusString;
 use crate::coordinator::PacketStats;
+use crate::logging;
 
 use rumqttc::{AsyncClient, Event, EventLoop, Incoming, LastWill, MqttOptions, Publish, QoS};
 use std::sync::{Arc, Mutex};
 
+/// Maps a config QoS integer (0/1/2) to rumqttc's `QoS` enum.
+fn qos_from_u8(v: u8) -> QoS {
+    match v {
+        0 => QoS::AtMostOnce,
+        2 => QoS::ExactlyOnce,
+        _ => QoS::AtLeastOnce,
+    }
+}
+
+/// Same as `qos_from_u8`, for rumqttc's v5 `QoS` type.
+fn qos_from_u8_v5(v: u8) -> rumqttc::v5::mqttbytes::QoS {
+    use rumqttc::v5::mqttbytes::QoS as QoSV5;
+    match v {
+        0 => QoSV5::AtMostOnce,
+        2 => QoSV5::ExactlyOnce,
+        _ => QoSV5::AtLeastOnce,
+    }
+}
+
+/// How many buffered log lines go into a single `logs/chunk/N` publish, so
+/// draining a large buffer doesn't block the MQTT sender task on one huge
+/// payload.
+const LOG_CHUNK_LINES: usize = 50;
+
 // Message {{{
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
 pub struct Message {
     pub topic: String,
     pub retain: bool,
     pub payload: String,
+
+    /// MQTT v5 `Response Topic` property, if the inbound command carried one
+    /// (or if this is a reply that should be sent there). `None` on v3.1.1
+    /// and on every outbound message that doesn't need one - the sender
+    /// falls back to the plain `topic` in that case.
+    pub response_topic: Option<String>,
+    /// MQTT v5 `Correlation Data` property, echoed back unchanged on a
+    /// command's reply so the requester can match it to its request.
+    pub correlation_data: Option<Vec<u8>>,
+    /// MQTT v5 `User Properties`, e.g. a caller-supplied request id or
+    /// requested-by tag. Carried from an inbound command onto its reply;
+    /// empty for everything else.
+    pub user_properties: Vec<(String, String)>,
 }
 
 pub enum TargetInverter {
@@ -27,6 +65,7 @@ impl Message {
                 topic: format!("{}/param/{}", rp.datalog, register),
                 retain: true,
                 payload: serde_json::to_string(&value)?,
+                ..Default::default()
             });
         }
 
@@ -41,6 +80,7 @@ impl Message {
                 topic: format!("{}/hold/{}", td.datalog, register),
                 retain: true,
                 payload: serde_json::to_string(&value)?,
+                ..Default::default()
             });
 
             if register == 21 {
@@ -49,6 +89,7 @@ impl Message {
                     topic: format!("{}/hold/{}/bits", td.datalog, register),
                     retain: true,
                     payload: serde_json::to_string(&bits)?,
+                    ..Default::default()
                 });
             }
 
@@ -58,6 +99,7 @@ impl Message {
                     topic: format!("{}/hold/{}/bits", td.datalog, register),
                     retain: true,
                     payload: serde_json::to_string(&bits)?,
+                    ..Default::default()
                 });
             }
         }
@@ -73,6 +115,7 @@ impl Message {
             topic: format!("{}/inputs/all", datalog),
             retain: false,
             payload: serde_json::to_string(&inputs)?,
+            ..Default::default()
         })
     }
 
@@ -95,6 +138,7 @@ impl Message {
                     topic: format!("{}/input/{}", td.datalog, register),
                     retain: false,
                     payload: serde_json::to_string(&value)?,
+                    ..Default::default()
                 });
 
                 if register == 0 {
@@ -102,6 +146,7 @@ impl Message {
                         topic: format!("{}/input/{}/parsed", td.datalog, register),
                         retain: false,
                         payload: crate::eg4::packet::StatusString::from_value(value).to_owned(),
+                        ..Default::default()
                     });
                 }
 
@@ -129,6 +174,7 @@ impl Message {
                     topic: format!("{}/input/warning_code/parsed", td.datalog),
                     retain: false,
                     payload: crate::eg4::packet::WarningCodeString::from_value(warning_code).to_owned(),
+                    ..Default::default()
                 });
             }
 
@@ -137,6 +183,7 @@ impl Message {
                     topic: format!("{}/input/fault_code/parsed", td.datalog),
                     retain: false,
                     payload: crate::eg4::packet::FaultCodeString::from_value(fault_code).to_owned(),
+                    ..Default::default()
                 });
             }
         }
@@ -146,11 +193,13 @@ impl Message {
                 topic: format!("{}/inputs/all", td.datalog),
                 retain: false,
                 payload: serde_json::to_string(&r_all)?,
+                ..Default::default()
             }),
             Ok(ReadInput::ReadInput1(r1)) => r.push(mqtt::Message {
                 topic: format!("{}/inputs/1", td.datalog),
                 retain: false,
                 payload: serde_json::to_string(&r1)?,
+                ..Default::default()
             }),
             Ok(ReadInput::ReadInput2(r2)) => {
                 // Create the main message with all data
@@ -158,6 +207,7 @@ impl Message {
                     topic: format!("{}/inputs/2", td.datalog),
                     retain: false,
                     payload: serde_json::to_string(&r2)?,
+                    ..Default::default()
                 });
                 
                 // Add human-readable battery information
@@ -180,12 +230,14 @@ impl Message {
                     topic: format!("{}/inputs/2/bat_brand_decoded", td.datalog),
                     retain: false,
                     payload: bat_brand_str.to_string(),
+                    ..Default::default()
                 });
                 
                 r.push(mqtt::Message {
                     topic: format!("{}/inputs/2/bat_com_type_decoded", td.datalog),
                     retain: false,
                     payload: bat_com_type_str.to_string(),
+                    ..Default::default()
                 });
             },
             Ok(ReadInput::ReadInput3(r3)) => {
@@ -194,6 +246,7 @@ impl Message {
                     topic: format!("{}/inputs/3", td.datalog),
                     retain: false,
                     payload: serde_json::to_string(&r3)?,
+                    ..Default::default()
                 });
 
                 // Add decoded battery status messages
@@ -204,28 +257,33 @@ impl Message {
                     topic: format!("{}/inputs/3/bat_status_9_decoded", td.datalog),
                     retain: false,
                     payload: status_9_decoded.join(", "),
+                    ..Default::default()
                 });
 
                 r.push(mqtt::Message {
                     topic: format!("{}/inputs/3/bat_status_inv_decoded", td.datalog),
                     retain: false,
                     payload: status_inv_decoded.join(", "),
+                    ..Default::default()
                 });
             },
             Ok(ReadInput::ReadInput4(r4)) => r.push(mqtt::Message {
                 topic: format!("{}/inputs/4", td.datalog),
                 retain: false,
                 payload: serde_json::to_string(&r4)?,
+                ..Default::default()
             }),
             Ok(ReadInput::ReadInput5(r5)) => r.push(mqtt::Message {
                 topic: format!("{}/inputs/5", td.datalog),
                 retain: false,
                 payload: serde_json::to_string(&r5)?,
+                ..Default::default()
             }),
             Ok(ReadInput::ReadInput6(r6)) => r.push(mqtt::Message {
                 topic: format!("{}/inputs/6", td.datalog),
                 retain: false,
                 payload: serde_json::to_string(&r6)?,
+                ..Default::default()
             }),
             Err(x) => warn!("ignoring {:?}", x),
         }
@@ -282,6 +340,10 @@ impl Message {
             ["set", "discharge_cutoff_soc_limit_pct"] => {
                 DischargeCutoffSocLimit(inverter, self.payload_int()?)
             }
+            ["set", "batch"] => SetBatch(inverter, self.payload_batch()?),
+            ["set", "schedule", kind] => {
+                SetSchedule(inverter, kind.to_string(), self.payload_schedule()?)
+            }
             [..] => bail!("unhandled: {:?}", self),
         };
 
@@ -291,6 +353,10 @@ impl Message {
     // given a cmd Message, return the datalog it is intended for.
     //
     // eg cmd/AB12345678/set/ac_charge => (AB12345678, ['set', 'ac_charge'])
+    //
+    // A trailing `id/<request_id>` pair, if present, is stripped from `rest`
+    // here (see `request_id`) so callers that match on `rest`'s shape don't
+    // need to know about it.
     pub fn split_cmd_topic(&self) -> Result<(TargetInverter, Vec<&str>)> {
         let parts: Vec<&str> = self.topic.split('/').collect();
 
@@ -302,7 +368,11 @@ impl Message {
 
         // parts[0] should be cmd
         let datalog = parts[1];
-        let rest = parts[2..].to_vec();
+        let mut rest = parts[2..].to_vec();
+
+        if rest.len() >= 2 && rest[rest.len() - 2] == "id" {
+            rest.truncate(rest.len() - 2);
+        }
 
         if datalog == "all" {
             Ok((TargetInverter::All, rest))
@@ -312,6 +382,21 @@ impl Message {
         }
     }
 
+    /// Optional correlation/request id carried on a trailing `.../id/<token>`
+    /// segment of a command topic, e.g.
+    /// `cmd/AB12345678/set/hold/21/id/req-42` -> `Some("req-42")`. A command
+    /// sent without this segment behaves exactly as before; one sent with it
+    /// gets a structured `CommandResult` published to `response/<token>`
+    /// once it's handled — see `Coordinator::process_message`.
+    pub fn request_id(&self) -> Option<&str> {
+        let parts: Vec<&str> = self.topic.split('/').collect();
+        if parts.len() >= 2 && parts[parts.len() - 2] == "id" {
+            Some(parts[parts.len() - 1])
+        } else {
+            None
+        }
+    }
+
     // not entirely happy with this return type but it avoids needing to expose a struct for now
     fn payload_start_end_time(&self) -> Result<[u8; 4]> {
         use serde::Deserialize;
@@ -337,6 +422,50 @@ impl Message {
         ])
     }
 
+    /// Parses a `set/batch` payload: a JSON array of `{"register": N,
+    /// "value": N}` objects, applied as a single atomic write.
+    fn payload_batch(&self) -> Result<Vec<(u16, u16)>> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        struct RegisterValue {
+            register: u16,
+            value: u16,
+        }
+
+        let entries: Vec<RegisterValue> = serde_json::from_str(&self.payload)?;
+        Ok(entries.into_iter().map(|e| (e.register, e.value)).collect())
+    }
+
+    /// Parses a `set/schedule/{kind}` payload: a JSON array of
+    /// `{"start":"HH:MM","end":"HH:MM"}` windows, applied in order as a
+    /// single atomic write of the whole schedule.
+    fn payload_schedule(&self) -> Result<Vec<[u8; 4]>> {
+        use serde::Deserialize;
+        #[derive(Deserialize)]
+        struct StartEndTime {
+            start: String,
+            end: String,
+        }
+
+        let windows: Vec<StartEndTime> = serde_json::from_str(&self.payload)?;
+        windows
+            .into_iter()
+            .map(|t| {
+                let start: Vec<&str> = t.start.split(':').collect();
+                let end: Vec<&str> = t.end.split(':').collect();
+                if start.len() != 2 || end.len() != 2 {
+                    bail!("badly formatted time, use HH:MM")
+                }
+                Ok([
+                    start[0].parse()?,
+                    start[1].parse()?,
+                    end[0].parse()?,
+                    end[1].parse()?,
+                ])
+            })
+            .collect()
+    }
+
     fn payload_int_or_1(&self) -> Result<u16> {
         self.payload_int().or(Ok(1))
     }
@@ -389,6 +518,10 @@ impl Mqtt {
             return Ok(());
         }
 
+        if c.mqtt().protocol_version() == 5 {
+            return self.start_v5().await;
+        }
+
         let mut options = MqttOptions::new("lxp-bridge", c.mqtt().host(), c.mqtt().port());
 
         let will = LastWill {
@@ -404,8 +537,13 @@ impl Mqtt {
             options.set_credentials(u, p);
         }
 
+        // Commands are only acked once `handle_message` has successfully
+        // forwarded them onto `channels.from_mqtt`, so a closed coordinator
+        // channel causes a redelivery instead of a silently dropped command.
+        options.set_manual_acks(true);
+
         info!(
-            "initializing mqtt at {}:{}",
+            "initializing mqtt (v4) at {}:{}",
             c.mqtt().host(),
             c.mqtt().port()
         );
@@ -414,13 +552,55 @@ impl Mqtt {
 
         futures::try_join!(
             self.setup(client.clone()),
-            self.receiver(eventloop),
+            self.receiver(client.clone(), eventloop),
             self.sender(client)
         )?;
 
         Ok(())
     }
 
+    /// MQTT v5 variant of `start()`. Speaks rumqttc's v5 client so publishes
+    /// can carry user properties (decoded register `name`) and, for
+    /// non-retained input topics, a message-expiry interval.
+    async fn start_v5(&self) -> Result<()> {
+        use rumqttc::v5::{mqttbytes::v5::LastWill as LastWillV5, AsyncClient as AsyncClientV5, MqttOptions as MqttOptionsV5};
+
+        let c = &self.config;
+
+        let mut options = MqttOptionsV5::new("lxp-bridge", c.mqtt().host(), c.mqtt().port());
+
+        options.set_last_will(LastWillV5::new(
+            self.lwt_topic(),
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+            None,
+        ));
+
+        options.set_keep_alive(std::time::Duration::from_secs(60));
+        if let (Some(u), Some(p)) = (c.mqtt().username(), c.mqtt().password()) {
+            options.set_credentials(u, p);
+        }
+
+        options.set_manual_acks(true);
+
+        info!(
+            "initializing mqtt (v5) at {}:{}",
+            c.mqtt().host(),
+            c.mqtt().port()
+        );
+
+        let (client, eventloop) = AsyncClientV5::new(options, 10);
+
+        futures::try_join!(
+            self.setup_v5(client.clone()),
+            self.receiver_v5(client.clone(), eventloop),
+            self.sender_v5(client)
+        )?;
+
+        Ok(())
+    }
+
     pub async fn stop(&self) -> Result<()> {
         info!("Stopping MQTT client...");
         let _ = self.channels.to_mqtt.send(ChannelData::Shutdown);
@@ -432,13 +612,34 @@ impl Mqtt {
             .publish(self.lwt_topic(), QoS::AtLeastOnce, true, "online")
             .await?;
 
+        let cmd_qos = qos_from_u8(self.config.mqtt().qos_commands());
+
         client
             .subscribe(
                 format!("{}/cmd/all/#", self.config.mqtt().namespace()),
-                QoS::AtMostOnce,
+                cmd_qos,
             )
             .await?;
 
+        // Remote management topics: not addressed to any particular
+        // inverter, so they sit alongside (not under) `cmd/{datalog}`.
+        client
+            .subscribe(format!("{}/cmd/loglevel", self.config.mqtt().namespace()), cmd_qos)
+            .await?;
+        client
+            .subscribe(format!("{}/cmd/logs", self.config.mqtt().namespace()), cmd_qos)
+            .await?;
+
+        // Runtime config get/set: topic suffix is a dotted path into the
+        // `Config` tree (see `config_path`), addressed independently of any
+        // particular inverter.
+        client
+            .subscribe(format!("{}/_config/+/set", self.config.mqtt().namespace()), cmd_qos)
+            .await?;
+        client
+            .subscribe(format!("{}/_config/+/get", self.config.mqtt().namespace()), cmd_qos)
+            .await?;
+
         for inverter in self.config.enabled_inverters() {
             client
                 .subscribe(
@@ -447,15 +648,16 @@ impl Mqtt {
                         self.config.mqtt().namespace(),
                         inverter.datalog().map(|s| s.to_string()).unwrap_or_default()
                     ),
-                    QoS::AtMostOnce,
+                    cmd_qos,
                 )
                 .await?;
 
             if self.config.homeassistant_enabled() && self.config.mqtt().homeassistant().enabled() {
+                let discovery_qos = qos_from_u8(self.config.mqtt().qos_discovery());
                 let ha = home_assistant::Config::new(&inverter, &self.config.mqtt(), &self.config);
                 for msg in ha.all()?.into_iter() {
                     let _ = client
-                        .publish(&msg.topic, QoS::AtLeastOnce, msg.retain, msg.payload)
+                        .publish(&msg.topic, discovery_qos, msg.retain, msg.payload)
                         .await;
                 }
             }
@@ -464,8 +666,88 @@ impl Mqtt {
         Ok(())
     }
 
+    /// Intercepts the remote-management topics (`cmd/loglevel`, `cmd/logs`)
+    /// before they'd otherwise reach the per-inverter command pipeline,
+    /// since they aren't addressed to any particular inverter. Returns
+    /// `Some(result)` if `topic` was a management topic (already handled),
+    /// `None` if the caller should continue with its normal dispatch.
+    fn handle_management_message(&self, topic: &str, payload: &str) -> Option<Result<()>> {
+        if let Some(path) = topic.strip_prefix("_config/").and_then(|t| t.strip_suffix("/set")) {
+            return Some(self.set_config_path(path, payload.trim()));
+        }
+        if let Some(path) = topic.strip_prefix("_config/").and_then(|t| t.strip_suffix("/get")) {
+            return Some(self.publish_config_path(path));
+        }
+
+        match topic {
+            "cmd/loglevel" => Some(self.set_log_level(payload.trim())),
+            "cmd/logs" => Some(self.publish_buffered_logs()),
+            _ => None,
+        }
+    }
+
+    /// Applies a runtime config mutation addressed by dotted `path` (see
+    /// `config_path`), then republishes the resolved value on the
+    /// corresponding `_config/<path>/get` topic so subscribers - e.g. a Home
+    /// Assistant automation - see the change take effect immediately.
+    fn set_config_path(&self, path: &str, payload: &str) -> Result<()> {
+        self.config.set_path(path, payload)?;
+        self.publish_config_path(path)
+    }
+
+    /// Publishes the current value at dotted `path` on `_config/<path>/get`.
+    fn publish_config_path(&self, path: &str) -> Result<()> {
+        let value = self.config.get_path(path)?;
+        let message = ChannelData::Message(Message {
+            topic: format!("_config/{}/get", path),
+            retain: true,
+            payload: value,
+            ..Default::default()
+        });
+        if self.channels.to_mqtt.send(message).is_err() {
+            bail!("send(to_mqtt) failed - channel closed?");
+        }
+        Ok(())
+    }
+
+    /// Changes the running log filter without a restart, e.g.
+    /// `mosquitto_pub -t <namespace>/cmd/loglevel -m debug`.
+    fn set_log_level(&self, level: &str) -> Result<()> {
+        let logger = logging::handle().ok_or_else(|| anyhow!("no reloadable logger installed"))?;
+        logger.set_level(level)?;
+        info!("log level changed to {} via MQTT", level);
+        Ok(())
+    }
+
+    /// Drains the in-memory log ring buffer and publishes it to
+    /// `logs/chunk/{n}`, `LOG_CHUNK_LINES` lines per message, so a large
+    /// buffer doesn't block the MQTT sender task with one giant payload.
+    fn publish_buffered_logs(&self) -> Result<()> {
+        let logger = logging::handle().ok_or_else(|| anyhow!("no reloadable logger installed"))?;
+        let lines = logger.drain_buffer();
+
+        for (i, chunk) in lines.chunks(LOG_CHUNK_LINES).enumerate() {
+            let message = ChannelData::Message(Message {
+                topic: format!("logs/chunk/{}", i),
+                retain: false,
+                payload: chunk.join("\n"),
+                ..Default::default()
+            });
+            if self.channels.to_mqtt.send(message).is_err() {
+                bail!("send(to_mqtt) failed - channel closed?");
+            }
+        }
+
+        Ok(())
+    }
+
     // mqtt -> coordinator
-    async fn receiver(&self, mut eventloop: EventLoop) -> Result<()> {
+    //
+    // Acks are sent manually (see `set_manual_acks` in `start`) only after
+    // `handle_message` has successfully forwarded the command onto
+    // `channels.from_mqtt`, so a closed coordinator channel leaves the
+    // message unacked and the broker redelivers it instead of losing it.
+    async fn receiver(&self, client: AsyncClient, mut eventloop: EventLoop) -> Result<()> {
         loop {
             if self.shutdown {
                 info!("MQTT receiver shutting down");
@@ -477,7 +759,16 @@ impl Mqtt {
             {
                 match event {
                     Ok(Event::Incoming(Incoming::Publish(publish))) => {
-                        self.handle_message(publish)?;
+                        match self.handle_message(publish.clone()) {
+                            Ok(()) => {
+                                if let Err(e) = client.ack(&publish).await {
+                                    error!("failed to ack mqtt publish: {}", e);
+                                }
+                            }
+                            Err(e) => {
+                                error!("not acking mqtt publish, will be redelivered: {}", e);
+                            }
+                        }
                     }
                     Err(e) => {
                         if !self.shutdown {
@@ -499,11 +790,17 @@ impl Mqtt {
         // remove the namespace, including the first /
         // doing it this way means we don't break if namespace happens to contain a /
         let topic = publish.topic[self.config.mqtt().namespace().len() + 1..].to_owned();
+        let payload = String::from_utf8(publish.payload.to_vec())?;
+
+        if let Some(result) = self.handle_management_message(&topic, &payload) {
+            return result;
+        }
 
         let message = Message {
             topic,
             retain: publish.retain,
-            payload: String::from_utf8(publish.payload.to_vec())?,
+            payload,
+            ..Default::default()
         };
         debug!("RX: {:?}", message);
         if self
@@ -525,20 +822,28 @@ impl Mqtt {
         let mut receiver = self.channels.to_mqtt.subscribe();
 
         loop {
-            match receiver.recv().await? {
+            match crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToMqtt).await? {
                 Shutdown => {
                     info!("MQTT sender received shutdown signal");
+                    // Publish the offline state ourselves so a clean shutdown is
+                    // distinguishable (by timing/retain semantics aside, both end
+                    // up retained "offline") from the broker firing the last will
+                    // on an unexpected disconnect.
+                    if let Err(e) = client.publish(self.lwt_topic(), QoS::AtLeastOnce, true, "offline").await {
+                        warn!("failed to publish offline status before shutdown: {}", e);
+                    }
                     // Flush any remaining messages before exiting
                     let _ = client.disconnect().await;
                     break;
                 }
                 Message(message) => {
                     let topic = format!("{}/{}", self.config.mqtt().namespace(), message.topic);
+                    let qos = qos_from_u8(self.qos_for_topic(&message.topic));
                     info!("publishing: {} = {}", topic, message.payload);
                     let payload = message.payload.as_bytes().to_vec();
                     let mut retry_count = 0;
                     loop {
-                        match client.publish(&topic, QoS::AtLeastOnce, message.retain, payload.as_slice()).await {
+                        match client.publish(&topic, qos, message.retain, payload.as_slice()).await {
                             Ok(_) => {
                                 info!("Successfully published message to topic: {}", topic);
                                 // Increment stats after successful publish
@@ -569,4 +874,213 @@ impl Mqtt {
     fn lwt_topic(&self) -> String {
         format!("{}/LWT", self.config.mqtt().namespace())
     }
+
+    /// Picks the configured QoS for an outgoing `message.topic` (already
+    /// stripped of the namespace prefix) based on which message class it
+    /// belongs to.
+    fn qos_for_topic(&self, topic: &str) -> u8 {
+        let mqtt = self.config.mqtt();
+        if topic.starts_with("result/") {
+            mqtt.qos_commands()
+        } else if topic.contains("/input") {
+            mqtt.qos_input()
+        } else {
+            mqtt.qos_state()
+        }
+    }
+
+    async fn setup_v5(&self, client: rumqttc::v5::AsyncClient) -> Result<()> {
+        use rumqttc::v5::mqttbytes::QoS as QoSV5;
+
+        client
+            .publish(self.lwt_topic(), QoSV5::AtLeastOnce, true, "online")
+            .await?;
+
+        let cmd_qos = qos_from_u8_v5(self.config.mqtt().qos_commands());
+
+        client
+            .subscribe(
+                format!("{}/cmd/all/#", self.config.mqtt().namespace()),
+                cmd_qos,
+            )
+            .await?;
+
+        for inverter in self.config.enabled_inverters() {
+            client
+                .subscribe(
+                    format!(
+                        "{}/cmd/{}/#",
+                        self.config.mqtt().namespace(),
+                        inverter.datalog().map(|s| s.to_string()).unwrap_or_default()
+                    ),
+                    cmd_qos,
+                )
+                .await?;
+
+            if self.config.homeassistant_enabled() && self.config.mqtt().homeassistant().enabled() {
+                let discovery_qos = qos_from_u8_v5(self.config.mqtt().qos_discovery());
+                let ha = home_assistant::Config::new(&inverter, &self.config.mqtt(), &self.config);
+                for msg in ha.all()?.into_iter() {
+                    let _ = client
+                        .publish(&msg.topic, discovery_qos, msg.retain, msg.payload)
+                        .await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // mqtt -> coordinator (v5)
+    //
+    // Same manual-ack discipline as `receiver`: only ack once the command
+    // has been forwarded onto `channels.from_mqtt`.
+    async fn receiver_v5(&self, client: rumqttc::v5::AsyncClient, mut eventloop: rumqttc::v5::EventLoop) -> Result<()> {
+        use rumqttc::v5::{mqttbytes::v5::Packet as IncomingV5, Event as EventV5};
+
+        loop {
+            if self.shutdown {
+                info!("MQTT (v5) receiver shutting down");
+                break;
+            }
+
+            if let Ok(event) =
+                tokio::time::timeout(std::time::Duration::from_secs(1), eventloop.poll()).await
+            {
+                match event {
+                    Ok(EventV5::Incoming(IncomingV5::Publish(publish))) => {
+                        let full_topic = String::from_utf8(publish.topic.to_vec())?;
+                        let topic = full_topic[self.config.mqtt().namespace().len() + 1..].to_owned();
+                        let payload = String::from_utf8(publish.payload.to_vec())?;
+
+                        // A command sent with a v5 Response Topic/Correlation Data
+                        // carries them through so the reply can go straight back to
+                        // the requester instead of the hardcoded `result/...` topic
+                        // - see `Coordinator::process_message`.
+                        let (response_topic, correlation_data, user_properties) =
+                            match &publish.properties {
+                                Some(props) => (
+                                    props.response_topic.clone(),
+                                    props.correlation_data.as_ref().map(|b| b.to_vec()),
+                                    props.user_properties.clone(),
+                                ),
+                                None => (None, None, Vec::new()),
+                            };
+
+                        let result = match self.handle_management_message(&topic, &payload) {
+                            Some(result) => result,
+                            None => {
+                                let message = Message {
+                                    topic,
+                                    retain: publish.retain,
+                                    payload,
+                                    response_topic,
+                                    correlation_data,
+                                    user_properties,
+                                };
+                                debug!("RX (v5): {:?}", message);
+                                self.channels
+                                    .from_mqtt
+                                    .send(ChannelData::Message(message))
+                                    .map(|_| ())
+                                    .map_err(|_| anyhow!("send(from_mqtt) failed - channel closed?"))
+                            }
+                        };
+
+                        match result {
+                            Ok(()) => {
+                                if let Err(e) = client.ack(&publish).await {
+                                    error!("failed to ack mqtt (v5) publish: {}", e);
+                                }
+                            }
+                            Err(e) => {
+                                error!("not acking mqtt (v5) publish, will be redelivered: {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        if !self.shutdown {
+                            error!("{}", e);
+                            info!("reconnecting in 5s");
+                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                        }
+                    }
+                    _ => {} // keepalives etc
+                }
+            }
+        }
+
+        info!("MQTT (v5) receiver loop exiting");
+        Ok(())
+    }
+
+    // coordinator -> mqtt (v5)
+    //
+    // Every publish carries the decoded register name (the last segment of
+    // its topic) as a user property, and non-retained `input` topics get a
+    // short message-expiry so stale high-frequency telemetry doesn't linger
+    // in a broker queue during a slow consumer.
+    async fn sender_v5(&self, client: rumqttc::v5::AsyncClient) -> Result<()> {
+        use rumqttc::v5::mqttbytes::v5::PublishProperties;
+        use ChannelData::*;
+
+        let mut receiver = self.channels.to_mqtt.subscribe();
+
+        loop {
+            match crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToMqtt).await? {
+                Shutdown => {
+                    info!("MQTT (v5) sender received shutdown signal");
+                    use rumqttc::v5::mqttbytes::QoS as QoSV5;
+                    if let Err(e) = client.publish(self.lwt_topic(), QoSV5::AtLeastOnce, true, "offline").await {
+                        warn!("failed to publish offline status before shutdown: {}", e);
+                    }
+                    let _ = client.disconnect().await;
+                    break;
+                }
+                Message(message) => {
+                    // A reply that came in with a v5 Response Topic goes
+                    // straight there, verbatim (it's the requester's own
+                    // topic, not ours to namespace); everything else keeps
+                    // publishing under our namespace as before.
+                    let topic = message
+                        .response_topic
+                        .clone()
+                        .unwrap_or_else(|| format!("{}/{}", self.config.mqtt().namespace(), message.topic));
+                    let name = message.topic.rsplit('/').next().unwrap_or(&message.topic);
+                    let qos = qos_from_u8_v5(self.qos_for_topic(&message.topic));
+
+                    let mut properties = PublishProperties::default();
+                    properties.user_properties.push(("name".to_string(), name.to_string()));
+                    properties.user_properties.extend(message.user_properties.clone());
+                    if let Some(correlation_data) = &message.correlation_data {
+                        properties.correlation_data = Some(bytes::Bytes::from(correlation_data.clone()));
+                    }
+                    if !message.retain && message.topic.contains("/input/") {
+                        properties.message_expiry_interval = Some(60);
+                    }
+
+                    info!("publishing (v5): {} = {}", topic, message.payload);
+                    match client
+                        .publish_with_properties(&topic, qos, message.retain, message.payload.as_bytes(), properties)
+                        .await
+                    {
+                        Ok(_) => {
+                            if let Ok(mut stats) = self.shared_stats.lock() {
+                                stats.mqtt_messages_sent += 1;
+                            }
+                        }
+                        Err(err) => {
+                            error!("MQTT (v5) publish failed: {:?}", err);
+                            if let Ok(mut stats) = self.shared_stats.lock() {
+                                stats.mqtt_errors += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        info!("MQTT (v5) sender loop exiting");
+        Ok(())
+    }
 }
@@ -19,12 +19,97 @@ pub struct Register {
     pub shortname: String,
     #[serde(default)]
     pub read_only: bool,
+
+    /// For 32-bit types (`u32`/`s32`), selects which of the two consecutive
+    /// registers (this one and `register_number + 1`) holds the low word.
+    /// `false` (the default) means `register_number` is the high word and
+    /// `register_number + 1` is the low word; `true` swaps that order.
+    /// Superseded by `endianness`, but still honored for older register
+    /// files that only set this.
+    #[serde(default)]
+    pub swap_words: bool,
+
+    /// How many consecutive registers, starting at `register_number`, this
+    /// definition spans. Defaults to 1 (a plain 16-bit value); left unset,
+    /// `word_count` still falls back to 2 for the legacy `u32`/`s32`
+    /// data types so existing register files keep working unchanged.
+    #[serde(default = "default_length")]
+    pub length: u16,
+
+    /// Word order for a multi-word (`length > 1`) value: `"big"` (the
+    /// default) means `register_number` holds the high word, `"little"`
+    /// swaps that order. `swap_words: true` has the same effect as
+    /// `endianness: "little"`.
+    #[serde(default = "default_endianness")]
+    pub endianness: String,
+
+    /// Added to `raw * scaling` so affine (not just multiplicative) values
+    /// can be expressed without the caller doing its own arithmetic.
+    #[serde(default)]
+    pub offset: f64,
+
+    /// For `datatype: "enum"`: maps this register's raw code to a label,
+    /// surfaced as a string-valued output instead of a number.
+    #[serde(default, rename = "enum")]
+    pub enum_values: Option<HashMap<u16, String>>,
+
+    /// For `datatype: "bitfield"`: expands this single register into one
+    /// boolean output per entry, named `{shortname}_{name}`.
+    #[serde(default)]
+    pub bitfield: Option<Vec<BitFlag>>,
+
+    /// How often the scheduler's per-register poll queue should refresh
+    /// this register, as a human-friendly duration string like "3s" or
+    /// "1m". Absent means this register is never polled on its own timer.
+    #[serde(default)]
+    pub period: Option<String>,
+
+    /// Which register type ("input"/"hold") this definition came from.
+    /// Populated by `RegisterParser::new` from the enclosing `RegisterType`,
+    /// not read from the register's own JSON.
+    #[serde(skip)]
+    pub register_type: String,
+}
+
+/// One named bit of a `"bitfield"` register, e.g. `{"bit": 3, "name":
+/// "overvoltage"}` decodes bit 3 into a `{shortname}_overvoltage` boolean.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BitFlag {
+    pub bit: u8,
+    pub name: String,
 }
 
 fn default_scaling() -> f64 {
     1.0
 }
 
+fn default_length() -> u16 {
+    1
+}
+
+fn default_endianness() -> String {
+    "big".to_string()
+}
+
+/// A single register decoded into its engineering value, for callers (like
+/// `DatalogWriter`) that want the typed value instead of a formatted string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedRegister {
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+}
+
+/// The decoded value of one field produced by `RegisterParser::decode_registers`.
+/// Most registers decode to a scaled `Number`; `"enum"` registers resolve to
+/// a `Text` label and `"bitfield"` registers expand into named `Flag`s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegisterValue {
+    Number(f64),
+    Text(String),
+    Flag(bool),
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct RegisterType {
     pub register_type: String,
@@ -104,6 +189,9 @@ impl RegisterParser {
                     0
                 };
                 
+                let mut register = register.clone();
+                register.register_type = register_type.register_type.clone();
+
                 if let Some(existing) = type_registers.get(&register.register_number) {
                     duplicates.push(format!(
                         "Register number {} is defined multiple times in type '{}':\n  - First: {} ({}) at line {}\n  - Second: {} ({}) at line {}",
@@ -166,23 +254,78 @@ impl RegisterParser {
         self.registers.get(&register_number)
     }
 
-    pub fn decode_registers(&self, raw_data: &HashMap<String, String>, show_unknown: bool, register_type: &str) -> HashMap<String, f64> {
+    /// Every loaded register, in no particular order - for callers (like
+    /// `home_assistant::Config`) that build something per-register rather
+    /// than looking one up by number/name.
+    pub fn all(&self) -> impl Iterator<Item = &Register> {
+        self.registers.values()
+    }
+
+    /// Looks a register up by its `shortname` (falling back to `name`) so
+    /// callers can build packets against a named register instead of a
+    /// magic register number - see `TranslatedData::read_register`/
+    /// `write_register`.
+    pub fn find_by_name(&self, name: &str) -> Option<&Register> {
+        self.registers.values().find(|r| {
+            (!r.shortname.is_empty() && r.shortname == name) || r.name == name
+        })
+    }
+
+    /// Returns `(register_number, register_type, period)` for every register
+    /// that declares a `period`, for the scheduler's per-register poll queue.
+    pub fn periodic_registers(&self) -> Vec<(u16, String, std::time::Duration)> {
+        self.registers
+            .values()
+            .filter_map(|r| {
+                let period = r.period()?;
+                Some((r.register_number, r.register_type.clone(), period))
+            })
+            .collect()
+    }
+
+    /// Rejects a write touching any of the `count` consecutive registers
+    /// starting at `register_number` if one is marked `read_only`, naming
+    /// the offending register and its shortname. `strict` additionally
+    /// rejects writes to registers absent from the loaded map entirely,
+    /// for deployments that want every write pre-declared.
+    pub fn can_write(&self, register_number: u16, count: u16, strict: bool) -> Result<()> {
+        for offset in 0..count {
+            let reg_num = register_number + offset;
+            match self.get_register(reg_num) {
+                Some(register) if register.access == "read_only" => {
+                    bail!(
+                        "refusing to write register {} ({}): marked read_only",
+                        reg_num,
+                        register.field_name()
+                    );
+                }
+                Some(_) => {}
+                None if strict => {
+                    bail!("refusing to write register {}: not present in the loaded register map", reg_num);
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn decode_registers(&self, raw_data: &HashMap<String, String>, show_unknown: bool, register_type: &str) -> HashMap<String, RegisterValue> {
         let mut decoded = HashMap::new();
-        
+
         for (reg_num_str, hex_value) in raw_data {
             if let Ok(reg_num) = reg_num_str.parse::<u16>() {
                 if let Some(register) = self.get_register(reg_num) {
-                    let value = register.decode_value(hex_value);
-                    let field_name = if !register.shortname.is_empty() {
-                        register.shortname.clone()
+                    let partner_hex = if register.is_32bit() {
+                        raw_data.get(&(reg_num + 1).to_string())
                     } else {
-                        register.name.clone()
+                        None
                     };
-                    decoded.insert(field_name, value);
+                    decoded.extend(register.decode_typed(hex_value, partner_hex.map(String::as_str)));
                 } else if show_unknown {
                     let value = u16::from_str_radix(hex_value, 16)
                         .unwrap_or(0) as f64;
-                    decoded.insert(format!("{}_unknown_{}", register_type, reg_num), value);
+                    decoded.insert(format!("{}_unknown_{}", register_type, reg_num), RegisterValue::Number(value));
                 }
             }
         }
@@ -192,14 +335,123 @@ impl RegisterParser {
 }
 
 impl Register {
+    /// How many consecutive registers this definition spans. `length` wins
+    /// when set above 1; otherwise the legacy `u32`/`s32`/`uint32`/`int32`
+    /// data types still imply 2 so older register files keep working.
+    pub(crate) fn word_count(&self) -> u16 {
+        if self.length > 1 {
+            self.length
+        } else if matches!(self.data_type.as_str(), "u32" | "s32" | "uint32" | "int32") {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn is_32bit(&self) -> bool {
+        self.word_count() == 2
+    }
+
+    /// Parses `period` into a `Duration`, if set.
+    pub fn period(&self) -> Option<std::time::Duration> {
+        self.period.as_deref().and_then(|s| crate::config::parse_duration_str(s).ok())
+    }
+
+    /// Orders `a` (this register's word) and `b` (its `register_number + 1`
+    /// partner) into `(high, low)`, honoring `endianness`/`swap_words` for
+    /// which one is the high word.
+    fn high_low(&self, a: u16, b: u16) -> (u16, u16) {
+        if self.swap_words || self.endianness.eq_ignore_ascii_case("little") {
+            (b, a)
+        } else {
+            (a, b)
+        }
+    }
+
+    /// Decodes a single 16-bit register according to `data_type`/`scaling`/
+    /// `offset`. `"float"` is kept for backward compatibility with existing
+    /// register files and behaves like `"u16"`.
     pub fn decode_value(&self, hex_value: &str) -> f64 {
-        let value = u16::from_str_radix(hex_value, 16)
-            .unwrap_or(0) as f64;
-        
-        if self.data_type == "float" {
-            value * self.scaling
+        let raw = u16::from_str_radix(hex_value, 16).unwrap_or(0);
+
+        let value = match self.data_type.as_str() {
+            "s16" | "int16" => raw as i16 as f64,
+            "u16" | "float" | "uint16" => raw as f64,
+            _ => raw as f64,
+        };
+
+        value * self.scaling + self.offset
+    }
+
+    /// Combines this register's hex word with its `register_number + 1`
+    /// partner into a 32-bit value, honoring `endianness`/`swap_words` for
+    /// which word is the high/low half, then applies `scaling`/`offset`.
+    pub fn decode_pair(&self, hex_value: &str, partner_hex_value: &str) -> f64 {
+        let a = u16::from_str_radix(hex_value, 16).unwrap_or(0);
+        let b = u16::from_str_radix(partner_hex_value, 16).unwrap_or(0);
+
+        let (hi, lo) = self.high_low(a, b);
+        let raw = ((hi as u32) << 16) | lo as u32;
+
+        let value = match self.data_type.as_str() {
+            "s32" | "int32" => raw as i32 as f64,
+            _ => raw as f64,
+        };
+
+        value * self.scaling + self.offset
+    }
+
+    /// The name this register's decoded value is published/stored under:
+    /// `shortname` if set, else `name`.
+    pub fn field_name(&self) -> String {
+        if !self.shortname.is_empty() {
+            self.shortname.clone()
         } else {
-            value
+            self.name.clone()
         }
     }
-} 
\ No newline at end of file
+
+    /// Decodes this register into one or more named `RegisterValue`s,
+    /// honoring `"enum"`/`"bitfield"` data types in addition to the plain
+    /// numeric ones `decode_value`/`decode_pair` handle. `partner_hex_value`
+    /// is only consulted for 32-bit types; a missing partner is logged and
+    /// skipped rather than publishing a half-built value.
+    fn decode_typed(&self, hex_value: &str, partner_hex_value: Option<&str>) -> HashMap<String, RegisterValue> {
+        let mut out = HashMap::new();
+        let field_name = self.field_name();
+
+        match self.data_type.as_str() {
+            "enum" => {
+                let raw = u16::from_str_radix(hex_value, 16).unwrap_or(0);
+                let label = self.enum_values.as_ref()
+                    .and_then(|values| values.get(&raw))
+                    .cloned()
+                    .unwrap_or_else(|| format!("unknown_{}", raw));
+                out.insert(field_name, RegisterValue::Text(label));
+            }
+            "bitfield" => {
+                let raw = u16::from_str_radix(hex_value, 16).unwrap_or(0);
+                for flag in self.bitfield.iter().flatten() {
+                    let set = raw & (1u16 << flag.bit) != 0;
+                    out.insert(format!("{}_{}", field_name, flag.name), RegisterValue::Flag(set));
+                }
+            }
+            _ if self.is_32bit() => match partner_hex_value {
+                Some(partner) => {
+                    out.insert(field_name, RegisterValue::Number(self.decode_pair(hex_value, partner)));
+                }
+                None => {
+                    warn!(
+                        "register {} ({}): partner word missing for 32-bit decode, skipping",
+                        self.register_number, field_name
+                    );
+                }
+            },
+            _ => {
+                out.insert(field_name, RegisterValue::Number(self.decode_value(hex_value)));
+            }
+        }
+
+        out
+    }
+}
\ No newline at end of file
@@ -1,5 +1,10 @@
+use crate::config;
+use crate::kafka_register_sink::KafkaRegisterSink;
 use crate::prelude::*;
+use crate::register_store::{EmbeddedMemoryStore, RegisterStore};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 // this just needs to be bigger than the max register we'll see
 const REGISTER_COUNT: usize = 512;
@@ -7,31 +12,125 @@ const REGISTER_COUNT: usize = 512;
 #[derive(Clone, Debug)]
 pub enum ChannelData {
     ReadRegister(u16, Arc<Mutex<Option<oneshot::Sender<u16>>>>),
+    /// Like `ReadRegister`, but only returns `Some(value)` if `register` was last
+    /// written within `Duration` of now; `None` means the mirror has no recent enough
+    /// value and the caller should re-poll the inverter instead of trusting stale data.
+    ReadRegisterFresh(u16, Duration, Arc<Mutex<Option<oneshot::Sender<Option<u16>>>>>),
+    /// Reads `count` consecutive registers starting at the first `u16`, in one
+    /// channel hop instead of `count` separate `ReadRegister`s.
+    ReadRegisterRange(u16, u16, Arc<Mutex<Option<oneshot::Sender<Vec<u16>>>>>),
     RegisterData(u16, u16),
+    /// Like `RegisterData`, but writes a whole run of consecutive register values
+    /// starting at the `u16` in one hop, for a full reply frame.
+    RegisterDataBulk(u16, Vec<u16>),
     Shutdown,
 }
 
+/// On-disk persistence settings plus the debounce flag that lets `snapshot_flusher`
+/// skip a write when nothing changed since the last flush.
+struct Snapshot {
+    path: String,
+    flush_interval: Duration,
+    dirty: AtomicBool,
+}
+
 pub struct RegisterCache {
     channels: Channels,
-    register_data: Arc<Mutex<[u16; REGISTER_COUNT]>>,
+    store: Box<dyn RegisterStore>,
+    /// When each register was last written by `cache_setter`, so `get_fresh` can tell a
+    /// just-polled value from one left over from startup or a stalled poll loop. Kept
+    /// local to this process regardless of `store`, since it's about *this* mirror's
+    /// freshness rather than the backing data itself.
+    register_timestamps: Arc<Mutex<[Option<chrono::NaiveDateTime>; REGISTER_COUNT]>>,
+    /// Optional publisher streaming every register delta out to Kafka. Absent unless
+    /// `with_kafka` was used, in which case `kafka_publisher` is a no-op.
+    kafka: Option<KafkaRegisterSink>,
+    /// Optional periodic on-disk snapshot, warm-started from on `start()`. Absent
+    /// unless `with_snapshot` was used, in which case `snapshot_flusher` is a no-op.
+    snapshot: Option<Snapshot>,
 }
 
 impl RegisterCache {
+    /// Builds a cache backed by the default process-local `EmbeddedMemoryStore`.
     pub fn new(channels: Channels) -> Self {
-        let register_data = Arc::new(Mutex::new([0; REGISTER_COUNT]));
+        Self::with_store(channels, Box::new(EmbeddedMemoryStore::new()))
+    }
+
+    /// Builds a cache backed by any `RegisterStore`, e.g. a `RedisStore` shared with
+    /// sibling processes against the same inverter.
+    pub fn with_store(channels: Channels, store: Box<dyn RegisterStore>) -> Self {
+        let register_timestamps = Arc::new(Mutex::new([None; REGISTER_COUNT]));
 
         Self {
             channels,
-            register_data,
+            store,
+            register_timestamps,
+            kafka: None,
+            snapshot: None,
         }
     }
 
+    /// Streams every register delta to Kafka in addition to the cache, spreading
+    /// high-frequency registers across partitions per `sink`'s configured strategy.
+    pub fn with_kafka(mut self, sink: KafkaRegisterSink) -> Self {
+        self.kafka = Some(sink);
+        self
+    }
+
+    /// Periodically persists the mirror to `config.path()` and warm-starts from it on
+    /// the next `start()`, so a restart doesn't serve zeros until the inverter is
+    /// re-polled.
+    pub fn with_snapshot(mut self, config: &config::RegisterCacheSnapshot) -> Self {
+        self.snapshot = Some(Snapshot {
+            path: config.path(),
+            flush_interval: Duration::from_secs(config.flush_interval_secs()),
+            dirty: AtomicBool::new(false),
+        });
+        self
+    }
+
     pub async fn start(&self) -> Result<()> {
-        futures::try_join!(self.cache_getter(), self.cache_setter())?;
+        self.warm_start().await;
+
+        futures::try_join!(
+            self.cache_getter(),
+            self.cache_setter(),
+            self.kafka_publisher(),
+            self.snapshot_flusher(),
+        )?;
 
         Ok(())
     }
 
+    /// Loads a previously-flushed snapshot into `store`, if `with_snapshot` was used
+    /// and a file exists at its path. Loaded registers are left stamped `None` in
+    /// `register_timestamps` - same as never having been polled - so `get_fresh`
+    /// treats them as stale until the inverter confirms them.
+    async fn warm_start(&self) {
+        let Some(snapshot) = &self.snapshot else {
+            return;
+        };
+
+        let bytes = match std::fs::read(&snapshot.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                warn!("register_cache: failed to read snapshot {}: {}", snapshot.path, e);
+                return;
+            }
+        };
+
+        match bincode::deserialize::<Vec<(u16, u16)>>(&bytes) {
+            Ok(entries) => {
+                info!("register_cache: warm-starting {} registers from {}", entries.len(), snapshot.path);
+                for (register, value) in entries {
+                    self.store.set(register, value).await;
+                }
+            }
+            Err(e) => warn!("register_cache: failed to parse snapshot {}: {}", snapshot.path, e),
+        }
+    }
+
     // external helper method to simplify access to the cache, use like so:
     //
     //   RegisterCache::get(&self.channels, 1);
@@ -45,23 +144,76 @@ impl RegisterCache {
             .expect("unexpected error reading from register cache")
     }
 
+    // external helper method for staleness-aware reads, use like so:
+    //
+    //   RegisterCache::get_fresh(&self.channels, 1, Duration::from_secs(5)).await
+    //
+    // returns `None` if `register` hasn't been refreshed within `max_age`.
+    pub async fn get_fresh(channels: &Channels, register: u16, max_age: Duration) -> Option<u16> {
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+        let channel_data = ChannelData::ReadRegisterFresh(register, max_age, tx);
+        let _ = channels.read_register_cache.send(channel_data);
+        rx.await
+            .expect("unexpected error reading from register cache")
+    }
+
+    // external helper method for reading a contiguous run of registers in one
+    // channel hop, use like so:
+    //
+    //   RegisterCache::get_range(&self.channels, 0, 40).await
+    //
+    pub async fn get_range(channels: &Channels, start: u16, count: u16) -> Vec<u16> {
+        let (tx, rx) = oneshot::channel();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+        let channel_data = ChannelData::ReadRegisterRange(start, count, tx);
+        let _ = channels.read_register_cache.send(channel_data);
+        rx.await
+            .expect("unexpected error reading from register cache")
+    }
+
     async fn cache_getter(&self) -> Result<()> {
         let mut receiver = self.channels.read_register_cache.subscribe();
 
         debug!("register_cache getter starting");
 
-        while let Ok(data) = receiver.recv().await {
-            match data {
-                ChannelData::ReadRegister(register, tx) => {
-                    let value = self.register_data.lock().unwrap()[register as usize];
+        loop {
+            match crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ReadRegisterCache).await {
+                Ok(ChannelData::ReadRegister(register, tx)) => {
+                    let value = self.store.get(register).await.unwrap_or(0);
                     if let Ok(mut tx) = tx.lock() {
                         if let Some(tx) = tx.take() {
                             let _ = tx.send(value);
                         }
                     }
                 }
-                ChannelData::Shutdown => break,
-                _ => (),
+                Ok(ChannelData::ReadRegisterFresh(register, max_age, tx)) => {
+                    let value = self.store.get(register).await.unwrap_or(0);
+                    let stamped_at = self.register_timestamps.lock().unwrap().get(register as usize).copied().flatten();
+                    let age = stamped_at.map(|stamped_at| chrono::Utc::now().naive_utc() - stamped_at);
+                    let fresh = match age {
+                        Some(age) => age.to_std().map(|age| age <= max_age).unwrap_or(false),
+                        None => false,
+                    };
+
+                    if let Ok(mut tx) = tx.lock() {
+                        if let Some(tx) = tx.take() {
+                            let _ = tx.send(fresh.then_some(value));
+                        }
+                    }
+                }
+                Ok(ChannelData::ReadRegisterRange(start, count, tx)) => {
+                    let values = self.store.get_range(start, count).await;
+                    if let Ok(mut tx) = tx.lock() {
+                        if let Some(tx) = tx.take() {
+                            let _ = tx.send(values);
+                        }
+                    }
+                }
+                Ok(ChannelData::Shutdown) => break,
+                Ok(_) => (),
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(_)) => unreachable!("recv_tracked retries Lagged internally"),
             }
         }
 
@@ -73,16 +225,119 @@ impl RegisterCache {
 
         debug!("register_cache setter starting");
 
-        while let Ok(data) = receiver.recv().await {
-            match data {
-                ChannelData::RegisterData(register, value) => {
-                    self.register_data.lock().unwrap()[register as usize] = value;
+        loop {
+            match crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToRegisterCache).await {
+                Ok(ChannelData::RegisterData(register, value)) => {
+                    self.store.set(register, value).await;
+                    if let Some(slot) = self.register_timestamps.lock().unwrap().get_mut(register as usize) {
+                        *slot = Some(chrono::Utc::now().naive_utc());
+                    }
+                    self.mark_dirty();
+                }
+                Ok(ChannelData::RegisterDataBulk(start, values)) => {
+                    self.store.set_range(start, &values).await;
+                    let now = Some(chrono::Utc::now().naive_utc());
+                    let mut timestamps = self.register_timestamps.lock().unwrap();
+                    for offset in 0..values.len() as u16 {
+                        if let Some(slot) = timestamps.get_mut((start + offset) as usize) {
+                            *slot = now;
+                        }
+                    }
+                    self.mark_dirty();
+                }
+                Ok(ChannelData::Shutdown) => break,
+                Ok(_) => (),
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(_)) => unreachable!("recv_tracked retries Lagged internally"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn mark_dirty(&self) {
+        if let Some(snapshot) = &self.snapshot {
+            snapshot.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Mirrors every register delta to Kafka when `with_kafka` configured a sink;
+    /// otherwise exits immediately so `start()`'s `try_join!` isn't held open by it.
+    async fn kafka_publisher(&self) -> Result<()> {
+        let Some(kafka) = &self.kafka else {
+            return Ok(());
+        };
+
+        let mut receiver = self.channels.to_register_cache.subscribe();
+
+        debug!("register_cache kafka publisher starting");
+
+        loop {
+            match crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToRegisterCache).await {
+                Ok(ChannelData::RegisterData(register, value)) => kafka.publish(register, value).await,
+                Ok(ChannelData::RegisterDataBulk(start, values)) => {
+                    for (offset, value) in values.into_iter().enumerate() {
+                        kafka.publish(start.saturating_add(offset as u16), value).await;
+                    }
                 }
-                ChannelData::Shutdown => break,
-                _ => (),
+                Ok(ChannelData::Shutdown) => break,
+                Ok(_) => (),
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(_)) => unreachable!("recv_tracked retries Lagged internally"),
             }
         }
 
         Ok(())
     }
+
+    /// Periodically flushes the mirror to disk when `with_snapshot` configured one,
+    /// skipping a write if nothing changed since the last flush; otherwise exits
+    /// immediately so `start()`'s `try_join!` isn't held open by it. Also flushes once
+    /// more on `ChannelData::Shutdown` so the last pre-exit state isn't lost.
+    async fn snapshot_flusher(&self) -> Result<()> {
+        let Some(snapshot) = &self.snapshot else {
+            return Ok(());
+        };
+
+        let mut receiver = self.channels.to_register_cache.subscribe();
+
+        debug!("register_cache snapshot flusher starting, path={}", snapshot.path);
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(snapshot.flush_interval) => {
+                    self.flush_snapshot(snapshot).await;
+                }
+                data = crate::channels::recv_tracked(&mut receiver, &self.channels.stats, crate::channels::ChannelKind::ToRegisterCache) => {
+                    match data {
+                        Ok(ChannelData::Shutdown) => {
+                            self.flush_snapshot(snapshot).await;
+                            break;
+                        }
+                        Ok(_) => (),
+                        Err(broadcast::error::RecvError::Closed) => break,
+                        Err(broadcast::error::RecvError::Lagged(_)) => unreachable!("recv_tracked retries Lagged internally"),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn flush_snapshot(&self, snapshot: &Snapshot) {
+        if !snapshot.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        let entries = self.store.snapshot().await;
+        match bincode::serialize(&entries) {
+            Ok(bytes) => {
+                if let Err(e) = std::fs::write(&snapshot.path, bytes) {
+                    warn!("register_cache: failed to write snapshot {}: {}", snapshot.path, e);
+                }
+            }
+            Err(e) => warn!("register_cache: failed to serialize snapshot: {}", e),
+        }
+    }
 }
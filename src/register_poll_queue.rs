@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use crate::register::RegisterParser;
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Metadata for one periodically-polled register.
+struct Entry {
+    register: u16,
+    register_type: String,
+    period: Duration,
+}
+
+/// A priority queue of registers due for a scheduled poll, keyed by
+/// next-due timestamp, so fast-changing registers (short `period`) come due
+/// far more often than slow ones without needing a single shared cadence.
+pub struct RegisterPollQueue {
+    entries: Vec<Entry>,
+    due: BinaryHeap<Reverse<(Instant, usize)>>,
+}
+
+/// A contiguous run of same-type registers that came due in the same tick,
+/// coalesced into a single `ReadInput`/`ReadHold` range to minimize
+/// round-trips to the inverter.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DueRange {
+    pub register_type: String,
+    pub start_register: u16,
+    pub count: u16,
+}
+
+impl RegisterPollQueue {
+    /// Builds a queue from every register in `parser` that declares a
+    /// `period`. All entries start due immediately.
+    pub fn new(parser: &RegisterParser) -> Self {
+        let now = Instant::now();
+        let mut entries = Vec::new();
+        let mut due = BinaryHeap::new();
+
+        for (register, register_type, period) in parser.periodic_registers() {
+            let idx = entries.len();
+            entries.push(Entry { register, register_type, period });
+            due.push(Reverse((now, idx)));
+        }
+
+        Self { entries, due }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pops every entry due at or before `now`, reschedules it for
+    /// `now + period`, and returns the due registers coalesced into
+    /// contiguous same-type ranges.
+    pub fn poll_due(&mut self, now: Instant) -> Vec<DueRange> {
+        let mut due_indices = Vec::new();
+
+        while let Some(Reverse((due_at, idx))) = self.due.peek().copied() {
+            if due_at > now {
+                break;
+            }
+            self.due.pop();
+            due_indices.push(idx);
+            self.due.push(Reverse((now + self.entries[idx].period, idx)));
+        }
+
+        self.coalesce(due_indices)
+    }
+
+    fn coalesce(&self, mut indices: Vec<usize>) -> Vec<DueRange> {
+        indices.sort_by_key(|&idx| (self.entries[idx].register_type.clone(), self.entries[idx].register));
+
+        let mut ranges: Vec<DueRange> = Vec::new();
+        for idx in indices {
+            let entry = &self.entries[idx];
+            if let Some(last) = ranges.last_mut() {
+                if last.register_type == entry.register_type
+                    && last.start_register + last.count == entry.register
+                {
+                    last.count += 1;
+                    continue;
+                }
+            }
+            ranges.push(DueRange {
+                register_type: entry.register_type.clone(),
+                start_register: entry.register,
+                count: 1,
+            });
+        }
+        ranges
+    }
+}
+
+/// Tracks which registers currently have a read outstanding, so a register
+/// whose previous scheduled read hasn't completed yet is skipped rather
+/// than piling up a second concurrent request for it.
+#[derive(Clone, Default)]
+pub struct OutstandingReads {
+    inner: std::sync::Arc<std::sync::Mutex<HashSet<u16>>>,
+}
+
+impl OutstandingReads {
+    /// Returns `true` and marks the whole range outstanding if none of its
+    /// registers already have a read in flight; returns `false` (no-op)
+    /// otherwise, meaning this tick's read for that range should be skipped.
+    pub fn try_start(&self, range: &DueRange) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let registers: Vec<u16> = (range.start_register..range.start_register + range.count).collect();
+        if registers.iter().any(|r| inner.contains(r)) {
+            return false;
+        }
+        inner.extend(registers);
+        true
+    }
+
+    pub fn finish(&self, range: &DueRange) {
+        let mut inner = self.inner.lock().unwrap();
+        for register in range.start_register..range.start_register + range.count {
+            inner.remove(&register);
+        }
+    }
+}
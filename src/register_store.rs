@@ -0,0 +1,182 @@
+use crate::prelude::*;
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::OnceCell;
+
+// this just needs to be bigger than the max register we'll see
+const REGISTER_COUNT: usize = 512;
+
+/// Pluggable backend for the register mirror `RegisterCache` maintains.
+///
+/// `EmbeddedMemoryStore` is the default, process-local backend. `RedisStore` lets
+/// multiple `eg4-bridge` processes against the same inverter (e.g. an HA add-on and a
+/// CLI tool) observe and share one register mirror instead of each polling and caching
+/// independently.
+#[async_trait]
+pub trait RegisterStore: Send + Sync {
+    async fn get(&self, register: u16) -> Option<u16>;
+    async fn set(&self, register: u16, value: u16);
+    async fn snapshot(&self) -> Vec<(u16, u16)>;
+
+    /// Reads `count` consecutive registers starting at `start`, missing ones as 0.
+    /// Default implementation is `count` sequential `get`s; backends that can hold a
+    /// single lock/round-trip for the whole range (see `EmbeddedMemoryStore`) should
+    /// override this.
+    async fn get_range(&self, start: u16, count: u16) -> Vec<u16> {
+        let mut values = Vec::with_capacity(count as usize);
+        for offset in 0..count {
+            values.push(self.get(start.saturating_add(offset)).await.unwrap_or(0));
+        }
+        values
+    }
+
+    /// Writes a contiguous run of register values starting at `start`. Default
+    /// implementation is `values.len()` sequential `set`s; see `get_range`.
+    async fn set_range(&self, start: u16, values: &[u16]) {
+        for (offset, value) in values.iter().enumerate() {
+            self.set(start.saturating_add(offset as u16), *value).await;
+        }
+    }
+}
+
+/// Process-local register mirror backed by a fixed-size in-memory array; the default
+/// `RegisterStore` when no shared backend is configured.
+pub struct EmbeddedMemoryStore {
+    data: Mutex<[u16; REGISTER_COUNT]>,
+}
+
+impl EmbeddedMemoryStore {
+    pub fn new() -> Self {
+        Self {
+            data: Mutex::new([0; REGISTER_COUNT]),
+        }
+    }
+}
+
+impl Default for EmbeddedMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RegisterStore for EmbeddedMemoryStore {
+    async fn get(&self, register: u16) -> Option<u16> {
+        self.data.lock().unwrap().get(register as usize).copied()
+    }
+
+    async fn set(&self, register: u16, value: u16) {
+        if let Some(slot) = self.data.lock().unwrap().get_mut(register as usize) {
+            *slot = value;
+        }
+    }
+
+    async fn snapshot(&self) -> Vec<(u16, u16)> {
+        self.data
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(register, value)| (register as u16, value))
+            .collect()
+    }
+
+    async fn get_range(&self, start: u16, count: u16) -> Vec<u16> {
+        let data = self.data.lock().unwrap();
+        (0..count)
+            .map(|offset| data.get(start.saturating_add(offset) as usize).copied().unwrap_or(0))
+            .collect()
+    }
+
+    async fn set_range(&self, start: u16, values: &[u16]) {
+        let mut data = self.data.lock().unwrap();
+        for (offset, value) in values.iter().enumerate() {
+            if let Some(slot) = data.get_mut(start as usize + offset) {
+                *slot = *value;
+            }
+        }
+    }
+}
+
+/// Redis-backed `RegisterStore`, keyed per-inverter-serial so multiple inverters
+/// sharing one Redis instance don't collide: each inverter's registers live in one
+/// Redis hash, fields are register numbers, values are register values.
+pub struct RedisStore {
+    client: redis::Client,
+    key: String,
+    // `MultiplexedConnection` pipelines concurrent commands over one underlying
+    // socket and is cheap to clone, so we connect once and hand out clones instead
+    // of reconnecting on every get/set/snapshot. Left unset until first use; a
+    // failed connect leaves the cell empty so the next call retries instead of
+    // wedging this store on a one-time connect failure.
+    connection: OnceCell<redis::aio::MultiplexedConnection>,
+}
+
+impl RedisStore {
+    /// `key` should uniquely identify the inverter (its datalog serial is the natural
+    /// choice) so multiple inverters sharing one Redis instance don't collide.
+    pub fn new(url: &str, key: impl Into<String>) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(|e| anyhow!("redis_store: failed to open client: {}", e))?;
+        Ok(Self {
+            client,
+            key: key.into(),
+            connection: OnceCell::new(),
+        })
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.connection
+            .get_or_try_init(|| async { self.client.get_multiplexed_async_connection().await })
+            .await
+            .map(|conn| conn.clone())
+            .map_err(|e| anyhow!("redis_store: failed to connect: {}", e))
+    }
+}
+
+#[async_trait]
+impl RegisterStore for RedisStore {
+    async fn get(&self, register: u16) -> Option<u16> {
+        let mut conn = self.connection().await.ok()?;
+        redis::cmd("HGET")
+            .arg(&self.key)
+            .arg(register)
+            .query_async(&mut conn)
+            .await
+            .ok()
+    }
+
+    async fn set(&self, register: u16, value: u16) {
+        let Ok(mut conn) = self.connection().await else {
+            warn!("redis_store: dropping write to register {} - no connection", register);
+            return;
+        };
+
+        let result: std::result::Result<(), redis::RedisError> = redis::cmd("HSET")
+            .arg(&self.key)
+            .arg(register)
+            .arg(value)
+            .query_async(&mut conn)
+            .await;
+
+        if let Err(e) = result {
+            warn!("redis_store: failed to write register {}: {}", register, e);
+        }
+    }
+
+    async fn snapshot(&self) -> Vec<(u16, u16)> {
+        let Ok(mut conn) = self.connection().await else {
+            return Vec::new();
+        };
+
+        let map: HashMap<u16, u16> = redis::cmd("HGETALL")
+            .arg(&self.key)
+            .query_async(&mut conn)
+            .await
+            .unwrap_or_default();
+
+        map.into_iter().collect()
+    }
+}
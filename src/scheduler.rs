@@ -1,4 +1,8 @@
 use crate::prelude::*;
+use crate::coordinator::commands::timesync::TimeSyncState;
+use crate::register::RegisterParser;
+use crate::register_poll_queue::{DueRange, OutstandingReads, RegisterPollQueue};
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Clone)]
@@ -12,6 +16,176 @@ impl Scheduler {
         Self { config, channels }
     }
 
+    /// Runs the poll for a single configured `RegisterGroup` against every
+    /// enabled inverter.
+    async fn poll_register_group(&self, group: &config::RegisterGroup) {
+        for inverter in self.config.enabled_inverters() {
+            let result = match group.register_type() {
+                "input" => {
+                    crate::coordinator::commands::read_inputs::ReadInputs::new(
+                        self.channels.clone(),
+                        inverter.clone(),
+                        group.start_register(),
+                        group.count(),
+                    )
+                    .run()
+                    .await
+                    .map(|_| ())
+                }
+                "hold" => {
+                    crate::coordinator::commands::read_hold::ReadHold::new(
+                        self.channels.clone(),
+                        inverter.clone(),
+                        group.start_register(),
+                        group.count(),
+                    )
+                    .run()
+                    .await
+                    .map(|_| ())
+                }
+                "param" => {
+                    crate::coordinator::commands::read_param::ReadParam::new(
+                        self.channels.clone(),
+                        inverter.clone(),
+                        group.start_register(),
+                    )
+                    .run()
+                    .await
+                    .map(|_| ())
+                }
+                other => {
+                    error!("register group '{}' has unknown register_type '{}'", group.name(), other);
+                    Ok(())
+                }
+            };
+
+            if let Err(e) = result {
+                error!(
+                    "scheduled poll of register group '{}' failed for inverter {}: {}",
+                    group.name(),
+                    inverter.serial().map(|s| s.to_string()).unwrap_or_default(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Spawns one independent timer task per configured register group that
+    /// has a `period` set. Groups without a period stay command-only.
+    fn spawn_register_group_timers(&self) {
+        for group in self.config.scheduler().map(|s| s.register_groups().to_vec()).unwrap_or_default() {
+            let Some(period) = group.period() else {
+                debug!("register group '{}' has no period, staying command-only", group.name());
+                continue;
+            };
+
+            let scheduler = self.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    debug!("register group '{}' ticked", group.name());
+                    scheduler.poll_register_group(&group).await;
+                }
+            });
+        }
+    }
+
+    /// Performs the single coalesced read for `range` against every enabled
+    /// inverter, releasing `outstanding`'s guard for this range once done so
+    /// a later tick can poll it again.
+    async fn run_due_range(&self, range: DueRange, outstanding: OutstandingReads) {
+        for inverter in self.config.enabled_inverters() {
+            let result = match range.register_type.as_str() {
+                "hold" => {
+                    crate::coordinator::commands::read_hold::ReadHold::new(
+                        self.channels.clone(),
+                        inverter.clone(),
+                        range.start_register,
+                        range.count,
+                    )
+                    .run()
+                    .await
+                    .map(|_| ())
+                }
+                _ => {
+                    crate::coordinator::commands::read_inputs::ReadInputs::new(
+                        self.channels.clone(),
+                        inverter.clone(),
+                        range.start_register,
+                        range.count,
+                    )
+                    .run()
+                    .await
+                    .map(|_| ())
+                }
+            };
+
+            if let Err(e) = result {
+                error!(
+                    "register poll queue: scheduled read of {} register(s) {}..{} failed for inverter {}: {}",
+                    range.register_type,
+                    range.start_register,
+                    range.start_register + range.count,
+                    inverter.serial().map(|s| s.to_string()).unwrap_or_default(),
+                    e
+                );
+            }
+        }
+
+        outstanding.finish(&range);
+    }
+
+    /// Spawns a 1s ticker driving a priority queue of per-register poll
+    /// periods (declared on each `Register` in `register_file`). Each tick,
+    /// due registers are coalesced into contiguous `ReadInput`/`ReadHold`
+    /// ranges; a range whose previous read is still outstanding is skipped
+    /// rather than queued up behind it.
+    fn spawn_register_poll_queue_timer(&self) {
+        let Some(register_file) = self.config.register_file() else {
+            return;
+        };
+
+        let parser = match RegisterParser::new(&register_file) {
+            Ok(parser) => parser,
+            Err(e) => {
+                error!("register poll queue: failed to load register file {}: {}", register_file, e);
+                return;
+            }
+        };
+
+        let mut queue = RegisterPollQueue::new(&parser);
+        if queue.is_empty() {
+            debug!("register poll queue: no registers declare a period, skipping");
+            return;
+        }
+
+        let scheduler = self.clone();
+        let outstanding = OutstandingReads::default();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                for range in queue.poll_due(std::time::Instant::now()) {
+                    if !outstanding.try_start(&range) {
+                        debug!(
+                            "register poll queue: skipping {} {}..{}, previous read still outstanding",
+                            range.register_type, range.start_register, range.start_register + range.count
+                        );
+                        continue;
+                    }
+
+                    let scheduler = scheduler.clone();
+                    let outstanding = outstanding.clone();
+                    tokio::spawn(async move {
+                        scheduler.run_due_range(range, outstanding).await;
+                    });
+                }
+            }
+        });
+    }
+
     async fn read_input_registers(&self, inverter: &config::Inverter) -> Result<()> {
         let block_size = inverter.register_block_size();
         
@@ -36,6 +210,10 @@ impl Scheduler {
 
     pub async fn start(&self) -> Result<()> {
         info!("Scheduler starting...");
+
+        self.spawn_register_group_timers();
+        self.spawn_register_poll_queue_timer();
+
         // Create intervals for time sync and register reading
         let mut timesync_interval = tokio::time::interval(Duration::from_secs(60));
         
@@ -48,17 +226,23 @@ impl Scheduler {
         // Create a channel for shutdown notification
         let mut shutdown_rx = self.channels.from_coordinator.subscribe();
 
+        // Per-inverter TimeSync backoff/error-budget state, kept across ticks
+        // for the lifetime of the scheduler loop.
+        let mut timesync_state: HashMap<Serial, TimeSyncState> = HashMap::new();
+
         loop {
             debug!("Scheduler waiting for interval tick or shutdown...");
             tokio::select! {
                 _ = timesync_interval.tick() => {
                     debug!("Timesync interval ticked");
                     for inverter in self.config.enabled_inverters() {
+                        let Some(datalog) = inverter.datalog() else { continue };
+                        let state = timesync_state.entry(datalog).or_default();
                         if let Err(e) = crate::coordinator::commands::timesync::TimeSync::new(
                             self.channels.clone(),
                             inverter.clone(),
                         )
-                        .run()
+                        .run(state)
                         .await
                         {
                             error!("Failed to sync time for inverter {}: {}", inverter.serial().unwrap_or_default(), e);
@@ -0,0 +1,22 @@
+use crate::prelude::*;
+
+use async_trait::async_trait;
+
+/// A destination that decoded inverter telemetry can be written to.
+///
+/// `Influx` is the first implementor; additional sinks (an MQTT publisher,
+/// a JSON-lines file writer, a Prometheus remote-write endpoint, ...) can be
+/// registered alongside it without the coordinator needing to know about
+/// their wire protocols.
+#[async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Starts any background task the sink needs (e.g. a batching writer).
+    async fn start(&self) -> Result<()>;
+
+    /// Writes one decoded `InputData` payload (the same JSON shape the
+    /// coordinator currently sends over `to_influx`) to the sink.
+    async fn write(&self, data: &serde_json::Value) -> Result<()>;
+
+    /// Signals the sink's background task to shut down.
+    fn stop(&self);
+}
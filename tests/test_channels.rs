@@ -0,0 +1,28 @@
+mod common;
+use common::*;
+use eg4_bridge::channels::{recv_tracked, ChannelConfig, ChannelKind};
+use eg4_bridge::database;
+use eg4_bridge::prelude::Channels;
+
+#[tokio::test]
+async fn lagged_receiver_is_counted_and_recv_tracked_still_returns_the_latest_message() {
+    common_setup();
+
+    let config = ChannelConfig {
+        to_database: 1,
+        ..ChannelConfig::default()
+    };
+    let channels = Channels::with_config(config);
+
+    let mut receiver = channels.to_database.subscribe();
+
+    // Overflow the capacity-1 channel so the subscriber lags behind.
+    let _ = channels.to_database.send(database::ChannelData::Shutdown);
+    let _ = channels.to_database.send(database::ChannelData::Shutdown);
+    let _ = channels.to_database.send(database::ChannelData::Shutdown);
+
+    let result = recv_tracked(&mut receiver, &channels.stats, ChannelKind::ToDatabase).await;
+
+    assert!(result.is_ok());
+    assert!(channels.stats.dropped(ChannelKind::ToDatabase) > 0);
+}
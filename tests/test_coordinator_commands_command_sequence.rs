@@ -0,0 +1,91 @@
+mod common;
+use common::*;
+use eg4_bridge::prelude::*;
+use eg4_bridge::eg4;
+use eg4_bridge::eg4::packet::Packet;
+use eg4_bridge::coordinator::commands::command_sequence::{CommandSequence, ReadIntent, ReadKind};
+use eg4_bridge::prelude::Channels;
+
+#[tokio::test]
+async fn replays_recorded_steps_in_order() {
+    common_setup();
+
+    let inverter = Factory::inverter();
+    let channels = Channels::new();
+
+    let intents = vec![
+        ReadIntent { kind: ReadKind::Hold, register: 0, count: 1 },
+        ReadIntent { kind: ReadKind::Input, register: 10, count: 1 },
+    ];
+
+    let subject = CommandSequence::record(channels.clone(), &inverter, &intents).unwrap();
+
+    let sf = async {
+        let results = subject.replay(&inverter).await?;
+        assert_eq!(
+            results,
+            vec![
+                Packet::TranslatedData(eg4::packet::TranslatedData {
+                    datalog: inverter.datalog(),
+                    device_function: eg4::packet::DeviceFunction::ReadHold,
+                    inverter: inverter.serial(),
+                    register: 0,
+                    values: vec![1, 2],
+                    checksum_valid: true,
+                }),
+                Packet::TranslatedData(eg4::packet::TranslatedData {
+                    datalog: inverter.datalog(),
+                    device_function: eg4::packet::DeviceFunction::ReadInput,
+                    inverter: inverter.serial(),
+                    register: 10,
+                    values: vec![3, 4],
+                    checksum_valid: true,
+                }),
+            ]
+        );
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let tf = async {
+        let mut to_inverter = channels.to_inverter.subscribe();
+
+        for (register, function, values) in [
+            (0u16, eg4::packet::DeviceFunction::ReadHold, vec![1u8, 2u8]),
+            (10u16, eg4::packet::DeviceFunction::ReadInput, vec![3u8, 4u8]),
+        ] {
+            to_inverter.recv().await?;
+            channels
+                .from_inverter
+                .send(eg4::inverter::ChannelData::Packet(Packet::TranslatedData(
+                    eg4::packet::TranslatedData {
+                        datalog: inverter.datalog(),
+                        device_function: function,
+                        inverter: inverter.serial(),
+                        register,
+                        values,
+                        checksum_valid: true,
+                    },
+                )))?;
+        }
+
+        Ok::<(), anyhow::Error>(())
+    };
+
+    futures::try_join!(tf, sf).unwrap();
+}
+
+#[tokio::test]
+async fn fails_fast_when_inverter_identity_changed_since_recording() {
+    common_setup();
+
+    let inverter = Factory::inverter();
+    let channels = Channels::new();
+
+    let intents = vec![ReadIntent { kind: ReadKind::Hold, register: 0, count: 1 }];
+    let subject = CommandSequence::record(channels.clone(), &inverter, &intents).unwrap();
+
+    let mut changed = inverter.clone();
+    changed.serial = Some(eg4::inverter::Serial::from("0000000001"));
+
+    assert!(subject.replay(&changed).await.is_err());
+}
@@ -32,6 +32,7 @@ async fn test_read_hold() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -42,6 +43,7 @@ async fn test_read_hold() -> Result<()> {
         inverter: serial,
         register: 0,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -77,6 +79,7 @@ async fn happy_path() {
         inverter: inverter.serial(),
         register: 0,
         values: vec![0, 0],
+        checksum_valid: true,
     });
 
     let sf = async {
@@ -117,7 +120,7 @@ async fn no_reply() {
         let result = subject.run().await;
         assert_eq!(
             result.unwrap_err().to_string(),
-            "wait_for_reply TranslatedData(TranslatedData { datalog: 2222222222, device_function: ReadHold, inverter: 5555555555, register: 0, values: [40, 0] }) - timeout"
+            "wait_for_reply TranslatedData(TranslatedData { datalog: 2222222222, device_function: ReadHold, inverter: 5555555555, register: 0, values: [40, 0], checksum_valid: true }) - timeout after 3 attempts"
         );
         Ok::<(), anyhow::Error>(())
     };
@@ -183,6 +186,7 @@ async fn test_read_hold_timeout() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -218,6 +222,7 @@ async fn test_read_hold_wrong_inverter() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -228,6 +233,7 @@ async fn test_read_hold_wrong_inverter() -> Result<()> {
         inverter: wrong_serial,
         register: 0,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -264,6 +270,7 @@ async fn test_read_hold_wrong_register() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -274,6 +281,7 @@ async fn test_read_hold_wrong_register() -> Result<()> {
         inverter: serial,
         register: 1,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -310,6 +318,7 @@ async fn test_read_hold_wrong_function() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -320,6 +329,7 @@ async fn test_read_hold_wrong_function() -> Result<()> {
         inverter: serial,
         register: 0,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -356,6 +366,7 @@ async fn test_read_hold_wrong_count() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -366,6 +377,7 @@ async fn test_read_hold_wrong_count() -> Result<()> {
         inverter: serial,
         register: 0,
         values: vec![42, 43],
+        checksum_valid: true,
     });
 
     channels
@@ -402,6 +414,7 @@ async fn test_read_hold_wrong_datalog() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -412,6 +425,7 @@ async fn test_read_hold_wrong_datalog() -> Result<()> {
         inverter: serial,
         register: 0,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -448,6 +462,7 @@ async fn test_read_hold_wrong_register_and_count() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -458,6 +473,7 @@ async fn test_read_hold_wrong_register_and_count() -> Result<()> {
         inverter: serial,
         register: 1,
         values: vec![42, 43],
+        checksum_valid: true,
     });
 
     channels
@@ -494,6 +510,7 @@ async fn test_read_hold_wrong_register_and_function() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -504,6 +521,7 @@ async fn test_read_hold_wrong_register_and_function() -> Result<()> {
         inverter: serial,
         register: 1,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -540,6 +558,7 @@ async fn test_read_hold_wrong_register_and_datalog() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -550,6 +569,7 @@ async fn test_read_hold_wrong_register_and_datalog() -> Result<()> {
         inverter: serial,
         register: 1,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -586,6 +606,7 @@ async fn test_read_hold_wrong_function_and_datalog() -> Result<()> {
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -596,6 +617,7 @@ async fn test_read_hold_wrong_function_and_datalog() -> Result<()> {
         inverter: serial,
         register: 0,
         values: vec![42],
+        checksum_valid: true,
     });
 
     channels
@@ -632,6 +654,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count() -> R
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -642,6 +665,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count() -> R
         inverter: serial,
         register: 1,
         values: vec![42, 43],
+        checksum_valid: true,
     });
 
     channels
@@ -680,6 +704,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count_and_in
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -690,6 +715,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count_and_in
         inverter: wrong_serial,
         register: 1,
         values: vec![42, 43],
+        checksum_valid: true,
     });
 
     channels
@@ -728,6 +754,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count_and_in
             inverter: serial,
             register,
             values: vec![0; count as usize],
+            checksum_valid: true,
         })
     );
 
@@ -738,6 +765,7 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count_and_in
         inverter: wrong_serial,
         register: 2,
         values: vec![42, 43, 44],
+        checksum_valid: true,
     });
 
     channels
@@ -749,3 +777,75 @@ async fn test_read_hold_wrong_register_and_function_and_datalog_and_count_and_in
 
     Ok(())
 }
+
+#[tokio::test]
+async fn chunks_reads_above_the_per_chunk_ceiling() {
+    common_setup();
+
+    let inverter = Factory::inverter();
+    let channels = Channels::new();
+
+    let register = 0 as u16;
+    let count = 5 as u16;
+
+    let subject = coordinator::commands::read_hold::ReadHold::new(
+        channels.clone(),
+        inverter.clone(),
+        register,
+        count,
+    )
+    .with_max_registers_per_read(2);
+
+    let sf = async {
+        let result = subject.run().await?;
+        assert_eq!(
+            result,
+            Packet::TranslatedData(eg4::packet::TranslatedData {
+                datalog: inverter.datalog(),
+                device_function: eg4::packet::DeviceFunction::ReadHold,
+                inverter: inverter.serial(),
+                register: 0,
+                values: vec![1, 2, 3, 4, 5],
+                checksum_valid: true,
+            })
+        );
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let tf = async {
+        let mut to_inverter = channels.to_inverter.subscribe();
+
+        // 5 registers split into chunks of at most 2: [0, 2), [2, 4), [4, 5)
+        for (chunk_register, values) in [(0u16, vec![1, 2]), (2u16, vec![3, 4]), (4u16, vec![5])] {
+            let sent = unwrap_inverter_channeldata_packet(to_inverter.recv().await?);
+            assert_eq!(
+                sent,
+                Packet::TranslatedData(eg4::packet::TranslatedData {
+                    datalog: inverter.datalog(),
+                    device_function: eg4::packet::DeviceFunction::ReadHold,
+                    inverter: inverter.serial(),
+                    register: chunk_register,
+                    values: vec![values.len() as u8, 0],
+                    checksum_valid: true,
+                })
+            );
+
+            channels
+                .from_inverter
+                .send(eg4::inverter::ChannelData::Packet(Packet::TranslatedData(
+                    eg4::packet::TranslatedData {
+                        datalog: inverter.datalog(),
+                        device_function: eg4::packet::DeviceFunction::ReadHold,
+                        inverter: inverter.serial(),
+                        register: chunk_register,
+                        values,
+                        checksum_valid: true,
+                    },
+                )))?;
+        }
+
+        Ok::<(), anyhow::Error>(())
+    };
+
+    futures::try_join!(tf, sf).unwrap();
+}
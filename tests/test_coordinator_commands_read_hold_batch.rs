@@ -0,0 +1,109 @@
+mod common;
+use common::*;
+use eg4_bridge::prelude::*;
+use eg4_bridge::eg4;
+use eg4_bridge::eg4::packet::Packet;
+use eg4_bridge::coordinator::commands::read_hold_batch::{ReadHoldBatch, ReadIntent};
+use eg4_bridge::prelude::Channels;
+
+#[tokio::test]
+async fn merges_adjacent_intents_into_one_request() {
+    common_setup();
+
+    let inverter = Factory::inverter();
+    let channels = Channels::new();
+
+    let intents = vec![
+        ReadIntent { register: 0, count: 2 },
+        ReadIntent { register: 2, count: 1 },
+    ];
+
+    let subject = ReadHoldBatch::new(channels.clone(), inverter.clone());
+
+    let sf = async {
+        let result = subject.run(&intents).await?;
+        assert_eq!(result, vec![vec![1, 2, 3, 4], vec![5, 6]]);
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let tf = async {
+        let mut to_inverter = channels.to_inverter.subscribe();
+
+        // Both intents are adjacent ([0,2) and [2,3)), so exactly one combined
+        // request covering [0, 3) should be sent.
+        let sent = unwrap_inverter_channeldata_packet(to_inverter.recv().await?);
+        assert_eq!(
+            sent,
+            Packet::TranslatedData(eg4::packet::TranslatedData {
+                datalog: inverter.datalog(),
+                device_function: eg4::packet::DeviceFunction::ReadHold,
+                inverter: inverter.serial(),
+                register: 0,
+                values: vec![3, 0],
+                checksum_valid: true,
+            })
+        );
+
+        channels
+            .from_inverter
+            .send(eg4::inverter::ChannelData::Packet(Packet::TranslatedData(
+                eg4::packet::TranslatedData {
+                    datalog: inverter.datalog(),
+                    device_function: eg4::packet::DeviceFunction::ReadHold,
+                    inverter: inverter.serial(),
+                    register: 0,
+                    values: vec![1, 2, 3, 4, 5, 6],
+                    checksum_valid: true,
+                },
+            )))?;
+
+        Ok::<(), anyhow::Error>(())
+    };
+
+    futures::try_join!(tf, sf).unwrap();
+}
+
+#[tokio::test]
+async fn keeps_disjoint_intents_as_separate_requests() {
+    common_setup();
+
+    let inverter = Factory::inverter();
+    let channels = Channels::new();
+
+    let intents = vec![
+        ReadIntent { register: 0, count: 1 },
+        ReadIntent { register: 100, count: 1 },
+    ];
+
+    let subject = ReadHoldBatch::new(channels.clone(), inverter.clone());
+
+    let sf = async {
+        let result = subject.run(&intents).await?;
+        assert_eq!(result, vec![vec![1, 2], vec![9, 9]]);
+        Ok::<(), anyhow::Error>(())
+    };
+
+    let tf = async {
+        let mut to_inverter = channels.to_inverter.subscribe();
+
+        for (register, values) in [(0u16, vec![1u8, 2u8]), (100u16, vec![9u8, 9u8])] {
+            to_inverter.recv().await?;
+            channels
+                .from_inverter
+                .send(eg4::inverter::ChannelData::Packet(Packet::TranslatedData(
+                    eg4::packet::TranslatedData {
+                        datalog: inverter.datalog(),
+                        device_function: eg4::packet::DeviceFunction::ReadHold,
+                        inverter: inverter.serial(),
+                        register,
+                        values,
+                        checksum_valid: true,
+                    },
+                )))?;
+        }
+
+        Ok::<(), anyhow::Error>(())
+    };
+
+    futures::try_join!(tf, sf).unwrap();
+}
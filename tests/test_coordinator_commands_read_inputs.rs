@@ -31,6 +31,7 @@ async fn happy_path() {
         inverter: inverter.serial(),
         register: 0,
         values: vec![0, 0],
+        checksum_valid: true,
     });
 
     let sf = async {
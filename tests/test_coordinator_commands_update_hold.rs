@@ -37,6 +37,7 @@ async fn happy_path() {
                 inverter: inverter.serial(),
                 register: 21,
                 values: vec![130, 0],
+                checksum_valid: true,
             })
         );
 
@@ -55,6 +56,7 @@ async fn happy_path() {
                 inverter: inverter.serial(),
                 register: 21,
                 values: vec![1, 0]
+                checksum_valid: true,
             })
         );
 
@@ -65,6 +67,7 @@ async fn happy_path() {
             inverter: inverter.serial(),
             register: 21,
             values: vec![2, 0],
+            checksum_valid: true,
         });
         channels
             .from_inverter
@@ -79,6 +82,7 @@ async fn happy_path() {
                 inverter: inverter.serial(),
                 register: 21,
                 values: vec![130, 0] // 128 + 2
+                checksum_valid: true,
             })
         );
 
@@ -89,6 +93,7 @@ async fn happy_path() {
             inverter: inverter.serial(),
             register: 21,
             values: vec![130, 0],
+            checksum_valid: true,
         });
         channels
             .from_inverter
@@ -123,7 +128,7 @@ async fn no_reply() {
         let result = subject.run().await;
         assert_eq!(
             result.unwrap_err().to_string(),
-            "wait_for_reply TranslatedData(TranslatedData { datalog: 2222222222, device_function: ReadHold, inverter: 5555555555, register: 21, values: [1, 0] }) - timeout"
+            "wait_for_reply TranslatedData(TranslatedData { datalog: 2222222222, device_function: ReadHold, inverter: 5555555555, register: 21, values: [1, 0], checksum_valid: true }) - timeout after 3 attempts"
         );
         Ok::<(), anyhow::Error>(())
     };
@@ -138,6 +143,7 @@ async fn no_reply() {
                 inverter: inverter.serial(),
                 register: 21,
                 values: vec![1, 0]
+                checksum_valid: true,
             })
         );
 